@@ -7,11 +7,12 @@
 // cargo bench --bench 01_enums_fsm
 
 use criterion::{Criterion, criterion_group, criterion_main};
-use std::fs::File;
 use std::hint::black_box;
-use std::io::{BufRead, BufReader};
 use std::path::Path;
 
+#[path = "bench_support.rs"]
+mod bench_support;
+
 #[derive(Debug, Clone, Copy)]
 enum FsmState {
     Whitespace,
@@ -80,16 +81,7 @@ impl Fsm {
 
 fn load_file_contents() -> String {
     let path = Path::new("./benches/book.txt");
-    let file = File::open(path).expect("Failed to open book.txt");
-    let reader = BufReader::new(file);
-
-    let mut contents = String::new();
-    for line in reader.lines() {
-        contents.push_str(&line.unwrap());
-        contents.push('\n');
-    }
-
-    contents
+    bench_support::load_or_generate_text(path, 0x01, 1_000_000, bench_support::DEFAULT_PROFILE)
 }
 
 fn benchmark_enum_fsm(c: &mut Criterion) {
@@ -10,11 +10,12 @@
 use criterion::{Criterion, criterion_group, criterion_main};
 use std::hint::black_box;
 
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::marker::PhantomData;
 use std::path::Path;
 
+#[path = "bench_support.rs"]
+mod bench_support;
+
 // --- Typestate markers (zero-sized types)
 struct Whitespace;
 struct InWord;
@@ -218,17 +219,7 @@ fn process_text(text: &str) -> TextStats {
 
 fn load_file_contents() -> String {
     let path = Path::new("./benches/book.txt");
-    let file = File::open(path).expect("Failed to open book.txt");
-    let reader = BufReader::new(file);
-
-    let mut contents = String::new();
-    for line in reader.lines() {
-        // NOTE: This preserves original newlines so line_count works
-        contents.push_str(&line.expect("I/O error while reading line"));
-        contents.push('\n');
-    }
-
-    contents
+    bench_support::load_or_generate_text(path, 0x03, 1_000_000, bench_support::DEFAULT_PROFILE)
 }
 
 // fn main() {
@@ -0,0 +1,77 @@
+// cargo add criterion
+
+// [[bench]]
+// name = "04_flyweight"
+// harness = false
+
+// cargo bench --bench 04_flyweight
+
+// Compares word-frequency counting on book.txt with and without the flyweight interner from
+// 22_flyweight: interning should win once the same words repeat often enough that the HashMap
+// lookup is cheaper than the allocations it avoids.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::path::Path;
+
+#[path = "bench_support.rs"]
+mod bench_support;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Symbol(u32);
+
+#[derive(Default)]
+struct Interner {
+    strings: Vec<String>,
+    lookup: HashMap<String, Symbol>,
+}
+impl Interner {
+    fn intern(&mut self, text: &str) -> Symbol {
+        if let Some(&symbol) = self.lookup.get(text) {
+            return symbol;
+        }
+        let symbol = Symbol(self.strings.len() as u32);
+        self.strings.push(text.to_string());
+        self.lookup.insert(text.to_string(), symbol);
+        symbol
+    }
+}
+
+fn frequencies_with_interning(text: &str) -> usize {
+    let mut interner = Interner::default();
+    let mut counts: HashMap<Symbol, usize> = HashMap::new();
+    for word in text.split_whitespace() {
+        let symbol = interner.intern(word);
+        *counts.entry(symbol).or_insert(0) += 1;
+    }
+    counts.len()
+}
+
+fn frequencies_without_interning(text: &str) -> usize {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for word in text.split_whitespace() {
+        *counts.entry(word.to_string()).or_insert(0) += 1;
+    }
+    counts.len()
+}
+
+fn load_book() -> String {
+    let path = Path::new("./benches/book.txt");
+    bench_support::load_or_generate_text(path, 0x04, 1_000_000, bench_support::DEFAULT_PROFILE)
+}
+
+fn benchmark_flyweight(c: &mut Criterion) {
+    let text = load_book();
+
+    c.bench_function("word_frequencies_with_interning", |b| {
+        b.iter(|| black_box(frequencies_with_interning(black_box(&text))));
+    });
+
+    c.bench_function("word_frequencies_without_interning", |b| {
+        b.iter(|| black_box(frequencies_without_interning(black_box(&text))));
+    });
+}
+
+criterion_group!(benches, benchmark_flyweight);
+criterion_main!(benches);
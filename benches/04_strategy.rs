@@ -0,0 +1,94 @@
+// cargo add criterion
+
+// [[bench]]
+// name = "04_strategy"
+// harness = false
+
+// cargo bench --bench 04_strategy
+
+// Companion to the 01-03_*_fsm.rs trait-object-vs-enum benches, but for 02_strategy.rs's
+// PaymentStrategy instead of an FSM: one million dispatches through `Box<dyn PaymentStrategy>`
+// against the same count through `PaymentMethod` (02_strategy_enum.rs), to put a number on the
+// dyn-dispatch cost the FSM bench comments already talk about.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+const DISPATCH_COUNT: usize = 1_000_000;
+
+trait PaymentStrategy {
+    fn pay(&self, amount: u64) -> u64;
+}
+
+struct CreditCard;
+impl PaymentStrategy for CreditCard {
+    fn pay(&self, amount: u64) -> u64 {
+        amount + amount / 50 // ~2% fee
+    }
+}
+
+struct Paypal;
+impl PaymentStrategy for Paypal {
+    fn pay(&self, amount: u64) -> u64 {
+        amount + amount * 29 / 1000 + 30 // ~2.9% + flat fee
+    }
+}
+
+struct Sepa;
+impl PaymentStrategy for Sepa {
+    fn pay(&self, amount: u64) -> u64 {
+        amount + 35 // flat fee
+    }
+}
+
+fn strategy_for(i: usize) -> Box<dyn PaymentStrategy> {
+    match i % 3 {
+        0 => Box::new(CreditCard),
+        1 => Box::new(Paypal),
+        _ => Box::new(Sepa),
+    }
+}
+
+fn dyn_dispatch_run(n: usize) -> u64 {
+    (0..n as u64).map(|i| strategy_for(i as usize).pay(i)).sum()
+}
+
+#[derive(Clone, Copy)]
+enum PaymentMethod {
+    CreditCard,
+    Paypal,
+    Sepa,
+}
+impl PaymentMethod {
+    fn for_index(i: usize) -> Self {
+        match i % 3 {
+            0 => PaymentMethod::CreditCard,
+            1 => PaymentMethod::Paypal,
+            _ => PaymentMethod::Sepa,
+        }
+    }
+    fn pay(self, amount: u64) -> u64 {
+        match self {
+            PaymentMethod::CreditCard => amount + amount / 50,
+            PaymentMethod::Paypal => amount + amount * 29 / 1000 + 30,
+            PaymentMethod::Sepa => amount + 35,
+        }
+    }
+}
+
+fn enum_dispatch_run(n: usize) -> u64 {
+    (0..n as u64).map(|i| PaymentMethod::for_index(i as usize).pay(i)).sum()
+}
+
+fn bench_strategy(c: &mut Criterion) {
+    assert_eq!(dyn_dispatch_run(DISPATCH_COUNT), enum_dispatch_run(DISPATCH_COUNT));
+
+    let mut group = c.benchmark_group("strategy_dispatch");
+    group.throughput(criterion::Throughput::Elements(DISPATCH_COUNT as u64));
+    group.bench_function("dyn", |b| b.iter(|| black_box(dyn_dispatch_run(DISPATCH_COUNT))));
+    group.bench_function("enum", |b| b.iter(|| black_box(enum_dispatch_run(DISPATCH_COUNT))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_strategy);
+criterion_main!(benches);
@@ -0,0 +1,99 @@
+// cargo add criterion
+
+// [[bench]]
+// name = "05_builder"
+// harness = false
+
+// cargo bench --bench 05_builder
+
+// Compares the consuming UserBuilder (01_builder.rs) against the non-consuming UserBuilderMut:
+// the consuming style moves and rebuilds `self` on every setter call, the &mut style mutates in
+// place and clones once in `build()`. Constructs 10k users each way to see whether that clone
+// actually costs anything noticeable against the moves it replaces.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+const USER_COUNT: usize = 10_000;
+
+#[derive(Debug)]
+struct User {
+    #[allow(dead_code)]
+    name: String,
+    #[allow(dead_code)]
+    age: u32,
+    roles: Vec<String>,
+}
+
+struct UserBuilder {
+    name: String,
+    age: u32,
+    roles: Vec<String>,
+}
+impl UserBuilder {
+    fn new(name: impl Into<String>, age: u32) -> Self {
+        Self { name: name.into(), age, roles: Vec::new() }
+    }
+    fn role(mut self, r: impl Into<String>) -> Self {
+        self.roles.push(r.into());
+        self
+    }
+    fn build(self) -> User {
+        User { name: self.name, age: self.age, roles: self.roles }
+    }
+}
+
+struct UserBuilderMut {
+    name: String,
+    age: u32,
+    roles: Vec<String>,
+}
+impl UserBuilderMut {
+    fn new(name: impl Into<String>, age: u32) -> Self {
+        Self { name: name.into(), age, roles: Vec::new() }
+    }
+    fn role(&mut self, r: impl Into<String>) -> &mut Self {
+        self.roles.push(r.into());
+        self
+    }
+    fn build(&self) -> User {
+        User { name: self.name.clone(), age: self.age, roles: self.roles.clone() }
+    }
+}
+
+fn build_with_consuming_builder(n: usize) -> usize {
+    let mut total_roles = 0;
+    for i in 0..n {
+        let user = UserBuilder::new("Alice", 30).role("admin").role("editor").build();
+        total_roles += user.roles.len();
+        black_box(i);
+    }
+    total_roles
+}
+
+fn build_with_mutable_builder(n: usize) -> usize {
+    let mut total_roles = 0;
+    for i in 0..n {
+        let mut builder = UserBuilderMut::new("Alice", 30);
+        builder.role("admin").role("editor");
+        let user = builder.build();
+        total_roles += user.roles.len();
+        black_box(i);
+    }
+    total_roles
+}
+
+fn benchmark_builder_styles(c: &mut Criterion) {
+    assert_eq!(build_with_consuming_builder(10), build_with_mutable_builder(10));
+
+    c.bench_function("consuming_builder_10k_users", |b| {
+        b.iter(|| black_box(build_with_consuming_builder(black_box(USER_COUNT))));
+    });
+
+    c.bench_function("mutable_builder_10k_users", |b| {
+        b.iter(|| black_box(build_with_mutable_builder(black_box(USER_COUNT))));
+    });
+}
+
+criterion_group!(benches, benchmark_builder_styles);
+criterion_main!(benches);
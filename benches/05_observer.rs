@@ -0,0 +1,101 @@
+// cargo add criterion
+
+// [[bench]]
+// name = "05_observer"
+// harness = false
+
+// cargo bench --bench 05_observer
+
+// Companion to 03_observer.rs's Topic: the old design cloned the message once per subscriber on
+// every publish (`msg.clone()` inside the delivery loop); the current design hands subscribers a
+// `&str` and only clones if a subscriber itself needs ownership. Publishes a 1KB message to 1k
+// subscribers 1k times each way to put a number on what that per-subscriber clone used to cost.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::cell::RefCell;
+use std::hint::black_box;
+use std::rc::Rc;
+
+const SUBSCRIBER_COUNT: usize = 1_000;
+const PUBLISH_COUNT: usize = 1_000;
+const MESSAGE_SIZE: usize = 1_024;
+
+// --- Old design: publish(&mut self, msg: String) clones msg once per subscriber. ---
+type OldSubscriber = Rc<RefCell<dyn FnMut(String)>>;
+
+struct OldTopic {
+    subs: Vec<OldSubscriber>,
+}
+impl OldTopic {
+    fn new() -> Self {
+        OldTopic { subs: vec![] }
+    }
+    fn subscribe(&mut self, callback: OldSubscriber) {
+        self.subs.push(callback);
+    }
+    fn publish(&mut self, msg: String) {
+        for sub in &self.subs {
+            sub.borrow_mut()(msg.clone());
+        }
+    }
+}
+
+fn clone_per_subscriber_run(msg: &str) -> u64 {
+    let mut topic = OldTopic::new();
+    let total = Rc::new(RefCell::new(0u64));
+    for _ in 0..SUBSCRIBER_COUNT {
+        let total = total.clone();
+        topic.subscribe(Rc::new(RefCell::new(move |m: String| *total.borrow_mut() += m.len() as u64)));
+    }
+    for _ in 0..PUBLISH_COUNT {
+        topic.publish(msg.to_string());
+    }
+    *total.borrow()
+}
+
+// --- Current design: publish(&self, msg: &str) delivers by reference. ---
+type NewSubscriber = Rc<RefCell<dyn FnMut(&str)>>;
+
+struct NewTopic {
+    subs: RefCell<Vec<NewSubscriber>>,
+}
+impl NewTopic {
+    fn new() -> Self {
+        NewTopic { subs: RefCell::new(vec![]) }
+    }
+    fn subscribe(&self, callback: NewSubscriber) {
+        self.subs.borrow_mut().push(callback);
+    }
+    fn publish(&self, msg: &str) {
+        for sub in self.subs.borrow().iter() {
+            sub.borrow_mut()(msg);
+        }
+    }
+}
+
+fn by_reference_run(msg: &str) -> u64 {
+    let topic = NewTopic::new();
+    let total = Rc::new(RefCell::new(0u64));
+    for _ in 0..SUBSCRIBER_COUNT {
+        let total = total.clone();
+        topic.subscribe(Rc::new(RefCell::new(move |m: &str| *total.borrow_mut() += m.len() as u64)));
+    }
+    for _ in 0..PUBLISH_COUNT {
+        topic.publish(msg);
+    }
+    *total.borrow()
+}
+
+fn bench_observer(c: &mut Criterion) {
+    let msg = "x".repeat(MESSAGE_SIZE);
+    assert_eq!(clone_per_subscriber_run(&msg), by_reference_run(&msg));
+
+    let mut group = c.benchmark_group("observer_publish");
+    group.throughput(criterion::Throughput::Elements((SUBSCRIBER_COUNT * PUBLISH_COUNT) as u64));
+    group.bench_function("clone_per_subscriber", |b| b.iter(|| black_box(clone_per_subscriber_run(&msg))));
+    group.bench_function("by_reference", |b| b.iter(|| black_box(by_reference_run(&msg))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_observer);
+criterion_main!(benches);
@@ -0,0 +1,300 @@
+// cargo add criterion
+
+// [[bench]]
+// name = "08_dispatch_matrix"
+// harness = false
+
+// cargo bench --bench 08_dispatch_matrix
+
+// The recurring theme across this crate is "trait objects vs enums vs generics" - this bench
+// puts all four families side by side on the same tiny workload shape so the numbers are
+// comparable: strategy, FSM, command bus, and observer, each with a dynamic-dispatch variant and
+// at least one static-dispatch variant. Every group asserts its variants agree before measuring,
+// so a refactor that silently changes behavior fails loudly instead of just shifting numbers.
+//
+// Ideally these would import one canonical implementation from a shared lib crate instead of
+// duplicating a minimal copy per group, the way 01-03_*_fsm.rs already do for the text-FSM
+// benches; until that extraction happens this file follows the same duplicate-a-small-copy
+// convention.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::hint::black_box;
+
+const WORKLOAD: usize = 1_000;
+
+// --- Strategy: Box<dyn> vs generic vs enum -------------------------------------------------
+
+trait PayStrategy {
+    fn pay(&self, amount: u64) -> u64;
+}
+struct FlatFee;
+impl PayStrategy for FlatFee {
+    fn pay(&self, amount: u64) -> u64 {
+        amount + 1
+    }
+}
+
+fn strategy_dyn(strategy: &dyn PayStrategy, n: usize) -> u64 {
+    (0..n as u64).map(|amount| strategy.pay(amount)).sum()
+}
+
+fn strategy_generic<S: PayStrategy>(strategy: &S, n: usize) -> u64 {
+    (0..n as u64).map(|amount| strategy.pay(amount)).sum()
+}
+
+#[derive(Clone, Copy)]
+enum StrategyEnum {
+    FlatFee,
+}
+impl StrategyEnum {
+    fn pay(self, amount: u64) -> u64 {
+        match self {
+            StrategyEnum::FlatFee => amount + 1,
+        }
+    }
+}
+fn strategy_enum(strategy: StrategyEnum, n: usize) -> u64 {
+    (0..n as u64).map(|amount| strategy.pay(amount)).sum()
+}
+
+fn bench_strategy(c: &mut Criterion) {
+    let dyn_strategy: &dyn PayStrategy = &FlatFee;
+    assert_eq!(strategy_dyn(dyn_strategy, WORKLOAD), strategy_generic(&FlatFee, WORKLOAD));
+    assert_eq!(strategy_generic(&FlatFee, WORKLOAD), strategy_enum(StrategyEnum::FlatFee, WORKLOAD));
+
+    let mut group = c.benchmark_group("strategy");
+    group.throughput(criterion::Throughput::Elements(WORKLOAD as u64));
+    group.bench_function("dyn", |b| b.iter(|| black_box(strategy_dyn(dyn_strategy, WORKLOAD))));
+    group.bench_function("generic", |b| b.iter(|| black_box(strategy_generic(&FlatFee, WORKLOAD))));
+    group.bench_function("enum", |b| b.iter(|| black_box(strategy_enum(StrategyEnum::FlatFee, WORKLOAD))));
+    group.finish();
+}
+
+// --- FSM: trait-object vs enum-match vs typestate vs transition table --------------------------
+// Same three-state pipeline (Validated -> Enriched -> Persisted) used by 04-07_state_machine_*.
+
+trait FsmState {
+    fn advance(self: Box<Self>) -> Box<dyn FsmState>;
+    fn step(&self) -> u8;
+}
+struct Validated;
+struct Enriched;
+struct Persisted;
+impl FsmState for Validated {
+    fn advance(self: Box<Self>) -> Box<dyn FsmState> {
+        Box::new(Enriched)
+    }
+    fn step(&self) -> u8 {
+        0
+    }
+}
+impl FsmState for Enriched {
+    fn advance(self: Box<Self>) -> Box<dyn FsmState> {
+        Box::new(Persisted)
+    }
+    fn step(&self) -> u8 {
+        1
+    }
+}
+impl FsmState for Persisted {
+    fn advance(self: Box<Self>) -> Box<dyn FsmState> {
+        self
+    }
+    fn step(&self) -> u8 {
+        2
+    }
+}
+fn fsm_trait_run(n: usize) -> u64 {
+    let mut state: Box<dyn FsmState> = Box::new(Validated);
+    let mut total = 0u64;
+    for _ in 0..n {
+        total += state.step() as u64;
+        state = state.advance();
+    }
+    total
+}
+
+#[derive(Clone, Copy)]
+enum FsmEnum {
+    Validated,
+    Enriched,
+    Persisted,
+}
+fn fsm_enum_run(n: usize) -> u64 {
+    let mut state = FsmEnum::Validated;
+    let mut total = 0u64;
+    for _ in 0..n {
+        total += match state {
+            FsmEnum::Validated => 0,
+            FsmEnum::Enriched => 1,
+            FsmEnum::Persisted => 2,
+        };
+        state = match state {
+            FsmEnum::Validated => FsmEnum::Enriched,
+            FsmEnum::Enriched => FsmEnum::Persisted,
+            FsmEnum::Persisted => FsmEnum::Persisted,
+        };
+    }
+    total
+}
+
+struct TypestateValidated;
+struct TypestateEnriched;
+struct TypestatePersisted;
+impl TypestateValidated {
+    fn advance(self) -> TypestateEnriched {
+        TypestateEnriched
+    }
+}
+impl TypestateEnriched {
+    fn advance(self) -> TypestatePersisted {
+        TypestatePersisted
+    }
+}
+fn fsm_typestate_run(n: usize) -> u64 {
+    // The typestate chain is fixed length at compile time; we replay it `n` times to match the
+    // other variants' workload shape (n state-advances total).
+    let mut total = 0u64;
+    for _ in 0..n / 2 {
+        let validated = TypestateValidated;
+        total += 0;
+        let enriched = validated.advance();
+        total += 1;
+        let _persisted = enriched.advance();
+        total += 2;
+    }
+    total
+}
+
+const FSM_TABLE: [(u8, usize); 3] = [(0, 1), (1, 2), (2, 2)];
+fn fsm_table_run(n: usize) -> u64 {
+    let mut state = 0usize;
+    let mut total = 0u64;
+    for _ in 0..n {
+        let (step, next) = FSM_TABLE[state];
+        total += step as u64;
+        state = next;
+    }
+    total
+}
+
+fn bench_fsm(c: &mut Criterion) {
+    assert_eq!(fsm_trait_run(WORKLOAD), fsm_enum_run(WORKLOAD));
+    assert_eq!(fsm_enum_run(WORKLOAD), fsm_table_run(WORKLOAD));
+    // Typestate can't "get stuck" in its final state the way the others do - once consumed, a
+    // typestate value is gone, so this variant replays the 3-step chain from scratch instead of
+    // idling at Persisted. It isn't bit-comparable to the others; this just checks it computes
+    // the per-replay total (0 + 1 + 2) consistently rather than silently rotting.
+    assert_eq!(fsm_typestate_run(WORKLOAD * 2), (WORKLOAD as u64) * 3);
+
+    let mut group = c.benchmark_group("fsm");
+    group.throughput(criterion::Throughput::Elements(WORKLOAD as u64));
+    group.bench_function("trait_object", |b| b.iter(|| black_box(fsm_trait_run(WORKLOAD))));
+    group.bench_function("enum_match", |b| b.iter(|| black_box(fsm_enum_run(WORKLOAD))));
+    group.bench_function("typestate", |b| b.iter(|| black_box(fsm_typestate_run(WORKLOAD))));
+    group.bench_function("transition_table", |b| b.iter(|| black_box(fsm_table_run(WORKLOAD))));
+    group.finish();
+}
+
+// --- Command bus: generic dispatch vs TypeId map -------------------------------------------
+
+trait Command {
+    type Output;
+}
+trait Handler<C: Command> {
+    fn handle(&self, cmd: C) -> C::Output;
+}
+struct Ping;
+impl Command for Ping {
+    type Output = u64;
+}
+struct PingHandler;
+impl Handler<Ping> for PingHandler {
+    fn handle(&self, _cmd: Ping) -> u64 {
+        1
+    }
+}
+fn bus_generic_dispatch<C: Command, H: Handler<C>>(cmd: C, handler: &H) -> C::Output {
+    handler.handle(cmd)
+}
+fn bus_generic_run(n: usize) -> u64 {
+    (0..n as u64).map(|_| bus_generic_dispatch(Ping, &PingHandler)).sum()
+}
+
+struct TypeIdBus {
+    handlers: HashMap<TypeId, Box<dyn Any>>,
+}
+impl TypeIdBus {
+    fn new() -> Self {
+        let mut handlers: HashMap<TypeId, Box<dyn Any>> = HashMap::new();
+        handlers.insert(TypeId::of::<Ping>(), Box::new(PingHandler));
+        Self { handlers }
+    }
+    fn dispatch(&self, _cmd: Ping) -> u64 {
+        let handler = self.handlers.get(&TypeId::of::<Ping>()).unwrap().downcast_ref::<PingHandler>().unwrap();
+        handler.handle(Ping)
+    }
+}
+fn bus_typeid_run(n: usize, bus: &TypeIdBus) -> u64 {
+    (0..n as u64).map(|_| bus.dispatch(Ping)).sum()
+}
+
+fn bench_command_bus(c: &mut Criterion) {
+    let bus = TypeIdBus::new();
+    assert_eq!(bus_generic_run(WORKLOAD), bus_typeid_run(WORKLOAD, &bus));
+
+    let mut group = c.benchmark_group("command_bus");
+    group.throughput(criterion::Throughput::Elements(WORKLOAD as u64));
+    group.bench_function("generic_dispatch", |b| b.iter(|| black_box(bus_generic_run(WORKLOAD))));
+    group.bench_function("typeid_map", |b| b.iter(|| black_box(bus_typeid_run(WORKLOAD, &bus))));
+    group.finish();
+}
+
+// --- Observer: closure vs trait object -------------------------------------------------------
+
+fn observer_closure_run(n: usize) -> u64 {
+    let mut total = 0u64;
+    let mut on_event = |payload: u64| total += payload;
+    for i in 0..n as u64 {
+        on_event(i);
+    }
+    total
+}
+
+trait Observer {
+    fn on_event(&mut self, payload: u64);
+    fn total(&self) -> u64;
+}
+struct SummingObserver {
+    total: u64,
+}
+impl Observer for SummingObserver {
+    fn on_event(&mut self, payload: u64) {
+        self.total += payload;
+    }
+    fn total(&self) -> u64 {
+        self.total
+    }
+}
+fn observer_dyn_run(n: usize) -> u64 {
+    let mut observer: Box<dyn Observer> = Box::new(SummingObserver { total: 0 });
+    for i in 0..n as u64 {
+        observer.on_event(i);
+    }
+    observer.total()
+}
+
+fn bench_observer(c: &mut Criterion) {
+    assert_eq!(observer_closure_run(WORKLOAD), observer_dyn_run(WORKLOAD));
+
+    let mut group = c.benchmark_group("observer");
+    group.throughput(criterion::Throughput::Elements(WORKLOAD as u64));
+    group.bench_function("closure", |b| b.iter(|| black_box(observer_closure_run(WORKLOAD))));
+    group.bench_function("trait_object", |b| b.iter(|| black_box(observer_dyn_run(WORKLOAD))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_strategy, bench_fsm, bench_command_bus, bench_observer);
+criterion_main!(benches);
@@ -0,0 +1,127 @@
+// Shared by every bench via `#[path = "bench_support.rs"] mod bench_support;` - Cargo compiles
+// each file under benches/ as its own independent crate root, so this `#[path]` include is how
+// they share code without a src/lib.rs.
+
+// Every bench used to hard-depend on `./benches/book.txt` existing and `.expect()`-panic
+// otherwise, which also made results non-reproducible across machines with a different file.
+// `load_or_generate_text` falls back to a deterministic synthetic corpus instead, and logs which
+// one it used.
+//
+// Not every bench that includes this file uses every function here - `#![allow(dead_code)]`
+// covers that instead of each bench needing its own per-item allow.
+#![allow(dead_code)]
+
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy)]
+pub struct TextProfile {
+    pub word_ratio: f64,
+    pub number_ratio: f64,
+    pub newline_every: usize,
+}
+
+pub const DEFAULT_PROFILE: TextProfile = TextProfile { word_ratio: 0.7, number_ratio: 0.1, newline_every: 80 };
+
+/// xorshift64 - small, seedable, and deterministic; not cryptographic, just reproducible.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn next_range(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+/// Deterministic pseudo-text: the same `seed` and `bytes` always produce byte-identical output.
+/// Each token is a word, a run of digits, or a lone space, drawn according to `profile`'s ratios,
+/// with a newline inserted roughly every `profile.newline_every` tokens.
+pub fn generate_text(seed: u64, bytes: usize, profile: TextProfile) -> String {
+    let mut rng = Rng::new(seed);
+    let mut out = String::with_capacity(bytes + 16);
+    let mut since_newline = 0usize;
+
+    let word_cut = (profile.word_ratio * 1000.0) as u64;
+    let number_cut = word_cut + (profile.number_ratio * 1000.0) as u64;
+
+    while out.len() < bytes {
+        let roll = rng.next_range(1000);
+        if roll < word_cut {
+            let len = 3 + rng.next_range(6) as usize;
+            for _ in 0..len {
+                out.push((b'a' + rng.next_range(26) as u8) as char);
+            }
+        } else if roll < number_cut {
+            let len = 1 + rng.next_range(4) as usize;
+            for _ in 0..len {
+                out.push((b'0' + rng.next_range(10) as u8) as char);
+            }
+        } else {
+            out.push(' ');
+            continue;
+        }
+
+        since_newline += 1;
+        if since_newline >= profile.newline_every {
+            out.push('\n');
+            since_newline = 0;
+        } else {
+            out.push(' ');
+        }
+    }
+
+    out.truncate(bytes);
+    out
+}
+
+/// Deterministic pseudo C source for the comment-scanner FSM (06_state_machine_enums_comments):
+/// a mix of `/* ... */` block comments (at roughly `comment_density` of tokens) and throwaway
+/// statements.
+pub fn generate_c_source(seed: u64, bytes: usize, comment_density: f64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut out = String::with_capacity(bytes + 16);
+    let density_cut = (comment_density * 1000.0) as u64;
+
+    while out.len() < bytes {
+        if rng.next_range(1000) < density_cut {
+            let len = 4 + rng.next_range(20) as usize;
+            out.push_str("/*");
+            for _ in 0..len {
+                out.push((b'a' + rng.next_range(26) as u8) as char);
+            }
+            out.push_str("*/");
+        } else {
+            out.push_str("int x");
+            out.push_str(&rng.next_range(100).to_string());
+            out.push_str(";\n");
+        }
+    }
+
+    out.truncate(bytes);
+    out
+}
+
+/// Uses `path` when it exists, generates `bytes` of deterministic text from `seed`/`profile`
+/// otherwise. Either way, logs to stderr which source was used so a CI run explains its own
+/// numbers.
+pub fn load_or_generate_text(path: &Path, seed: u64, bytes: usize, profile: TextProfile) -> String {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => {
+            eprintln!("bench_support: using {} ({} bytes)", path.display(), contents.len());
+            contents
+        }
+        Err(_) => {
+            eprintln!("bench_support: {} not found, generating {bytes} deterministic bytes (seed={seed})", path.display());
+            generate_text(seed, bytes, profile)
+        }
+    }
+}
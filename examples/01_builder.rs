@@ -3,43 +3,1498 @@
 // Builders play nicely with ownership, immutability, and compile-time guarantees.
 // Rust lacks default function arguments — Builder is often the cleanest way to configure complex structs.
 
-#[derive(Debug)]
+use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+pub const MIN_AGE: u32 = 0;
+pub const MAX_AGE: u32 = 150;
+
+/// Where a `User` was built from, set via `UserBuilder::source` and defaulting to `Manual`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BuildSource {
+    #[default]
+    Manual,
+    Import,
+    Api,
+}
+
+#[derive(Clone)]
 pub struct User {
     name: String,
     age: u32,
     email: Option<String>,
+    roles: Vec<String>,
+    tags: Vec<String>,
+    address: Option<Address>,
+    created_at: SystemTime,
+    source: BuildSource,
+}
+
+/// Hand-written to exclude `created_at`: two `User`s built from identical fields at different
+/// instants should still compare equal, the way they did before provenance tracking was added.
+impl PartialEq for User {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.age == other.age
+            && self.email == other.email
+            && self.roles == other.roles
+            && self.tags == other.tags
+            && self.address == other.address
+            && self.source == other.source
+    }
+}
+impl Eq for User {}
+
+/// Matches the `PartialEq` impl above: `created_at` is excluded so that equal `User`s (by the
+/// definition above) always hash equally, as `Hash`'s contract requires.
+impl std::hash::Hash for User {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.age.hash(state);
+        self.email.hash(state);
+        self.roles.hash(state);
+        self.tags.hash(state);
+        self.address.hash(state);
+        self.source.hash(state);
+    }
+}
+
+/// Hand-written so `created_at` prints as an RFC3339-ish string instead of the raw `SystemTime`
+/// debug representation (e.g. `SystemTime { tv_sec: ..., tv_nsec: ... }` on Unix).
+impl std::fmt::Debug for User {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("User")
+            .field("name", &self.name)
+            .field("age", &self.age)
+            .field("email", &self.email)
+            .field("roles", &self.roles)
+            .field("tags", &self.tags)
+            .field("address", &self.address)
+            .field("created_at", &format_rfc3339(self.created_at))
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl User {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn age(&self) -> u32 {
+        self.age
+    }
+    pub fn email(&self) -> Option<&str> {
+        self.email.as_deref()
+    }
+    pub fn roles(&self) -> &[String] {
+        &self.roles
+    }
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+    pub fn address(&self) -> Option<&Address> {
+        self.address.as_ref()
+    }
+    pub fn created_at(&self) -> SystemTime {
+        self.created_at
+    }
+    pub fn source(&self) -> BuildSource {
+        self.source
+    }
+
+    /// Clones `self` into a `UserBuilder` preloaded with its current fields, for "edit a copy"
+    /// workflows: `user.to_builder().email("new@x.com").build()`.
+    pub fn to_builder(&self) -> UserBuilder {
+        self.clone().into()
+    }
+}
+
+impl From<User> for UserBuilder {
+    fn from(user: User) -> Self {
+        let created_at = user.created_at;
+        Self {
+            name: user.name,
+            age: user.age,
+            email: user.email,
+            roles: user.roles,
+            tags: user.tags,
+            address: user.address,
+            source: user.source,
+            clock: Box::new(move || created_at),
+            env_errors: Vec::new(),
+            validators: Vec::new(),
+            steps: Vec::new(),
+        }
+    }
+}
+
+/// A nested struct built the same way as `User`, to show a builder composing another builder
+/// instead of every field living on one flat struct.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Address {
+    pub street: String,
+    pub city: String,
+    pub postal_code: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AddressBuilder {
+    street: String,
+    city: String,
+    postal_code: String,
+}
+impl AddressBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn street(mut self, street: impl Into<String>) -> Self {
+        self.street = street.into();
+        self
+    }
+    pub fn city(mut self, city: impl Into<String>) -> Self {
+        self.city = city.into();
+        self
+    }
+    pub fn postal_code(mut self, postal_code: impl Into<String>) -> Self {
+        self.postal_code = postal_code.into();
+        self
+    }
+    pub fn build(self) -> Address {
+        Address { street: self.street, city: self.city, postal_code: self.postal_code }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum UserBuildError {
+    #[error("name must not be empty")]
+    EmptyName,
+    #[error("age {got} is out of range {min}..={max}")]
+    AgeOutOfRange { min: u32, max: u32, got: u32 },
+    #[error("invalid email: {0}")]
+    InvalidEmail(String),
+    #[error("cross-field validation failed: {0:?}")]
+    ValidationFailed(Vec<String>),
+    #[error("invalid environment override(s): {0}")]
+    InvalidEnvOverride(#[source] EnvOverrideErrors),
+}
+
+/// A single `{prefix}_AGE` (etc.) environment variable that failed to parse, keeping the
+/// underlying `ParseIntError` around as `source()` instead of discarding it into a `String` -
+/// `apply_env` used to do the latter, which meant `?` on a `UserBuildError` lost the reason a
+/// caller might want to match on (e.g. to tell "empty string" from "not a number" apart).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{var} is not a valid age: {value:?}")]
+pub struct EnvOverrideError {
+    pub var: String,
+    pub value: String,
+    #[source]
+    pub source: std::num::ParseIntError,
+}
+
+/// Every `EnvOverrideError` collected by one `apply_env` call (or more, if it's called more than
+/// once). `Display` lists them all, same as `UserBuildError::ValidationFailed` does for
+/// cross-field failures; `source()` exposes the first one so `std::error::Error::source` chains
+/// down to the `ParseIntError` that caused it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvOverrideErrors(pub Vec<EnvOverrideError>);
+
+impl std::fmt::Display for EnvOverrideErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl std::error::Error for EnvOverrideErrors {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.first().map(|e| e as &(dyn std::error::Error + 'static))
+    }
+}
+
+impl UserBuildError {
+    /// Renders this error together with the step (if any) in `steps` that most recently set the
+    /// field it complains about, e.g. `invalid email: "@@" ("email" set to "\"@@\"" at step 3)`.
+    /// `steps` should come from `UserBuilder::steps` on the same builder that produced this error -
+    /// call it before `build()`, since `build()` consumes the builder even on failure.
+    pub fn context(&self, steps: &[BuildStep]) -> String {
+        let base = self.to_string();
+        let Some(field) = self.offending_field() else { return base };
+        match steps.iter().enumerate().rev().find(|(_, step)| step.field == field) {
+            Some((index, step)) => format!("{base} ({} set to {} at step {})", step.field, step.value, index + 1),
+            None => base,
+        }
+    }
+
+    fn offending_field(&self) -> Option<&'static str> {
+        match self {
+            UserBuildError::EmptyName => Some("name"),
+            UserBuildError::AgeOutOfRange { .. } => Some("age"),
+            UserBuildError::InvalidEmail(_) => Some("email"),
+            UserBuildError::ValidationFailed(_) | UserBuildError::InvalidEnvOverride(_) => None,
+        }
+    }
+}
+
+/// One recorded `UserBuilder` setter call: which field it touched and what the call's argument
+/// formatted to (via `Debug`), so a failed `build()` can be traced back to the step that set the
+/// bad value via `UserBuildError::context`. Recording happens behind a per-call
+/// `if cfg!(debug_assertions)` check, so it compiles away entirely in `--release` builds instead of
+/// costing a push per setter call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildStep {
+    pub field: &'static str,
+    pub value: String,
+}
+
+/// One step of a recorded edit history, replayable onto a fresh builder via
+/// `UserBuilder::apply` - the event-sourcing counterpart to `BuildStep`, which only ever looks
+/// backward at a builder that already ran.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserOp {
+    SetName(String),
+    SetAge(u32),
+    SetEmail(String),
+    ClearEmail,
+}
+
+type Validator = Box<dyn Fn(&User) -> Result<(), String>>;
+
+/// Shared starting point for `UserBuilder::from_defaults`: a default age, an email domain, and a
+/// name prefix used as the placeholder name until `.name(...)` is called. Plain setters on the
+/// resulting builder always override these - `from_defaults` only seeds the fields, it doesn't
+/// lock them.
+#[derive(Debug, Clone)]
+pub struct UserDefaults {
+    pub age: u32,
+    pub email_domain: String,
+    pub name_prefix: String,
+}
+
+impl Default for UserDefaults {
+    fn default() -> Self {
+        Self { age: 18, email_domain: "example.com".to_string(), name_prefix: "user".to_string() }
+    }
 }
 
 pub struct UserBuilder {
     name: String,
     age: u32,
     email: Option<String>,
+    roles: Vec<String>,
+    tags: Vec<String>,
+    address: Option<Address>,
+    source: BuildSource,
+    clock: Box<dyn Fn() -> SystemTime>,
+    env_errors: Vec<EnvOverrideError>,
+    validators: Vec<Validator>,
+    steps: Vec<BuildStep>,
 }
 impl UserBuilder {
-    pub fn new(name: String, age: u32) -> Self {
-        Self { name, age, email: None }
+    pub fn new(name: impl Into<String>, age: u32) -> Self {
+        Self {
+            name: name.into(),
+            age,
+            email: None,
+            roles: Vec::new(),
+            tags: Vec::new(),
+            address: None,
+            source: BuildSource::default(),
+            clock: Box::new(SystemTime::now),
+            env_errors: Vec::new(),
+            validators: Vec::new(),
+            steps: Vec::new(),
+        }
+    }
+
+    /// Records where this `User` came from (`Manual` by default). Surfaced on the built `User` via
+    /// `source()`.
+    pub fn source(mut self, source: BuildSource) -> Self {
+        self.source = source;
+        self.record_step("source", source);
+        self
+    }
+
+    /// Replaces the clock used for `created_at` at `build()` time, instead of the real
+    /// `SystemTime::now()` - tests should inject a fixed clock so assertions don't race the wall
+    /// clock.
+    pub fn clock(mut self, clock: impl Fn() -> SystemTime + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Records a setter call against `field` for `UserBuildError::context`, formatting `value` via
+    /// `Debug`. A no-op in `--release` builds: the `if cfg!(...)` condition is a compile-time
+    /// constant, so the optimizer drops the `format!`/`push` entirely when it's `false`.
+    fn record_step(&mut self, field: &'static str, value: impl std::fmt::Debug) {
+        if cfg!(debug_assertions) {
+            self.steps.push(BuildStep { field, value: format!("{value:?}") });
+        }
+    }
+
+    /// The setter calls recorded so far, oldest first. Capture this *before* calling `build()` or
+    /// `build_clone()` if you want to pass it to `UserBuildError::context` on failure - `build()`
+    /// consumes the builder even when it returns `Err`.
+    pub fn steps(&self) -> &[BuildStep] {
+        &self.steps
+    }
+
+    /// Seeds `name`, `age`, and `email` from a shared `UserDefaults`: `name` starts out as
+    /// `defaults.name_prefix`, `age` as `defaults.age`, and `email` as
+    /// `"{name_prefix}@{email_domain}"`. Every field is a plain starting value, not a constraint -
+    /// calling `.name(...)`, `.age(...)`, or `.email(...)` afterward overrides it like any other
+    /// `UserBuilder`.
+    pub fn from_defaults(defaults: &UserDefaults) -> Self {
+        let mut builder = Self::new(defaults.name_prefix.clone(), defaults.age);
+        builder.email = Some(format!("{}@{}", defaults.name_prefix, defaults.email_domain));
+        builder
     }
-    pub fn email(mut self, email: String) -> Self {
-        self.email = Some(email);
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self.record_step("name", self.name.clone());
         self
     }
-    pub fn build(self) -> User {
+    pub fn age(mut self, age: u32) -> Self {
+        self.age = age;
+        self.record_step("age", age);
+        self
+    }
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        self.email = Some(email.into());
+        self.record_step("email", self.email.clone());
+        self
+    }
+    /// Unsets `email`, the counterpart `UserOp::ClearEmail` needs since `email()` alone can only
+    /// ever set it to `Some(...)`.
+    pub fn clear_email(mut self) -> Self {
+        self.email = None;
+        self.record_step("email", self.email.clone());
+        self
+    }
+
+    /// Folds `ops` onto `self` in order, last-write-wins per field - e.g. `SetEmail` followed by
+    /// `ClearEmail` leaves `email` at `None`. Meant for replaying a recorded edit history (the
+    /// `steps()` trail, a CQRS event log, ...) onto a fresh builder instead of re-deriving it by
+    /// hand.
+    pub fn apply(self, ops: &[UserOp]) -> Self {
+        ops.iter().fold(self, |builder, op| match op.clone() {
+            UserOp::SetName(name) => builder.name(name),
+            UserOp::SetAge(age) => builder.age(age),
+            UserOp::SetEmail(email) => builder.email(email),
+            UserOp::ClearEmail => builder.clear_email(),
+        })
+    }
+
+    /// Like `age`, but validates the range immediately instead of waiting for `build()`. Rejects
+    /// negative values and values above `MAX_AGE` before the lossy `as u32` cast, so a value that
+    /// would silently wrap (e.g. a negative number) is reported instead of truncated.
+    pub fn try_age(mut self, n: i64) -> Result<Self, UserBuildError> {
+        if n < 0 || n > i64::from(MAX_AGE) {
+            let got = n.clamp(0, i64::from(u32::MAX)) as u32;
+            return Err(UserBuildError::AgeOutOfRange { min: MIN_AGE, max: MAX_AGE, got });
+        }
+        let age = u32::try_from(n).expect("checked above: 0 <= n <= MAX_AGE");
+        self.age = age;
+        self.record_step("age", age);
+        Ok(self)
+    }
+
+    /// Like `email`, but validates the address immediately instead of waiting for `build()`. A
+    /// value set via the infallible `email` setter still only fails at `build()`-time - this is an
+    /// alternative entry point, not a replacement.
+    pub fn try_email(mut self, s: &str) -> Result<Self, UserBuildError> {
+        validate_email(s)?;
+        self.email = Some(s.to_string());
+        self.record_step("email", self.email.clone());
+        Ok(self)
+    }
+
+    /// Overrides `name`, `age`, and `email` from `{prefix}_NAME`, `{prefix}_AGE`, and
+    /// `{prefix}_EMAIL` in `std::env`, for each variable that's present. A present-but-unparsable
+    /// value (e.g. a non-numeric `{prefix}_AGE`) is collected rather than silently ignored, and
+    /// surfaces from `build()` as `UserBuildError::InvalidEnvOverride` - it does not panic here and
+    /// does not stop the other overrides from being read.
+    pub fn apply_env(mut self, prefix: &str) -> Self {
+        if let Ok(name) = std::env::var(format!("{prefix}_NAME")) {
+            self.name = name;
+        }
+        if let Ok(age) = std::env::var(format!("{prefix}_AGE")) {
+            match age.parse::<u32>() {
+                Ok(age) => self.age = age,
+                Err(source) => self.env_errors.push(EnvOverrideError {
+                    var: format!("{prefix}_AGE"),
+                    value: age,
+                    source,
+                }),
+            }
+        }
+        if let Ok(email) = std::env::var(format!("{prefix}_EMAIL")) {
+            self.email = Some(email);
+        }
+        self
+    }
+
+    /// Appends a single role. Call repeatedly to build up the set one at a time; combine freely
+    /// with `roles()`, which replaces the whole set instead.
+    pub fn role(mut self, r: impl Into<String>) -> Self {
+        self.roles.push(r.into());
+        self.record_step("role", self.roles.last().cloned());
+        self
+    }
+    /// Replaces the whole role set, discarding any roles added via `role()` or a previous `roles()`
+    /// call.
+    pub fn roles(mut self, iter: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.roles = iter.into_iter().map(Into::into).collect();
+        self.record_step("roles", self.roles.clone());
+        self
+    }
+    pub fn tag(mut self, t: impl Into<String>) -> Self {
+        self.tags.push(t.into());
+        self.record_step("tag", self.tags.last().cloned());
+        self
+    }
+    pub fn tags(mut self, iter: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.tags = iter.into_iter().map(Into::into).collect();
+        self.record_step("tags", self.tags.clone());
+        self
+    }
+
+    /// Attaches an already-built `Address`. Use `address_with` instead to build one inline with a
+    /// nested `AddressBuilder`.
+    pub fn address(mut self, address: Address) -> Self {
+        self.address = Some(address);
+        self.record_step("address", self.address.clone());
+        self
+    }
+
+    /// Builds an `Address` inline via a nested `AddressBuilder`, e.g.
+    /// `.address_with(|b| b.city("Paris"))`. Starts from `AddressBuilder::default()`, so only the
+    /// fields the closure sets differ from empty strings.
+    pub fn address_with(mut self, f: impl FnOnce(AddressBuilder) -> AddressBuilder) -> Self {
+        self.address = Some(f(AddressBuilder::new()).build());
+        self.record_step("address", self.address.clone());
+        self
+    }
+
+    /// Registers a cross-field validation closure, run against the fully-built `User` after the
+    /// per-field checks pass. Validators run in registration order; `build()` runs all of them and
+    /// aggregates every failure instead of stopping at the first one, so a bad `User` shows every
+    /// problem at once.
+    pub fn validator(mut self, f: impl Fn(&User) -> Result<(), String> + 'static) -> Self {
+        self.validators.push(Box::new(f));
+        self
+    }
+
+    /// Validates `name`, `age`, and `email` (when present), then runs every registered
+    /// `validator()` against the candidate `User`, aggregating all of their failures. Use
+    /// `build_unchecked` to skip these checks.
+    pub fn build(self) -> Result<User, UserBuildError> {
+        if !self.env_errors.is_empty() {
+            return Err(UserBuildError::InvalidEnvOverride(EnvOverrideErrors(self.env_errors)));
+        }
+        validate(&self.name, self.age, &self.email)?;
+        let candidate = User {
+            name: self.name,
+            age: self.age,
+            email: self.email,
+            roles: dedup_preserve_order(self.roles),
+            tags: self.tags,
+            address: self.address,
+            created_at: (self.clock)(),
+            source: self.source,
+        };
+
+        let failures: Vec<String> = self.validators.iter().filter_map(|f| f(&candidate).err()).collect();
+        if !failures.is_empty() {
+            return Err(UserBuildError::ValidationFailed(failures));
+        }
+        Ok(candidate)
+    }
+
+    /// Builds without consuming `self`, so one configured builder can stamp out several `User`s
+    /// (e.g. changing only the email between calls) instead of being spent after the first
+    /// `build()`. Runs exactly the same validation and cross-field `validator()`s as `build`.
+    pub fn build_clone(&self) -> Result<User, UserBuildError> {
+        if !self.env_errors.is_empty() {
+            return Err(UserBuildError::InvalidEnvOverride(EnvOverrideErrors(self.env_errors.clone())));
+        }
+        validate(&self.name, self.age, &self.email)?;
+        let candidate = User {
+            name: self.name.clone(),
+            age: self.age,
+            email: self.email.clone(),
+            roles: dedup_preserve_order(self.roles.clone()),
+            tags: self.tags.clone(),
+            address: self.address.clone(),
+            created_at: (self.clock)(),
+            source: self.source,
+        };
+
+        let failures: Vec<String> = self.validators.iter().filter_map(|f| f(&candidate).err()).collect();
+        if !failures.is_empty() {
+            return Err(UserBuildError::ValidationFailed(failures));
+        }
+        Ok(candidate)
+    }
+
+    /// Clears `email`, `roles`, `tags`, and `address` while keeping `name`, `age`, and any
+    /// registered `validator()`s, for reconfiguring a builder between rounds of stamped-out users.
+    pub fn reset(mut self) -> UserBuilder {
+        self.email = None;
+        self.roles = Vec::new();
+        self.tags = Vec::new();
+        self.address = None;
+        self
+    }
+
+    /// Builds without validation, preserving the original unchecked behavior for callers that
+    /// already validate elsewhere (or want to construct deliberately malformed `User`s in tests).
+    pub fn build_unchecked(self) -> User {
         User {
             name: self.name,
             age: self.age,
             email: self.email,
+            roles: dedup_preserve_order(self.roles),
+            tags: self.tags,
+            address: self.address,
+            created_at: (self.clock)(),
+            source: self.source,
         }
     }
 }
 
-fn main() {
+/// Shared by `UserBuilder::build` and `UserBuilderMut::build` so the two styles enforce exactly
+/// the same rules.
+fn validate(name: &str, age: u32, email: &Option<String>) -> Result<(), UserBuildError> {
+    if name.is_empty() {
+        return Err(UserBuildError::EmptyName);
+    }
+    validate_age(age)?;
+    if let Some(email) = email {
+        validate_email(email)?;
+    }
+    Ok(())
+}
+
+/// Shared by `validate` (checking a field already set) and `UserBuilder::try_age` (checking a value
+/// before it's set), so both paths reject the same range with the same error.
+fn validate_age(age: u32) -> Result<(), UserBuildError> {
+    if !(MIN_AGE..=MAX_AGE).contains(&age) {
+        return Err(UserBuildError::AgeOutOfRange { min: MIN_AGE, max: MAX_AGE, got: age });
+    }
+    Ok(())
+}
+
+/// Shared by `validate` and `UserBuilder::try_email`, so a value rejected at the setter is rejected
+/// for the exact same reason `build()` would have rejected it.
+fn validate_email(email: &str) -> Result<(), UserBuildError> {
+    let Some((local, domain)) = email.split_once('@') else {
+        return Err(UserBuildError::InvalidEmail(email.to_string()));
+    };
+    if local.is_empty() || domain.is_empty() {
+        return Err(UserBuildError::InvalidEmail(email.to_string()));
+    }
+    Ok(())
+}
+
+/// Removes later duplicates while keeping each item's first position, e.g. `[a, b, a]` -> `[a, b]`.
+fn dedup_preserve_order(items: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    items.into_iter().filter(|item| seen.insert(item.clone())).collect()
+}
+
+/// Formats `t` as `YYYY-MM-DDTHH:MM:SSZ` (UTC, whole seconds) for `User`'s `Debug` impl, without
+/// pulling in a date/time crate - just this one conversion doesn't justify a new dependency.
+fn format_rfc3339(t: SystemTime) -> String {
+    let secs = match t.duration_since(UNIX_EPOCH) {
+        Ok(since_epoch) => since_epoch.as_secs() as i64,
+        Err(before_epoch) => -(before_epoch.duration().as_secs() as i64),
+    };
+    let days = secs.div_euclid(86_400);
+    let time_of_day = secs.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+    let (hour, minute, second) = (time_of_day / 3600, (time_of_day % 3600) / 60, time_of_day % 60);
+    format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z")
+}
+
+/// Howard Hinnant's `civil_from_days`: converts a day count since the Unix epoch into a
+/// `(year, month, day)` proleptic-Gregorian civil date. See
+/// <https://howardhinnant.github.io/date_algorithms.html>.
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = (z - era * 146_097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = if month <= 2 { y + 1 } else { y };
+    (year, month, day)
+}
+
+// --- Non-consuming variant: setters take &mut self, so fields can be set conditionally inside an
+// `if`/loop without juggling `let mut builder = builder.xyz(...)` reassignment. `build(&self)`
+// clones the accumulated fields instead of moving them, so the builder can be reused afterward. ---
+
+pub struct UserBuilderMut {
+    name: String,
+    age: u32,
+    email: Option<String>,
+    roles: Vec<String>,
+    tags: Vec<String>,
+}
+impl UserBuilderMut {
+    pub fn new(name: impl Into<String>, age: u32) -> Self {
+        Self { name: name.into(), age, email: None, roles: Vec::new(), tags: Vec::new() }
+    }
+    pub fn name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.name = name.into();
+        self
+    }
+    pub fn email(&mut self, email: impl Into<String>) -> &mut Self {
+        self.email = Some(email.into());
+        self
+    }
+    pub fn role(&mut self, r: impl Into<String>) -> &mut Self {
+        self.roles.push(r.into());
+        self
+    }
+    pub fn roles(&mut self, iter: impl IntoIterator<Item = impl Into<String>>) -> &mut Self {
+        self.roles = iter.into_iter().map(Into::into).collect();
+        self
+    }
+    pub fn tag(&mut self, t: impl Into<String>) -> &mut Self {
+        self.tags.push(t.into());
+        self
+    }
+    pub fn tags(&mut self, iter: impl IntoIterator<Item = impl Into<String>>) -> &mut Self {
+        self.tags = iter.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn build(&self) -> Result<User, UserBuildError> {
+        validate(&self.name, self.age, &self.email)?;
+        Ok(User {
+            name: self.name.clone(),
+            age: self.age,
+            email: self.email.clone(),
+            roles: dedup_preserve_order(self.roles.clone()),
+            tags: self.tags.clone(),
+            address: None,
+            created_at: SystemTime::now(),
+            source: BuildSource::default(),
+        })
+    }
+}
+
+// --- Command-bus integration: UserBuilder::build_command lets validated input flow straight onto
+// a command bus (see 09-12_command_bus.rs for the fuller, multi-command version of this pattern;
+// this is a single-command trim of it, since examples here don't share code across files). ---
+
+pub trait Command {
+    type Output;
+}
+
+pub trait Handler<C: Command> {
+    fn handle(&self, cmd: C) -> C::Output;
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CreateUser {
+    pub name: String,
+    pub email: Option<String>,
+}
+
+impl Command for CreateUser {
+    type Output = Result<String, String>;
+}
+
+pub struct CommandBus<C: Command> {
+    handler: Box<dyn Handler<C>>,
+}
+impl<C: Command> CommandBus<C> {
+    pub fn new(handler: impl Handler<C> + 'static) -> Self {
+        Self { handler: Box::new(handler) }
+    }
+    pub fn dispatch(&self, cmd: C) -> C::Output {
+        self.handler.handle(cmd)
+    }
+}
+
+impl UserBuilder {
+    /// Validates like `build`, then carries the validated `name` (and `email`, if set) onto a
+    /// `CreateUser` command instead of a `User`. A builder that fails validation returns its
+    /// `UserBuildError` here without ever constructing a command, so it can't reach a
+    /// `CommandBus::dispatch` call downstream.
+    pub fn build_command(self) -> Result<CreateUser, UserBuildError> {
+        let user = self.build()?;
+        Ok(CreateUser { name: user.name, email: user.email })
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Create a user without email
-    let user1 = UserBuilder::new("Alice".to_string(), 30).build();
-    println!("User without email: {user1:?}");
-    let (_name1, _age1, _email1) = (user1.name, user1.age, user1.email);
+    let user1 = UserBuilder::new("Alice", 30).build()?;
+    println!("User without email: {} is {} years old, email: {:?}", user1.name(), user1.age(), user1.email());
 
     // Create a user with email
-    let user2 = UserBuilder::new("Bob".to_string(), 25).email("bob@example.com".to_string()).build();
+    let user2 = UserBuilder::new("Bob", 25).email("bob@example.com").build()?;
     println!("User with email: {user2:?}");
     dbg!("User with email: {:?}", user2);
+
+    // Mixing role() and roles(), with a duplicate that build() will dedup
+    let user3 = UserBuilder::new("Carol", 40).role("admin").roles(["editor", "admin"]).tag("vip").build()?;
+    println!("User with roles {:?} and tags {:?}", user3.roles(), user3.tags());
+
+    // Invalid users still construct fine via build_unchecked
+    let bad = UserBuilder::new("", 999).build_unchecked();
+    println!("Unchecked (deliberately invalid) user: {bad:?}");
+
+    // UserBuilderMut: setting fields conditionally inside an `if`/loop is awkward with the
+    // consuming builder (each branch would need to return the same `UserBuilder` type), but
+    // natural with &mut self setters.
+    let grant_admin = true;
+    let mut builder = UserBuilderMut::new("Dave", 50);
+    if grant_admin {
+        builder.role("admin");
+    }
+    for tag in ["vip", "beta"] {
+        builder.tag(tag);
+    }
+    let user4 = builder.build()?;
+    println!("User built via UserBuilderMut: {user4:?}");
+
+    // "Edit a copy": take an existing User, change one field, keep the rest.
+    let original = UserBuilder::new("Eve", 28).email("eve@example.com").build()?;
+    let updated = original.to_builder().email("new@x.com").build()?;
+    println!("Original: {original:?}, updated: {updated:?}");
+
+    // Cross-field validation: both failures are reported, not just the first.
+    let bad_user_result = UserBuilder::new("Frank", 10)
+        .email("frank@example.com")
+        .validator(|u| if u.age < 16 && u.email.is_some() { Err("users under 16 must not have an email".to_string()) } else { Ok(()) })
+        .validator(|u| {
+            let local_part_matches_name = u.email.as_deref().and_then(|e| e.split_once('@')).map(|(local, _)| local.eq_ignore_ascii_case(&u.name)).unwrap_or(false);
+            if local_part_matches_name { Err("name and email local-part must not be identical".to_string()) } else { Ok(()) }
+        })
+        .build();
+    println!("Deliberately bad user: {bad_user_result:?}");
+
+    // One shared UserDefaults, three users that only override the name.
+    let defaults = UserDefaults { age: 21, email_domain: "example.org".to_string(), name_prefix: "guest".to_string() };
+    let alice = UserBuilder::from_defaults(&defaults).name("Alice").build()?;
+    let bob = UserBuilder::from_defaults(&defaults).name("Bob").build()?;
+    let carol = UserBuilder::from_defaults(&defaults).name("Carol").build()?;
+    println!("From shared defaults: {alice:?}, {bob:?}, {carol:?}");
+
+    // Nested builder, prebuilt struct style:
+    let prebuilt_address = AddressBuilder::new().street("1 Rue de Rivoli").city("Paris").postal_code("75001").build();
+    let grace = UserBuilder::new("Grace", 33).address(prebuilt_address).build()?;
+    println!("User with a prebuilt address: {grace:#?}");
+
+    // Nested builder, inline closure style:
+    let henry = UserBuilder::new("Henry", 44).address_with(|b| b.city("Paris")).build()?;
+    println!("User with an inline address: {henry:#?}");
+
+    // build_clone(): the same builder stamps out three users, only the email differing between
+    // calls. Each `.email(...)` call still consumes and returns a new builder (it's the consuming
+    // variant), but `build_clone()` itself never consumes, so it can be called as many times as
+    // needed along the way.
+    let builder = UserBuilder::new("Ivy", 29).role("member");
+    let builder = builder.email("ivy1@example.com");
+    let ivy1 = builder.build_clone()?;
+    let builder = builder.email("ivy2@example.com");
+    let ivy2 = builder.build_clone()?;
+    let builder = builder.email("ivy3@example.com");
+    let ivy3 = builder.build_clone()?;
+    println!("Three users stamped from one builder, only the email differs: {ivy1:?}, {ivy2:?}, {ivy3:?}");
+
+    // reset(): keep name/age, wipe everything else, and reuse the builder for a fresh user.
+    let reused = builder.reset().role("guest").build()?;
+    println!("Builder reset and reused: {reused:?}");
+
+    // builder -> command -> CommandBus::dispatch -> handler result.
+    struct CreateUserHandler;
+    impl Handler<CreateUser> for CreateUserHandler {
+        fn handle(&self, cmd: CreateUser) -> Result<String, String> {
+            Ok(format!("User created: {} ({:?})", cmd.name, cmd.email))
+        }
+    }
+    let bus = CommandBus::new(CreateUserHandler);
+    let command = UserBuilder::new("Kara", 27).email("kara@example.com").build_command()?;
+    let dispatch_result = bus.dispatch(command);
+    println!("Command bus result: {dispatch_result:?}");
+
+    // apply_env(): override fields from `APP_NAME`/`APP_AGE`/`APP_EMAIL` when set. None of these
+    // are set here, so apply_env is a no-op and the explicit `.name(...)` wins as usual.
+    let from_env = UserBuilder::new("Liam", 19).apply_env("APP").build()?;
+    println!("Builder with (absent) env overrides applied: {from_env:?}");
+
+    // `User` derives `Hash` and `Eq`, so it works directly as a `HashMap` key.
+    let mut visit_counts = std::collections::HashMap::new();
+    visit_counts.insert(user1.clone(), 1);
+    *visit_counts.entry(user1.clone()).or_insert(0) += 1;
+    println!("Visit count for {}: {}", user1.name(), visit_counts[&user1]);
+
+    // steps()/UserBuildError::context(): trace a failing build back to the setter call that caused
+    // it. Capture `steps()` before `build()`, which consumes the builder even on failure.
+    let broken = UserBuilder::new("Mia", 30).email("mia@example.com").email("not-an-email");
+    let steps = broken.steps().to_vec();
+    let err = broken.build().unwrap_err();
+    println!("Build failed: {err} -> {}", err.context(&steps));
+
+    // try_email()/try_age(): fail fast at the setter instead of waiting for build().
+    let try_email_err = UserBuilder::new("Noah", 22).try_email("not-an-email").err().unwrap();
+    println!("try_email rejected immediately: {try_email_err}");
+    let try_age_err = UserBuilder::new("Noah", 22).try_age(-5).err().unwrap();
+    println!("try_age rejected immediately: {try_age_err}");
+    let valid = UserBuilder::new("Noah", 22).try_email("noah@example.com")?.try_age(23)?.build()?;
+    println!("Built via fallible setters: {valid:?}");
+
+    // created_at/source: defaults to the wall clock and BuildSource::Manual; both overridable.
+    let imported = UserBuilder::new("Priya", 35).source(BuildSource::Import).clock(|| UNIX_EPOCH).build()?;
+    println!("Imported user with a fixed clock: {imported:?}");
+
+    // apply(): replay a recorded edit history onto a fresh builder instead of re-deriving it by
+    // hand - the last SetEmail is overwritten by ClearEmail, same as calling the setters directly.
+    let history = vec![
+        UserOp::SetName("Quinn".to_string()),
+        UserOp::SetAge(31),
+        UserOp::SetEmail("quinn@example.com".to_string()),
+        UserOp::ClearEmail,
+    ];
+    let replayed = UserBuilder::new("placeholder", 0).apply(&history).build()?;
+    println!("Replayed from a recorded op history: {replayed:?}");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::sync::Mutex;
+
+    // `std::env::set_var`/`remove_var` are process-global, so tests that touch them must not run
+    // concurrently with each other - this mutex (not the env vars themselves) is what's shared.
+    static ENV_GUARD: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn build_succeeds_on_the_happy_path() {
+        let user = UserBuilder::new("Alice".to_string(), 30).email("alice@example.com".to_string()).build().unwrap();
+        assert_eq!(user.name, "Alice");
+        assert_eq!(user.age, 30);
+        assert_eq!(user.email.as_deref(), Some("alice@example.com"));
+    }
+
+    #[test]
+    fn build_succeeds_without_an_email() {
+        let user = UserBuilder::new("Bob".to_string(), 25).build().unwrap();
+        assert_eq!(user.email, None);
+    }
+
+    #[test]
+    fn build_rejects_an_empty_name() {
+        let err = UserBuilder::new(String::new(), 30).build().unwrap_err();
+        assert_eq!(err, UserBuildError::EmptyName);
+    }
+
+    #[test]
+    fn build_rejects_an_out_of_range_age() {
+        let err = UserBuilder::new("Alice".to_string(), 999).build().unwrap_err();
+        assert_eq!(err, UserBuildError::AgeOutOfRange { min: MIN_AGE, max: MAX_AGE, got: 999 });
+    }
+
+    #[test]
+    fn build_rejects_an_email_without_an_at_sign() {
+        let err = UserBuilder::new("Alice".to_string(), 30).email("not-an-email".to_string()).build().unwrap_err();
+        assert_eq!(err, UserBuildError::InvalidEmail("not-an-email".to_string()));
+    }
+
+    #[test]
+    fn build_rejects_an_email_with_an_empty_domain() {
+        let err = UserBuilder::new("Alice".to_string(), 30).email("alice@".to_string()).build().unwrap_err();
+        assert_eq!(err, UserBuildError::InvalidEmail("alice@".to_string()));
+    }
+
+    #[test]
+    fn build_unchecked_skips_all_validation() {
+        let user = UserBuilder::new(String::new(), 999).email("not-an-email".to_string()).build_unchecked();
+        assert_eq!(user.name, "");
+        assert_eq!(user.age, 999);
+    }
+
+    #[test]
+    fn string_setters_accept_str_slices_owned_strings_and_cow() {
+        use std::borrow::Cow;
+
+        let via_str = UserBuilder::new("Alice", 30).email("alice@example.com").build().unwrap();
+        let via_string = UserBuilder::new("Alice".to_string(), 30).email("alice@example.com".to_string()).build().unwrap();
+        let via_cow = UserBuilder::new(Cow::Borrowed("Alice"), 30).email(Cow::Owned("alice@example.com".to_string())).build().unwrap();
+
+        assert_eq!(via_str.name, via_string.name);
+        assert_eq!(via_str.email, via_string.email);
+        assert_eq!(via_str.name, via_cow.name);
+        assert_eq!(via_str.email, via_cow.email);
+    }
+
+    #[test]
+    fn name_setter_overrides_the_name_given_to_new() {
+        let user = UserBuilder::new("Alice", 30).name("Alicia").build().unwrap();
+        assert_eq!(user.name, "Alicia");
+    }
+
+    #[test]
+    fn mixing_role_and_roles_preserves_first_seen_order_and_dedups() {
+        let user = UserBuilder::new("Alice", 30).role("admin").roles(["editor", "admin", "viewer"]).build().unwrap();
+        // roles() replaces the single role() call, so only the roles() call's own duplicate is deduped
+        assert_eq!(user.roles, vec!["editor", "admin", "viewer"]);
+    }
+
+    #[test]
+    fn repeated_role_calls_are_deduped_preserving_first_seen_order() {
+        let user = UserBuilder::new("Alice", 30).role("admin").role("editor").role("admin").build().unwrap();
+        assert_eq!(user.roles, vec!["admin", "editor"]);
+    }
+
+    #[test]
+    fn tags_are_not_deduplicated() {
+        let user = UserBuilder::new("Alice", 30).tag("vip").tags(["vip", "beta"]).build().unwrap();
+        assert_eq!(user.tags, vec!["vip", "beta"]);
+    }
+
+    #[test]
+    fn builder_mut_produces_the_same_user_as_the_consuming_builder() {
+        let consuming = UserBuilder::new("Alice", 30).email("alice@example.com").role("admin").tag("vip").build().unwrap();
+
+        let mut builder_mut = UserBuilderMut::new("Alice", 30);
+        builder_mut.email("alice@example.com").role("admin").tag("vip");
+        let via_mut = builder_mut.build().unwrap();
+
+        assert_eq!(consuming.name, via_mut.name);
+        assert_eq!(consuming.age, via_mut.age);
+        assert_eq!(consuming.email, via_mut.email);
+        assert_eq!(consuming.roles, via_mut.roles);
+        assert_eq!(consuming.tags, via_mut.tags);
+    }
+
+    #[test]
+    fn builder_mut_can_be_built_multiple_times_and_reused_after_build() {
+        let mut builder = UserBuilderMut::new("Alice", 30);
+        builder.role("admin");
+        let first = builder.build().unwrap();
+        builder.role("editor");
+        let second = builder.build().unwrap();
+
+        assert_eq!(first.roles, vec!["admin"]);
+        assert_eq!(second.roles, vec!["admin", "editor"]);
+    }
+
+    #[test]
+    fn builder_mut_propagates_the_same_validation_errors() {
+        let builder = UserBuilderMut::new(String::new(), 30);
+        let err = builder.build().unwrap_err();
+        assert_eq!(err, UserBuildError::EmptyName);
+    }
+
+    #[test]
+    fn to_builder_round_trips_a_user_unchanged() {
+        let user = UserBuilder::new("Alice", 30).email("alice@example.com").role("admin").tag("vip").build().unwrap();
+        let round_tripped = user.to_builder().build().unwrap();
+        assert_eq!(user, round_tripped);
+    }
+
+    #[test]
+    fn to_builder_preserves_a_missing_email_as_none() {
+        let user = UserBuilder::new("Bob", 25).build().unwrap();
+        let round_tripped = user.to_builder().build().unwrap();
+        assert_eq!(round_tripped.email, None);
+    }
+
+    #[test]
+    fn to_builder_lets_one_field_change_while_the_rest_stay_the_same() {
+        let user = UserBuilder::new("Alice", 30).email("alice@example.com").role("admin").build().unwrap();
+        let updated = user.to_builder().email("new@x.com").build().unwrap();
+
+        assert_eq!(updated.name, user.name);
+        assert_eq!(updated.age, user.age);
+        assert_eq!(updated.roles, user.roles);
+        assert_ne!(updated.email, user.email);
+        assert_eq!(updated.email.as_deref(), Some("new@x.com"));
+    }
+
+    #[test]
+    fn a_passing_validator_does_not_affect_a_successful_build() {
+        let user = UserBuilder::new("Alice", 30).validator(|_| Ok(())).build().unwrap();
+        assert_eq!(user.name, "Alice");
+    }
+
+    #[test]
+    fn a_single_failing_validator_is_reported() {
+        let err = UserBuilder::new("Alice", 10)
+            .email("alice@example.com")
+            .validator(|u| if u.age < 16 && u.email.is_some() { Err("too young for an email".to_string()) } else { Ok(()) })
+            .build()
+            .unwrap_err();
+        assert_eq!(err, UserBuildError::ValidationFailed(vec!["too young for an email".to_string()]));
+    }
+
+    #[test]
+    fn multiple_failing_validators_are_all_reported_in_registration_order() {
+        let err = UserBuilder::new("Alice", 10)
+            .email("alice@example.com")
+            .validator(|_| Err("first".to_string()))
+            .validator(|_| Err("second".to_string()))
+            .build()
+            .unwrap_err();
+        assert_eq!(err, UserBuildError::ValidationFailed(vec!["first".to_string(), "second".to_string()]));
+    }
+
+    #[test]
+    fn validators_only_run_after_per_field_checks_pass() {
+        let err = UserBuilder::new(String::new(), 30).validator(|_| Err("should never run".to_string())).build().unwrap_err();
+        assert_eq!(err, UserBuildError::EmptyName);
+    }
+
+    #[test]
+    fn build_unchecked_ignores_validators() {
+        let user = UserBuilder::new(String::new(), 30).validator(|_| Err("ignored".to_string())).build_unchecked();
+        assert_eq!(user.name, "");
+    }
+
+    #[test]
+    fn from_defaults_seeds_name_age_and_email() {
+        let defaults = UserDefaults { age: 21, email_domain: "example.org".to_string(), name_prefix: "guest".to_string() };
+        let user = UserBuilder::from_defaults(&defaults).build().unwrap();
+        assert_eq!(user.name, "guest");
+        assert_eq!(user.age, 21);
+        assert_eq!(user.email.as_deref(), Some("guest@example.org"));
+    }
+
+    #[test]
+    fn explicit_setters_always_win_over_defaults() {
+        let defaults = UserDefaults { age: 21, email_domain: "example.org".to_string(), name_prefix: "guest".to_string() };
+        let user = UserBuilder::from_defaults(&defaults).name("Alice").age(40).email("alice@elsewhere.com").build().unwrap();
+        assert_eq!(user.name, "Alice");
+        assert_eq!(user.age, 40);
+        assert_eq!(user.email.as_deref(), Some("alice@elsewhere.com"));
+    }
+
+    #[test]
+    fn three_users_from_one_shared_defaults_only_differ_by_name() {
+        let defaults = UserDefaults { age: 21, email_domain: "example.org".to_string(), name_prefix: "guest".to_string() };
+        let alice = UserBuilder::from_defaults(&defaults).name("Alice").build().unwrap();
+        let bob = UserBuilder::from_defaults(&defaults).name("Bob").build().unwrap();
+
+        assert_eq!(alice.age, bob.age);
+        assert_eq!(alice.email, bob.email);
+        assert_ne!(alice.name, bob.name);
+    }
+
+    #[test]
+    fn user_defaults_default_impl_is_usable_on_its_own() {
+        let user = UserBuilder::from_defaults(&UserDefaults::default()).name("Alice").build().unwrap();
+        assert_eq!(user.age, 18);
+        assert_eq!(user.email.as_deref(), Some("user@example.com"));
+    }
+
+    #[test]
+    fn address_accepts_a_prebuilt_struct() {
+        let address = AddressBuilder::new().street("1 Rue de Rivoli").city("Paris").postal_code("75001").build();
+        let user = UserBuilder::new("Grace", 33).address(address.clone()).build().unwrap();
+        assert_eq!(user.address, Some(address));
+    }
+
+    #[test]
+    fn address_with_builds_inline_from_a_nested_builder() {
+        let user = UserBuilder::new("Henry", 44).address_with(|b| b.city("Paris").postal_code("75001")).build().unwrap();
+        let address = user.address.unwrap();
+        assert_eq!(address.city, "Paris");
+        assert_eq!(address.postal_code, "75001");
+        assert_eq!(address.street, "");
+    }
+
+    #[test]
+    fn address_defaults_to_none_when_never_set() {
+        let user = UserBuilder::new("Ivan", 50).build().unwrap();
+        assert_eq!(user.address, None);
+    }
+
+    #[test]
+    fn build_clone_does_not_consume_the_builder() {
+        let builder = UserBuilder::new("Ivy", 29).role("member");
+        let first = builder.build_clone().unwrap();
+        let second = builder.build_clone().unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn build_clone_stamps_out_users_that_only_differ_by_email() {
+        let builder = UserBuilder::new("Ivy", 29).role("member");
+        let builder = builder.email("ivy1@example.com");
+        let ivy1 = builder.build_clone().unwrap();
+        let builder = builder.email("ivy2@example.com");
+        let ivy2 = builder.build_clone().unwrap();
+
+        assert_eq!(ivy1.name, ivy2.name);
+        assert_eq!(ivy1.roles, ivy2.roles);
+        assert_ne!(ivy1.email, ivy2.email);
+    }
+
+    #[test]
+    fn build_clone_reports_the_same_validation_errors_as_build() {
+        let builder = UserBuilder::new(String::new(), 30);
+        assert_eq!(builder.build_clone().unwrap_err(), UserBuildError::EmptyName);
+    }
+
+    #[test]
+    fn reset_clears_email_and_collections_but_keeps_name_and_age() {
+        let user = UserBuilder::new("Jack", 31)
+            .email("jack@example.com")
+            .role("admin")
+            .tag("vip")
+            .address_with(|b| b.city("Paris"))
+            .reset()
+            .build()
+            .unwrap();
+
+        assert_eq!(user.name, "Jack");
+        assert_eq!(user.age, 31);
+        assert_eq!(user.email, None);
+        assert_eq!(user.roles, Vec::<String>::new());
+        assert_eq!(user.tags, Vec::<String>::new());
+        assert_eq!(user.address, None);
+    }
+
+    struct CountingHandler {
+        calls: Cell<u32>,
+    }
+    impl Handler<CreateUser> for CountingHandler {
+        fn handle(&self, cmd: CreateUser) -> Result<String, String> {
+            self.calls.set(self.calls.get() + 1);
+            Ok(cmd.name)
+        }
+    }
+
+    #[test]
+    fn build_command_carries_the_validated_name_and_email() {
+        let command = UserBuilder::new("Kara", 27).email("kara@example.com").build_command().unwrap();
+        assert_eq!(command, CreateUser { name: "Kara".to_string(), email: Some("kara@example.com".to_string()) });
+    }
+
+    #[test]
+    fn build_command_reaches_the_bus_and_the_handler_runs() {
+        let handler = CountingHandler { calls: Cell::new(0) };
+        let command = UserBuilder::new("Kara", 27).build_command().unwrap();
+        let bus = CommandBus::new(handler);
+        let result = bus.dispatch(command);
+        assert_eq!(result, Ok("Kara".to_string()));
+    }
+
+    #[test]
+    fn a_builder_that_fails_validation_never_reaches_the_bus() {
+        let handler = CountingHandler { calls: Cell::new(0) };
+        let result = UserBuilder::new(String::new(), 30).build_command();
+
+        assert_eq!(result.unwrap_err(), UserBuildError::EmptyName);
+        assert_eq!(handler.calls.get(), 0, "the handler must never be invoked when build_command fails");
+    }
+
+    /// Removes `{prefix}_NAME`/`{prefix}_AGE`/`{prefix}_EMAIL` when dropped, so a test that panics
+    /// partway through setting them up never leaks env state into the next test.
+    struct EnvVars {
+        prefix: &'static str,
+    }
+    impl EnvVars {
+        fn set(prefix: &'static str, name: Option<&str>, age: Option<&str>, email: Option<&str>) -> Self {
+            for (suffix, value) in [("NAME", name), ("AGE", age), ("EMAIL", email)] {
+                match value {
+                    Some(value) => unsafe { std::env::set_var(format!("{prefix}_{suffix}"), value) },
+                    None => unsafe { std::env::remove_var(format!("{prefix}_{suffix}")) },
+                }
+            }
+            Self { prefix }
+        }
+    }
+    impl Drop for EnvVars {
+        fn drop(&mut self) {
+            for suffix in ["NAME", "AGE", "EMAIL"] {
+                unsafe { std::env::remove_var(format!("{}_{suffix}", self.prefix)) };
+            }
+        }
+    }
+
+    #[test]
+    fn apply_env_overrides_present_and_parseable_fields() {
+        let _guard = ENV_GUARD.lock().unwrap();
+        let _vars = EnvVars::set("BUILDER_TEST_A", Some("Mona"), Some("52"), Some("mona@example.com"));
+
+        let user = UserBuilder::new("placeholder", 1).apply_env("BUILDER_TEST_A").build().unwrap();
+        assert_eq!(user.name, "Mona");
+        assert_eq!(user.age, 52);
+        assert_eq!(user.email.as_deref(), Some("mona@example.com"));
+    }
+
+    #[test]
+    fn apply_env_leaves_fields_alone_when_the_variable_is_unset() {
+        let _guard = ENV_GUARD.lock().unwrap();
+        let _vars = EnvVars::set("BUILDER_TEST_B", None, None, None);
+
+        let user = UserBuilder::new("Nina", 33).apply_env("BUILDER_TEST_B").build().unwrap();
+        assert_eq!(user.name, "Nina");
+        assert_eq!(user.age, 33);
+        assert_eq!(user.email, None);
+    }
+
+    #[test]
+    fn apply_env_surfaces_an_unparsable_age_as_a_build_error_instead_of_ignoring_it() {
+        let _guard = ENV_GUARD.lock().unwrap();
+        let _vars = EnvVars::set("BUILDER_TEST_C", None, Some("not-a-number"), None);
+
+        let err = UserBuilder::new("Oscar", 44).apply_env("BUILDER_TEST_C").build().unwrap_err();
+        let source_parse_error = "not-a-number".parse::<u32>().unwrap_err();
+        assert_eq!(
+            err,
+            UserBuildError::InvalidEnvOverride(EnvOverrideErrors(vec![EnvOverrideError {
+                var: "BUILDER_TEST_C_AGE".to_string(),
+                value: "not-a-number".to_string(),
+                source: source_parse_error,
+            }]))
+        );
+        let env_override_errors = std::error::Error::source(&err).unwrap();
+        let env_override_error = std::error::Error::source(env_override_errors).unwrap();
+        let parse_error_source = std::error::Error::source(env_override_error).unwrap();
+        assert_eq!(parse_error_source.to_string(), "not-a-number".parse::<u32>().unwrap_err().to_string());
+    }
+
+    #[test]
+    fn accessors_expose_the_same_data_as_the_private_fields() {
+        let user = UserBuilder::new("Alice", 30).email("alice@example.com").role("admin").tag("vip").build().unwrap();
+        assert_eq!(user.name(), "Alice");
+        assert_eq!(user.age(), 30);
+        assert_eq!(user.email(), Some("alice@example.com"));
+        assert_eq!(user.roles(), ["admin"]);
+        assert_eq!(user.tags(), ["vip"]);
+        assert_eq!(user.address(), None);
+    }
+
+    #[test]
+    fn two_users_built_from_the_same_fields_are_equal() {
+        let a = UserBuilder::new("Alice", 30).email("alice@example.com").build().unwrap();
+        let b = UserBuilder::new("Alice", 30).email("alice@example.com").build().unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn users_that_differ_only_by_email_are_not_equal() {
+        let a = UserBuilder::new("Alice", 30).email("alice@example.com").build().unwrap();
+        let b = UserBuilder::new("Alice", 30).build().unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn user_can_be_used_as_a_hashmap_key() {
+        use std::collections::HashMap;
+
+        let alice = UserBuilder::new("Alice", 30).email("alice@example.com").build().unwrap();
+        let bob = UserBuilder::new("Bob", 25).build().unwrap();
+
+        let mut scores = HashMap::new();
+        scores.insert(alice.clone(), 10);
+        scores.insert(bob.clone(), 20);
+
+        assert_eq!(scores[&alice], 10);
+        assert_eq!(scores[&bob], 20);
+        // A value equal to `alice` looks up the same entry, proving Hash and Eq agree.
+        assert_eq!(scores[&UserBuilder::new("Alice", 30).email("alice@example.com").build().unwrap()], 10);
+    }
+
+    #[test]
+    fn steps_records_one_entry_per_setter_call_in_order() {
+        let builder = UserBuilder::new("Alice", 30).age(31).email("alice@example.com").role("admin");
+        let fields: Vec<&str> = builder.steps().iter().map(|s| s.field).collect();
+        assert_eq!(fields, ["age", "email", "role"]);
+    }
+
+    #[test]
+    fn steps_records_every_override_not_just_the_last() {
+        let builder = UserBuilder::new("Alice", 30).name("Alicia").name("Al");
+        let names: Vec<&str> = builder.steps().iter().filter(|s| s.field == "name").map(|s| s.value.as_str()).collect();
+        assert_eq!(names, [r#""Alicia""#, r#""Al""#]);
+    }
+
+    #[test]
+    fn context_falls_back_to_plain_display_when_no_step_set_the_field() {
+        let err = UserBuildError::EmptyName;
+        assert_eq!(err.context(&[]), err.to_string());
+    }
+
+    #[test]
+    fn context_points_at_the_step_that_set_the_offending_field() {
+        let builder = UserBuilder::new("Alice", 30).email("alice@example.com").email("not-an-email");
+        let steps = builder.steps().to_vec();
+        let err = builder.build().unwrap_err();
+        let context = err.context(&steps);
+        assert!(context.contains("email set to"));
+        assert!(context.contains("at step 2"));
+    }
+
+    #[test]
+    fn context_uses_the_most_recent_step_for_the_offending_field() {
+        // Two `age` steps land the builder out of range; context should point at the last one,
+        // since that's the value `build()` actually rejected.
+        let builder = UserBuilder::new("Alice", 30).age(10).age(200);
+        let steps = builder.steps().to_vec();
+        let err = builder.build().unwrap_err();
+        assert!(err.context(&steps).contains("at step 2"));
+    }
+
+    #[test]
+    fn display_text_for_each_user_build_error_variant_is_stable() {
+        assert_eq!(UserBuildError::EmptyName.to_string(), "name must not be empty");
+        assert_eq!(
+            UserBuildError::AgeOutOfRange { min: 0, max: 150, got: 200 }.to_string(),
+            "age 200 is out of range 0..=150"
+        );
+        assert_eq!(
+            UserBuildError::InvalidEmail("not-an-email".to_string()).to_string(),
+            "invalid email: not-an-email"
+        );
+        assert_eq!(
+            UserBuildError::ValidationFailed(vec!["too young".to_string()]).to_string(),
+            r#"cross-field validation failed: ["too young"]"#
+        );
+        let env_error = EnvOverrideError {
+            var: "APP_AGE".to_string(),
+            value: "nope".to_string(),
+            source: "nope".parse::<u32>().unwrap_err(),
+        };
+        assert_eq!(
+            UserBuildError::InvalidEnvOverride(EnvOverrideErrors(vec![env_error])).to_string(),
+            r#"invalid environment override(s): [EnvOverrideError { var: "APP_AGE", value: "nope", source: ParseIntError { kind: InvalidDigit } }]"#
+        );
+    }
+
+    #[test]
+    fn try_email_rejects_a_malformed_address_without_building() {
+        let err = UserBuilder::new("Alice", 30).try_email("not-an-email").err().unwrap();
+        assert_eq!(err, UserBuildError::InvalidEmail("not-an-email".to_string()));
+    }
+
+    #[test]
+    fn try_email_accepts_a_well_formed_address() {
+        let user = UserBuilder::new("Alice", 30).try_email("alice@example.com").unwrap().build().unwrap();
+        assert_eq!(user.email(), Some("alice@example.com"));
+    }
+
+    #[test]
+    fn try_age_rejects_a_negative_value_before_the_lossy_cast() {
+        let err = UserBuilder::new("Alice", 30).try_age(-5).err().unwrap();
+        assert_eq!(err, UserBuildError::AgeOutOfRange { min: MIN_AGE, max: MAX_AGE, got: 0 });
+    }
+
+    #[test]
+    fn try_age_rejects_a_value_above_the_maximum() {
+        let err = UserBuilder::new("Alice", 30).try_age(200).err().unwrap();
+        assert_eq!(err, UserBuildError::AgeOutOfRange { min: MIN_AGE, max: MAX_AGE, got: 200 });
+    }
+
+    #[test]
+    fn try_age_accepts_an_in_range_value() {
+        let user = UserBuilder::new("Alice", 30).try_age(40).unwrap().build().unwrap();
+        assert_eq!(user.age(), 40);
+    }
+
+    #[test]
+    fn apply_folds_ops_in_order_with_last_write_wins_per_field() {
+        let ops = vec![
+            UserOp::SetName("Quinn".to_string()),
+            UserOp::SetName("Rory".to_string()),
+            UserOp::SetAge(20),
+            UserOp::SetAge(21),
+        ];
+        let user = UserBuilder::new("placeholder", 0).apply(&ops).build().unwrap();
+        assert_eq!(user.name(), "Rory");
+        assert_eq!(user.age(), 21);
+    }
+
+    #[test]
+    fn apply_clear_email_after_set_email_yields_none() {
+        let ops = vec![UserOp::SetEmail("quinn@example.com".to_string()), UserOp::ClearEmail];
+        let user = UserBuilder::new("Quinn", 30).apply(&ops).build().unwrap();
+        assert_eq!(user.email(), None);
+    }
+
+    #[test]
+    fn apply_on_an_empty_op_list_leaves_the_builder_unchanged() {
+        let user = UserBuilder::new("Quinn", 30).apply(&[]).build().unwrap();
+        assert_eq!(user.name(), "Quinn");
+        assert_eq!(user.age(), 30);
+    }
+
+    #[test]
+    fn apply_set_email_after_clear_email_restores_it() {
+        let ops = vec![UserOp::ClearEmail, UserOp::SetEmail("quinn@example.com".to_string())];
+        let user = UserBuilder::new("Quinn", 30).email("old@example.com").apply(&ops).build().unwrap();
+        assert_eq!(user.email(), Some("quinn@example.com"));
+    }
+
+    #[test]
+    fn a_value_rejected_by_try_email_would_instead_pass_through_email_and_fail_at_build() {
+        let bad = "not-an-email";
+        assert!(UserBuilder::new("Alice", 30).try_email(bad).is_err());
+
+        let err = UserBuilder::new("Alice", 30).email(bad).build().unwrap_err();
+        assert_eq!(err, UserBuildError::InvalidEmail(bad.to_string()));
+    }
+
+    #[test]
+    fn the_infallible_email_setter_never_fails_by_itself() {
+        // `email()` returns `Self`, not a `Result` - a bad value is only ever reported by `build()`,
+        // unlike `try_email()` which reports it immediately.
+        let builder = UserBuilder::new("Alice", 30).email("not-an-email");
+        assert_eq!(builder.build().unwrap_err(), UserBuildError::InvalidEmail("not-an-email".to_string()));
+    }
+
+    #[test]
+    fn default_build_source_is_manual() {
+        let user = UserBuilder::new("Alice", 30).build().unwrap();
+        assert_eq!(user.source(), BuildSource::Manual);
+    }
+
+    #[test]
+    fn source_setter_overrides_the_default() {
+        let user = UserBuilder::new("Alice", 30).source(BuildSource::Api).build().unwrap();
+        assert_eq!(user.source(), BuildSource::Api);
+    }
+
+    #[test]
+    fn injected_clock_is_used_instead_of_the_wall_clock() {
+        let fixed = UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        let user = UserBuilder::new("Alice", 30).clock(move || fixed).build().unwrap();
+        assert_eq!(user.created_at(), fixed);
+    }
+
+    #[test]
+    fn default_clock_produces_a_time_at_or_after_the_time_before_build() {
+        let before = SystemTime::now();
+        let user = UserBuilder::new("Alice", 30).build().unwrap();
+        assert!(user.created_at() >= before);
+    }
+
+    #[test]
+    fn debug_output_renders_created_at_as_rfc3339_not_the_raw_system_time() {
+        let user = UserBuilder::new("Alice", 30).clock(|| UNIX_EPOCH).build().unwrap();
+        let debug = format!("{user:?}");
+        assert!(debug.contains("1970-01-01T00:00:00Z"), "debug output was: {debug}");
+        assert!(!debug.contains("tv_sec"), "debug output leaked raw SystemTime fields: {debug}");
+    }
+
+    #[test]
+    fn to_builder_preserves_the_original_created_at_unless_overridden() {
+        let user = UserBuilder::new("Alice", 30).clock(|| UNIX_EPOCH).build().unwrap();
+        let round_tripped = user.to_builder().build().unwrap();
+        assert_eq!(round_tripped.created_at(), UNIX_EPOCH);
+    }
 }
@@ -0,0 +1,119 @@
+// cargo add rand --optional
+// cargo run --example 01_builder_fixtures --features rand
+
+// 01_builder.rs's tests hand-write two or three `UserBuilder` configurations and check each one by
+// hand. That only exercises a handful of points in the input space. `UserBuilder::randomized`
+// below generates a random-but-plausible user from a shared `Rng`, so a simple loop can throw
+// hundreds of them at `build()` and confirm the validation rules hold everywhere, not just at the
+// cases someone thought to write down - the same idea `tests/property.rs` applies with `proptest`,
+// done here with the `rand` dependency this crate already pulls in for 08_tokio_event_dispatcher.
+
+use rand::Rng;
+
+const NAMES: &[&str] = &["Alice", "Bob", "Cara", "Dev", "Elan", "Farah", "Gus", "Hana"];
+const MIN_AGE: u32 = 18;
+const MAX_AGE: u32 = 99;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct User {
+    pub name: String,
+    pub age: u32,
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UserBuilder {
+    name: String,
+    age: u32,
+    email: Option<String>,
+}
+
+impl UserBuilder {
+    pub fn new(name: impl Into<String>, age: u32) -> Self {
+        Self { name: name.into(), age, email: None }
+    }
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        self.email = Some(email.into());
+        self
+    }
+
+    /// Picks a name from `NAMES`, an age in `MIN_AGE..=MAX_AGE`, and (half the time) an email
+    /// derived from the chosen name - every field this produces is guaranteed to pass `build()`,
+    /// so a loop generating thousands of these is a cheap fuzz test for the validation rules.
+    pub fn randomized(rng: &mut impl Rng) -> Self {
+        let name = NAMES[rng.random_range(0..NAMES.len())];
+        let age = rng.random_range(MIN_AGE..=MAX_AGE);
+        let mut builder = Self::new(name, age);
+        if rng.random_bool(0.5) {
+            builder = builder.email(format!("{}@example.com", name.to_lowercase()));
+        }
+        builder
+    }
+
+    pub fn build(self) -> Result<User, String> {
+        if self.name.is_empty() {
+            return Err("name must not be empty".to_string());
+        }
+        if !(MIN_AGE..=MAX_AGE).contains(&self.age) {
+            return Err(format!("age {} is out of range {MIN_AGE}..={MAX_AGE}", self.age));
+        }
+        if let Some(email) = &self.email {
+            let Some((local, domain)) = email.split_once('@') else {
+                return Err(format!("invalid email: {email}"));
+            };
+            if local.is_empty() || domain.is_empty() {
+                return Err(format!("invalid email: {email}"));
+            }
+        }
+        Ok(User { name: self.name, age: self.age, email: self.email })
+    }
+}
+
+fn main() {
+    let mut rng = rand::rng();
+
+    for _ in 0..5 {
+        let user = UserBuilder::randomized(&mut rng).build().unwrap();
+        println!("Randomized user: {user:?}");
+    }
+
+    let trials = 1000;
+    let failures: Vec<String> =
+        (0..trials).filter_map(|_| UserBuilder::randomized(&mut rng).build().err()).collect();
+    println!("{}/{trials} randomized builders passed validation", trials - failures.len());
+    assert!(failures.is_empty(), "randomized builder produced invalid users: {failures:?}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn randomized_users_always_pass_build_validation() {
+        let mut rng = rand::rng();
+        for _ in 0..1000 {
+            let result = UserBuilder::randomized(&mut rng).build();
+            assert!(result.is_ok(), "unexpected validation failure: {result:?}");
+        }
+    }
+
+    #[test]
+    fn randomized_age_is_always_within_the_configured_range() {
+        let mut rng = rand::rng();
+        for _ in 0..1000 {
+            let user = UserBuilder::randomized(&mut rng).build().unwrap();
+            assert!((MIN_AGE..=MAX_AGE).contains(&user.age));
+        }
+    }
+
+    #[test]
+    fn randomized_email_when_present_matches_the_chosen_name() {
+        let mut rng = rand::rng();
+        for _ in 0..1000 {
+            let user = UserBuilder::randomized(&mut rng).build().unwrap();
+            if let Some(email) = &user.email {
+                assert_eq!(*email, format!("{}@example.com", user.name.to_lowercase()));
+            }
+        }
+    }
+}
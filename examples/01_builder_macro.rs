@@ -0,0 +1,148 @@
+// cargo run --example 01_builder_macro
+
+// 01_builder.rs hand-writes every builder from scratch, which is fine once but gets repetitive
+// past the second or third struct. `builder!` below is a small `macro_rules!` that emits the
+// struct, a builder with fluent setters, and a `build()` returning `Result<_, String>` that lists
+// every missing required field - the same shape as 01_builder.rs's `UserBuilder`, without pulling
+// in the `derive_builder` crate. It's applied to two unrelated structs (`User`, `ServerConfig`) to
+// show it isn't tied to one shape.
+
+macro_rules! builder {
+    (
+        $(#[$struct_attr:meta])*
+        $struct_vis:vis struct $name:ident / $builder_vis:vis struct $builder_name:ident {
+            required: { $($req_field:ident : $req_ty:ty),* $(,)? },
+            optional: { $($opt_field:ident : $opt_ty:ty),* $(,)? } $(,)?
+        }
+    ) => {
+        // Optional fields are stored (and exposed) as `Option<$opt_ty>`, same as `email` on
+        // 01_builder.rs's `User` - "optional" means "may be None", not "has a default value".
+        $(#[$struct_attr])*
+        $struct_vis struct $name {
+            $(pub $req_field: $req_ty,)*
+            $(pub $opt_field: Option<$opt_ty>,)*
+        }
+
+        #[derive(Default)]
+        $builder_vis struct $builder_name {
+            $($req_field: Option<$req_ty>,)*
+            $($opt_field: Option<$opt_ty>,)*
+        }
+
+        impl $builder_name {
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            $(
+                pub fn $req_field(mut self, value: impl Into<$req_ty>) -> Self {
+                    self.$req_field = Some(value.into());
+                    self
+                }
+            )*
+
+            $(
+                pub fn $opt_field(mut self, value: impl Into<$opt_ty>) -> Self {
+                    self.$opt_field = Some(value.into());
+                    self
+                }
+            )*
+
+            /// Returns every missing `#[required]` field at once instead of stopping at the
+            /// first one, matching 01_builder.rs's `UserBuilder::validator` aggregation style.
+            pub fn build(self) -> Result<$name, String> {
+                let mut missing: Vec<&'static str> = Vec::new();
+                $(
+                    if self.$req_field.is_none() {
+                        missing.push(stringify!($req_field));
+                    }
+                )*
+                if !missing.is_empty() {
+                    return Err(format!("missing required field(s): {}", missing.join(", ")));
+                }
+                Ok($name {
+                    $($req_field: self.$req_field.unwrap(),)*
+                    $($opt_field: self.$opt_field,)*
+                })
+            }
+        }
+    };
+}
+
+builder! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct User / pub struct UserBuilder {
+        required: { name: String, age: u32 },
+        optional: { email: String },
+    }
+}
+
+builder! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ServerConfig / pub struct ServerConfigBuilder {
+        required: { host: String, port: u16 },
+        optional: { max_connections: u32, use_tls: bool },
+    }
+}
+
+fn main() {
+    let user = UserBuilder::new().name("Alice").age(30u32).email("alice@example.com").build().unwrap();
+    println!("Macro-generated user: {user:?}");
+
+    let missing_both = UserBuilder::new().build().unwrap_err();
+    println!("Missing both required fields: {missing_both}");
+
+    let config = ServerConfigBuilder::new().host("localhost").port(8080u16).use_tls(true).build().unwrap();
+    println!("Macro-generated server config: {config:?}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_builder_succeeds_with_all_fields_set() {
+        let user = UserBuilder::new().name("Alice").age(30u32).email("alice@example.com").build().unwrap();
+        assert_eq!(user, User { name: "Alice".to_string(), age: 30, email: Some("alice@example.com".to_string()) });
+    }
+
+    #[test]
+    fn user_builder_optional_field_defaults_when_unset() {
+        let user = UserBuilder::new().name("Bob").age(25u32).build().unwrap();
+        assert_eq!(user.email, None);
+    }
+
+    #[test]
+    fn user_builder_reports_every_missing_required_field() {
+        let err = UserBuilder::new().build().unwrap_err();
+        assert_eq!(err, "missing required field(s): name, age");
+    }
+
+    #[test]
+    fn user_builder_reports_a_single_missing_required_field() {
+        let err = UserBuilder::new().name("Alice").build().unwrap_err();
+        assert_eq!(err, "missing required field(s): age");
+    }
+
+    #[test]
+    fn server_config_builder_succeeds_with_all_fields_set() {
+        let config = ServerConfigBuilder::new().host("localhost").port(8080u16).max_connections(100u32).use_tls(true).build().unwrap();
+        assert_eq!(
+            config,
+            ServerConfig { host: "localhost".to_string(), port: 8080, max_connections: Some(100), use_tls: Some(true) }
+        );
+    }
+
+    #[test]
+    fn server_config_builder_optional_fields_default_to_none_when_unset() {
+        let config = ServerConfigBuilder::new().host("localhost").port(8080u16).build().unwrap();
+        assert_eq!(config.max_connections, None);
+        assert_eq!(config.use_tls, None);
+    }
+
+    #[test]
+    fn server_config_builder_reports_missing_required_fields() {
+        let err = ServerConfigBuilder::new().build().unwrap_err();
+        assert_eq!(err, "missing required field(s): host, port");
+    }
+}
@@ -0,0 +1,117 @@
+// cargo run --example 01_builder_typestate
+// cargo test --doc --example 01_builder_typestate
+
+// Typestate applied to a builder: 06_state_machine_typed.rs uses marker states
+// (Uninitialized/Connected/Closed) so calling `send` before `connect` is a compile error, not a
+// runtime one. Here the same trick makes a missing required field a compile error: `build()` only
+// exists on `UserBuilder<HasName, HasAge>`, so forgetting `.name()` or `.age()` fails to compile
+// instead of producing a half-built `User` (compare with 01_builder.rs's `UserBuildError`, which
+// catches the same mistakes but only at `build()`-time).
+
+use std::marker::PhantomData;
+
+pub struct MissingName;
+pub struct HasName;
+pub struct MissingAge;
+pub struct HasAge;
+
+#[derive(Debug)]
+pub struct User {
+    name: String,
+    age: u32,
+    email: Option<String>,
+}
+
+/// Field presence is tracked in `NameState`/`AgeState`, not at runtime. Builder methods are
+/// implemented per typestate below, so `name()`/`age()` are each only callable once and `build()`
+/// only once both have been supplied.
+///
+/// This crate has no `src/lib.rs`, so this doc-test cannot run under `cargo test --doc` (there is
+/// no library target to host it) - it documents the rejected call shape the same way the
+/// commented-out `closed_client.send("oops")` line does in 06_state_machine_typed.rs:
+///
+/// ```compile_fail
+/// let user = UserBuilder::new().age(30).build(); // missing .name(...): does not compile
+/// ```
+pub struct UserBuilder<NameState, AgeState> {
+    name: Option<String>,
+    age: Option<u32>,
+    email: Option<String>,
+    _name_state: PhantomData<NameState>,
+    _age_state: PhantomData<AgeState>,
+}
+
+impl UserBuilder<MissingName, MissingAge> {
+    pub fn new() -> Self {
+        Self { name: None, age: None, email: None, _name_state: PhantomData, _age_state: PhantomData }
+    }
+}
+
+impl Default for UserBuilder<MissingName, MissingAge> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<AgeState> UserBuilder<MissingName, AgeState> {
+    pub fn name(self, name: impl Into<String>) -> UserBuilder<HasName, AgeState> {
+        UserBuilder { name: Some(name.into()), age: self.age, email: self.email, _name_state: PhantomData, _age_state: PhantomData }
+    }
+}
+
+impl<NameState> UserBuilder<NameState, MissingAge> {
+    pub fn age(self, age: u32) -> UserBuilder<NameState, HasAge> {
+        UserBuilder { name: self.name, age: Some(age), email: self.email, _name_state: PhantomData, _age_state: PhantomData }
+    }
+}
+
+impl<NameState, AgeState> UserBuilder<NameState, AgeState> {
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        self.email = Some(email.into());
+        self
+    }
+}
+
+impl UserBuilder<HasName, HasAge> {
+    pub fn build(self) -> User {
+        User { name: self.name.expect("HasName guarantees this"), age: self.age.expect("HasAge guarantees this"), email: self.email }
+    }
+}
+
+fn main() {
+    let user = UserBuilder::new().name("Alice").age(30).email("alice@example.com").build();
+    println!("{user:?}");
+    let (_name, _age, _email) = (user.name, user.age, user.email);
+
+    // Order doesn't matter, as long as both are set before build():
+    let user2 = UserBuilder::new().age(25).name("Bob").build();
+    println!("{user2:?}");
+
+    // let incomplete = UserBuilder::new().age(30).build(); // Does NOT compile: no `build` on UserBuilder<MissingName, HasAge>
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_succeeds_once_both_required_fields_are_set() {
+        let user = UserBuilder::new().name("Alice").age(30).build();
+        assert_eq!(user.name, "Alice");
+        assert_eq!(user.age, 30);
+        assert_eq!(user.email, None);
+    }
+
+    #[test]
+    fn required_fields_can_be_set_in_either_order() {
+        let user = UserBuilder::new().age(25).name("Bob").build();
+        assert_eq!(user.name, "Bob");
+        assert_eq!(user.age, 25);
+    }
+
+    #[test]
+    fn email_is_optional_and_settable_in_any_typestate() {
+        let user = UserBuilder::new().email("bob@example.com").name("Bob").age(25).build();
+        assert_eq!(user.email.as_deref(), Some("bob@example.com"));
+    }
+}
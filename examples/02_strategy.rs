@@ -4,22 +4,743 @@
 // Works great where you don’t know behavior at compile-time.
 // Rust’s trait objects (Box<dyn Trait>) provide dynamic dispatch and make this elegant.
 
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// The currencies this example knows about. `Money` refuses to mix them, so a strategy can't
+/// silently add euro cents to dollar cents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Currency {
+    Eur,
+    Usd,
+}
+
+/// A payment amount as whole cents plus a currency, instead of a bare `f64` - the anti-pattern
+/// this example used to copy. `f64` can't represent "2 cents" exactly and happily lets you add
+/// dollars to euros; `i64` cents and a tagged `Currency` can't.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Money {
+    pub cents: i64,
+    pub currency: Currency,
+}
+
+impl Money {
+    pub const fn new(cents: i64, currency: Currency) -> Self {
+        Self { cents, currency }
+    }
+
+    pub const fn eur(cents: i64) -> Self {
+        Self::new(cents, Currency::Eur)
+    }
+
+    /// Adds two amounts, failing instead of silently mixing currencies - or silently wrapping on
+    /// overflow, which a plain `+` would do for an amount near `i64::MAX`.
+    pub fn checked_add(self, other: Money) -> Result<Money, PaymentError> {
+        if self.currency != other.currency {
+            return Err(PaymentError::CurrencyMismatch { lhs: self.currency, rhs: other.currency });
+        }
+        let cents = self.cents.checked_add(other.cents).ok_or(PaymentError::AmountOverflow)?;
+        Ok(Money::new(cents, self.currency))
+    }
+}
+
+impl std::fmt::Display for Money {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self.currency {
+            Currency::Eur => '€',
+            Currency::Usd => '$',
+        };
+        write!(f, "{symbol}{}.{:02}", self.cents / 100, (self.cents % 100).abs())
+    }
+}
+
+/// Rounds a fractional number of cents to the nearest whole cent, ties breaking away from zero
+/// (half-up) rather than to the nearest even cent (banker's rounding). Half-up is simpler to
+/// reason about for a one-off fee calculation and matches what most card-network fee schedules
+/// actually publish; banker's rounding earns its complexity when you're summing many roundings
+/// and want the bias to cancel out, which isn't the case here.
+fn round_half_up_cents(cents: f64) -> i64 {
+    cents.round() as i64
+}
+
+/// Proof that a `PaymentStrategy::pay` call actually happened: which strategy handled it, the
+/// amount and fee charged, and an opaque transaction id. Gives tests something to assert against
+/// instead of only a `println!`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Receipt {
+    pub strategy: &'static str,
+    pub amount: Money,
+    pub fee: Money,
+    pub transaction_id: String,
+    /// How many times `pay` had to be called before this receipt came back `Ok`. 1 for every
+    /// strategy except `RetryingStrategy`, which overwrites it with the attempt that finally
+    /// succeeded.
+    pub attempts: u32,
+    /// The amount the payer was actually charged in before `ConvertingStrategy` converted it to
+    /// `amount`'s currency. `None` for every strategy that didn't go through a conversion.
+    pub original_amount: Option<Money>,
+}
+
+impl Receipt {
+    /// The amount plus its fee. Checked, even though every strategy below charges its fee in the
+    /// same currency as the amount it was given - the point is that a future strategy that didn't
+    /// uphold that invariant would fail loudly here instead of producing a meaningless total.
+    pub fn total(&self) -> Result<Money, PaymentError> {
+        self.amount.checked_add(self.fee)
+    }
+}
+
+/// What comes back from `PaymentStrategy::refund`. Its own type rather than reusing `Receipt`,
+/// since a refund has no fee of its own to report - only how much actually went back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Refund {
+    pub strategy: &'static str,
+    pub amount: Money,
+    pub transaction_id: String,
+}
+
+/// Rejects refunding a receipt with a different strategy than `expected` - shared by every
+/// `PaymentStrategy::refund` override so a PayPal receipt can't be "refunded" through CreditCard.
+fn ensure_receipt_matches(receipt: &Receipt, expected: &'static str) -> Result<(), PaymentError> {
+    if receipt.strategy != expected {
+        return Err(PaymentError::StrategyMismatch { expected, actual: receipt.strategy });
+    }
+    Ok(())
+}
+
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum PaymentError {
+    #[error("amount must be positive, got {0}")]
+    AmountNotPositive(Money),
+    #[error("payment declined: {0}")]
+    Declined(String),
+    #[error("every strategy declined: {0:?}")]
+    AllFailed(Vec<PaymentError>),
+    #[error("split payment fractions summed to {sum}, expected 1.0")]
+    FractionsDoNotSumToOne { sum: f64 },
+    #[error("cannot combine {lhs:?} with {rhs:?}")]
+    CurrencyMismatch { lhs: Currency, rhs: Currency },
+    #[error("no route for {0}")]
+    NoRouteFor(Money),
+    #[error("{0} does not support refunds")]
+    UnsupportedRefund(&'static str),
+    #[error("cannot refund a {actual} receipt using {expected}")]
+    StrategyMismatch { expected: &'static str, actual: &'static str },
+    #[error("no rate from {0:?} to {1:?}")]
+    NoRate(Currency, Currency),
+    #[error("idempotency key {key:?} was already used for {original}, not {attempted}")]
+    KeyReuseMismatch { key: IdempotencyKey, original: Money, attempted: Money },
+    #[error("amount overflowed i64 cents")]
+    AmountOverflow,
+}
+
+impl PaymentError {
+    /// Whether `RetryingStrategy` should try again after this error. Only `Declined` represents a
+    /// transient, strategy-specific rejection worth retrying; `AmountNotPositive` is a caller bug
+    /// that won't fix itself, and the rest are already the end state of some other aggregation.
+    fn is_retryable(&self) -> bool {
+        matches!(self, PaymentError::Declined(_))
+    }
+}
+
+static NEXT_TRANSACTION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Hands out a process-wide unique id, prefixed by strategy (`CC-1`, `PP-2`, ...) - good enough to
+/// tell receipts apart without pulling in a UUID dependency for one example.
+fn next_transaction_id(prefix: &str) -> String {
+    format!("{prefix}-{}", NEXT_TRANSACTION_ID.fetch_add(1, Ordering::Relaxed))
+}
+
 trait PaymentStrategy {
-    fn pay(&self, amount: f64);
+    /// The fee this strategy would charge to move `amount`, computed without actually moving any
+    /// money - lets a caller preview a fee, or let a composite strategy total several fees up,
+    /// without generating a transaction id for a payment that hasn't happened.
+    fn fee(&self, amount: Money) -> Money;
+    fn pay(&self, amount: Money) -> Result<Receipt, PaymentError>;
+
+    /// Reverses a payment this same strategy issued. Defaults to unsupported so strategies that
+    /// can't meaningfully refund (a flat bank transfer, a composite over several legs, ...) don't
+    /// have to invent an answer; `CreditCard` and `Paypal` below override it.
+    fn refund(&self, receipt: &Receipt) -> Result<Refund, PaymentError> {
+        Err(PaymentError::UnsupportedRefund(receipt.strategy))
+    }
 }
 
-struct CreditCard;
+/// Lets a `Box<dyn PaymentStrategy>` itself be wrapped by a generic decorator like
+/// `RetryingStrategy<S>` - without it, `PaymentContextBuilder` below could only stack one
+/// concrete, compile-time-known strategy type instead of layering decorators on top of whatever
+/// boxed strategy it started with.
+impl PaymentStrategy for Box<dyn PaymentStrategy> {
+    fn fee(&self, amount: Money) -> Money {
+        (**self).fee(amount)
+    }
+
+    fn pay(&self, amount: Money) -> Result<Receipt, PaymentError> {
+        (**self).pay(amount)
+    }
+
+    fn refund(&self, receipt: &Receipt) -> Result<Refund, PaymentError> {
+        (**self).refund(receipt)
+    }
+}
+
+/// Charges 1.9% plus a flat 25c, or 3.5% plus the same flat 25c for Amex - real card networks do
+/// price differently. Declines anything above `decline_above`, e.g. to mimic a low per-transaction
+/// card limit. `requires_3ds` adds a flat 10c liability-shift verification fee on top, the usual
+/// cost of running the cardholder through 3-D Secure before settling.
+#[derive(Debug)]
+struct CreditCard {
+    network: String,
+    requires_3ds: bool,
+    decline_above: Option<Money>,
+}
 impl PaymentStrategy for CreditCard {
-    fn pay(&self, amount: f64) {
-        println!("Paid €{amount} using Credit Card");
+    fn fee(&self, amount: Money) -> Money {
+        let rate = if self.network.eq_ignore_ascii_case("amex") { 0.035 } else { 0.019 };
+        let mut cents = round_half_up_cents(amount.cents as f64 * rate) + 25;
+        if self.requires_3ds {
+            cents += 10;
+        }
+        Money::new(cents, amount.currency)
+    }
+
+    fn pay(&self, amount: Money) -> Result<Receipt, PaymentError> {
+        if amount.cents <= 0 {
+            return Err(PaymentError::AmountNotPositive(amount));
+        }
+        if let Some(limit) = self.decline_above {
+            if limit.currency != amount.currency {
+                return Err(PaymentError::CurrencyMismatch { lhs: amount.currency, rhs: limit.currency });
+            }
+            if amount.cents > limit.cents {
+                return Err(PaymentError::Declined(format!("amount {amount} exceeds the card limit of {limit}")));
+            }
+        }
+        let fee = self.fee(amount);
+        Ok(Receipt { strategy: "Credit Card", amount, fee, transaction_id: next_transaction_id("CC"), attempts: 1, original_amount: None })
+    }
+
+    /// The card network keeps its flat 25c per-transaction fee even on a refund - only the rest of
+    /// the original amount comes back.
+    fn refund(&self, receipt: &Receipt) -> Result<Refund, PaymentError> {
+        ensure_receipt_matches(receipt, "Credit Card")?;
+        let amount = Money::new((receipt.amount.cents - 25).max(0), receipt.amount.currency);
+        Ok(Refund { strategy: "Credit Card", amount, transaction_id: next_transaction_id("RF-CC") })
+    }
+}
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+enum CreditCardBuildError {
+    #[error("card network must not be empty")]
+    EmptyNetwork,
+}
+
+/// Builds a `CreditCard`, following `01_builder.rs`'s `UserBuilder` conventions: a consuming,
+/// fluent builder with one setter per field and a terminal `build()` that validates before handing
+/// back the configured strategy.
+struct CreditCardBuilder {
+    network: String,
+    requires_3ds: bool,
+    decline_above: Option<Money>,
+}
+impl CreditCardBuilder {
+    fn new(network: impl Into<String>) -> Self {
+        Self { network: network.into(), requires_3ds: false, decline_above: None }
+    }
+
+    fn requires_3ds(mut self, requires_3ds: bool) -> Self {
+        self.requires_3ds = requires_3ds;
+        self
+    }
+
+    fn decline_above(mut self, limit: Money) -> Self {
+        self.decline_above = Some(limit);
+        self
+    }
+
+    fn build(self) -> Result<CreditCard, CreditCardBuildError> {
+        if self.network.trim().is_empty() {
+            return Err(CreditCardBuildError::EmptyNetwork);
+        }
+        Ok(CreditCard { network: self.network, requires_3ds: self.requires_3ds, decline_above: self.decline_above })
     }
 }
-struct Paypal;
+
+/// Charges 2.9% plus a flat 35c, PayPal's usual structure. Declines up front if the configured
+/// account email is obviously malformed, instead of charging a card that can't be paid out to.
+/// `sandbox` routes the transaction through PayPal's test environment instead of production - its
+/// only observable effect here is the `PPTEST-` transaction id prefix instead of `PP-`, so sandbox
+/// and live receipts can never be confused for one another downstream.
+#[derive(Debug)]
+struct Paypal {
+    account_email: Option<String>,
+    sandbox: bool,
+}
 impl PaymentStrategy for Paypal {
-    fn pay(&self, amount: f64) {
-        println!("Paid €{amount} via PayPal");
+    fn fee(&self, amount: Money) -> Money {
+        let cents = round_half_up_cents(amount.cents as f64 * 0.029) + 35;
+        Money::new(cents, amount.currency)
+    }
+
+    fn pay(&self, amount: Money) -> Result<Receipt, PaymentError> {
+        if amount.cents <= 0 {
+            return Err(PaymentError::AmountNotPositive(amount));
+        }
+        if let Some(email) = &self.account_email
+            && !email.contains('@')
+        {
+            return Err(PaymentError::Declined(format!("invalid PayPal account email: {email}")));
+        }
+        let fee = self.fee(amount);
+        let prefix = if self.sandbox { "PPTEST" } else { "PP" };
+        Ok(Receipt { strategy: "PayPal", amount, fee, transaction_id: next_transaction_id(prefix), attempts: 1, original_amount: None })
+    }
+
+    /// PayPal refunds the full amount, unlike a card network's flat fee - see `CreditCard::refund`.
+    fn refund(&self, receipt: &Receipt) -> Result<Refund, PaymentError> {
+        ensure_receipt_matches(receipt, "PayPal")?;
+        Ok(Refund { strategy: "PayPal", amount: receipt.amount, transaction_id: next_transaction_id("RF-PP") })
+    }
+}
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+enum PaypalBuildError {
+    #[error("invalid PayPal account email: {0}")]
+    InvalidEmail(String),
+}
+
+/// Builds a `Paypal`, following the same `01_builder.rs` conventions as `CreditCardBuilder`.
+#[derive(Default)]
+struct PaypalBuilder {
+    account_email: Option<String>,
+    sandbox: bool,
+}
+impl PaypalBuilder {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn account_email(mut self, email: impl Into<String>) -> Self {
+        self.account_email = Some(email.into());
+        self
+    }
+
+    fn sandbox(mut self, sandbox: bool) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    fn build(self) -> Result<Paypal, PaypalBuildError> {
+        if let Some(email) = &self.account_email
+            && !email.contains('@')
+        {
+            return Err(PaypalBuildError::InvalidEmail(email.clone()));
+        }
+        Ok(Paypal { account_email: self.account_email, sandbox: self.sandbox })
+    }
+}
+
+/// Flat-fee bank transfer: a fixed 50c regardless of amount, the usual SEPA-style pricing (same
+/// idea as `02_strategy_enum.rs`'s `Sepa` variant, modeled here as its own `PaymentStrategy` so
+/// `StrategyRegistry` below has a third provider to register at runtime).
+struct BankTransfer;
+impl PaymentStrategy for BankTransfer {
+    fn fee(&self, amount: Money) -> Money {
+        Money::new(50, amount.currency)
+    }
+
+    fn pay(&self, amount: Money) -> Result<Receipt, PaymentError> {
+        if amount.cents <= 0 {
+            return Err(PaymentError::AmountNotPositive(amount));
+        }
+        let fee = self.fee(amount);
+        Ok(Receipt { strategy: "Bank Transfer", amount, fee, transaction_id: next_transaction_id("BT"), attempts: 1, original_amount: None })
+    }
+}
+
+/// No-fee in-app balance, meant for small amounts where a card or bank fee would be disproportionate
+/// (see `RoutingStrategy` below, which routes small amounts here).
+struct Wallet;
+impl PaymentStrategy for Wallet {
+    fn fee(&self, amount: Money) -> Money {
+        Money::new(0, amount.currency)
+    }
+
+    fn pay(&self, amount: Money) -> Result<Receipt, PaymentError> {
+        if amount.cents <= 0 {
+            return Err(PaymentError::AmountNotPositive(amount));
+        }
+        let fee = self.fee(amount);
+        Ok(Receipt { strategy: "Wallet", amount, fee, transaction_id: next_transaction_id("WA"), attempts: 1, original_amount: None })
+    }
+}
+
+/// Maps a config-style name to a factory that builds a fresh strategy, so a new payment provider
+/// can be plugged in at runtime instead of adding a match arm to `PaymentContext::from_name`.
+/// Registering the same name twice follows `HashMap::insert`'s own rule - the later registration
+/// silently wins, same as `CommandBus::register` elsewhere in this crate.
+struct StrategyRegistry {
+    factories: std::collections::HashMap<String, Box<dyn Fn() -> Box<dyn PaymentStrategy>>>,
+}
+impl StrategyRegistry {
+    fn new() -> Self {
+        Self { factories: std::collections::HashMap::new() }
+    }
+
+    fn register(&mut self, name: &str, factory: Box<dyn Fn() -> Box<dyn PaymentStrategy>>) {
+        self.factories.insert(name.to_string(), factory);
+    }
+
+    fn create(&self, name: &str) -> Option<Box<dyn PaymentStrategy>> {
+        self.factories.get(name).map(|factory| factory())
+    }
+}
+
+/// Decorator that retries the wrapped strategy up to `max_attempts` times when it fails with a
+/// retryable error (see `PaymentError::is_retryable`), waiting `backoff(attempt)` between tries.
+/// `backoff` is a plain closure instead of a fixed sleep so tests can make it a no-op and run
+/// instantly. On eventual success the returned `Receipt::attempts` records how many tries it took.
+struct RetryingStrategy<S: PaymentStrategy> {
+    inner: S,
+    max_attempts: u32,
+    backoff: Box<dyn Fn(u32)>,
+}
+impl<S: PaymentStrategy> RetryingStrategy<S> {
+    fn new(inner: S, max_attempts: u32, backoff: Box<dyn Fn(u32)>) -> Self {
+        Self { inner, max_attempts, backoff }
+    }
+}
+impl<S: PaymentStrategy> PaymentStrategy for RetryingStrategy<S> {
+    fn fee(&self, amount: Money) -> Money {
+        self.inner.fee(amount)
+    }
+
+    fn pay(&self, amount: Money) -> Result<Receipt, PaymentError> {
+        let mut last_err = None;
+        for attempt in 1..=self.max_attempts {
+            match self.inner.pay(amount) {
+                Ok(mut receipt) => {
+                    receipt.attempts = attempt;
+                    return Ok(receipt);
+                }
+                Err(err) if err.is_retryable() && attempt < self.max_attempts => {
+                    (self.backoff)(attempt);
+                    last_err = Some(err);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Err(last_err.expect("max_attempts is at least 1, so the loop runs at least once"))
+    }
+
+    fn refund(&self, receipt: &Receipt) -> Result<Refund, PaymentError> {
+        self.inner.refund(receipt)
+    }
+}
+
+// Middleware - metrics
+trait MetricsSink {
+    fn record(&self, strategy: &'static str, duration: Duration);
+}
+
+struct ConsoleMetrics;
+
+impl MetricsSink for ConsoleMetrics {
+    fn record(&self, strategy: &'static str, duration: Duration) {
+        println!("[METRICS] {strategy} took {duration:?}");
+    }
+}
+
+/// Decorator that wraps a strategy, timing each `pay` call with `clock` and reporting the elapsed
+/// duration through `sink` - the same middleware idea as `examples/11_command_bus.rs`'s
+/// `CommandLogger`, but for latency instead of log lines. `clock` is a closure rather than a
+/// straight `Instant::now()` call so tests can inject a fake one and assert on recorded calls
+/// without depending on real timing.
+struct TimedStrategy<S: PaymentStrategy> {
+    inner: S,
+    sink: Box<dyn MetricsSink>,
+    clock: Box<dyn Fn() -> Instant>,
+}
+impl<S: PaymentStrategy> TimedStrategy<S> {
+    fn new(inner: S, sink: Box<dyn MetricsSink>) -> Self {
+        Self::with_clock(inner, sink, Box::new(Instant::now))
+    }
+
+    fn with_clock(inner: S, sink: Box<dyn MetricsSink>, clock: Box<dyn Fn() -> Instant>) -> Self {
+        Self { inner, sink, clock }
+    }
+}
+impl<S: PaymentStrategy> PaymentStrategy for TimedStrategy<S> {
+    fn fee(&self, amount: Money) -> Money {
+        self.inner.fee(amount)
+    }
+
+    fn pay(&self, amount: Money) -> Result<Receipt, PaymentError> {
+        let start = (self.clock)();
+        let result = self.inner.pay(amount);
+        let elapsed = (self.clock)().duration_since(start);
+        self.sink.record(result.as_ref().map_or("failed payment", |receipt| receipt.strategy), elapsed);
+        result
+    }
+
+    fn refund(&self, receipt: &Receipt) -> Result<Refund, PaymentError> {
+        self.inner.refund(receipt)
+    }
+}
+
+/// Where `ConvertingStrategy` gets its exchange rates from. A trait instead of a bare `HashMap`
+/// so a real implementation could hit a live rates API without changing the decorator.
+trait RateProvider {
+    /// `1 unit of from == rate units of to`, or `None` if no rate is known for that pair.
+    fn rate(&self, from: Currency, to: Currency) -> Option<f64>;
+}
+
+/// A fixed lookup table of exchange rates - good enough for an example and for tests, where a live
+/// rate would make the numbers nondeterministic.
+struct StaticRates {
+    rates: std::collections::HashMap<(Currency, Currency), f64>,
+}
+impl StaticRates {
+    fn new(rates: Vec<((Currency, Currency), f64)>) -> Self {
+        Self { rates: rates.into_iter().collect() }
+    }
+}
+impl RateProvider for StaticRates {
+    fn rate(&self, from: Currency, to: Currency) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+        self.rates.get(&(from, to)).copied()
+    }
+}
+
+/// Decorator that lets the inner strategy accept any currency: it converts the incoming amount to
+/// the currency the inner strategy actually settles in (whatever currency it's called with),
+/// records the original amount on the `Receipt` alongside the converted one, then charges the
+/// inner strategy as usual. `PaymentError::NoRate` surfaces a missing conversion rate instead of
+/// silently charging the wrong amount.
+struct ConvertingStrategy<S: PaymentStrategy> {
+    inner: S,
+    settlement_currency: Currency,
+    rates: Box<dyn RateProvider>,
+}
+impl<S: PaymentStrategy> ConvertingStrategy<S> {
+    fn new(inner: S, settlement_currency: Currency, rates: Box<dyn RateProvider>) -> Self {
+        Self { inner, settlement_currency, rates }
+    }
+
+    fn convert(&self, amount: Money) -> Result<Money, PaymentError> {
+        if amount.currency == self.settlement_currency {
+            return Ok(amount);
+        }
+        let rate = self.rates.rate(amount.currency, self.settlement_currency).ok_or(PaymentError::NoRate(amount.currency, self.settlement_currency))?;
+        Ok(Money::new(round_half_up_cents(amount.cents as f64 * rate), self.settlement_currency))
+    }
+}
+impl<S: PaymentStrategy> PaymentStrategy for ConvertingStrategy<S> {
+    fn fee(&self, amount: Money) -> Money {
+        match self.convert(amount) {
+            Ok(converted) => self.inner.fee(converted),
+            Err(_) => Money::new(0, amount.currency),
+        }
+    }
+
+    fn pay(&self, amount: Money) -> Result<Receipt, PaymentError> {
+        let converted = self.convert(amount)?;
+        let mut receipt = self.inner.pay(converted)?;
+        receipt.original_amount = Some(amount);
+        Ok(receipt)
+    }
+}
+
+/// A caller-supplied token identifying "this one logical payment attempt" - typically a UUID
+/// generated once per user action (e.g. once per checkout button, regardless of how many times the
+/// click actually fires) and replayed on every retry of that same action.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IdempotencyKey(pub String);
+
+/// Wraps a strategy so replaying the same `IdempotencyKey` returns the original `Receipt` instead
+/// of charging twice - the classic "double-click the pay button" problem. Not a `PaymentStrategy`
+/// itself: idempotency needs the extra key parameter `pay` doesn't have, so it's called through
+/// `pay_with_key` instead of being stacked like the other decorators above.
+struct IdempotentStrategy<S: PaymentStrategy> {
+    inner: S,
+    seen: std::cell::RefCell<std::collections::HashMap<IdempotencyKey, Receipt>>,
+}
+impl<S: PaymentStrategy> IdempotentStrategy<S> {
+    fn new(inner: S) -> Self {
+        Self { inner, seen: std::cell::RefCell::new(std::collections::HashMap::new()) }
+    }
+
+    fn pay_with_key(&self, key: IdempotencyKey, amount: Money) -> Result<Receipt, PaymentError> {
+        if let Some(receipt) = self.seen.borrow().get(&key) {
+            if receipt.amount != amount {
+                return Err(PaymentError::KeyReuseMismatch { key, original: receipt.amount, attempted: amount });
+            }
+            return Ok(receipt.clone());
+        }
+        let receipt = self.inner.pay(amount)?;
+        self.seen.borrow_mut().insert(key, receipt.clone());
+        Ok(receipt)
+    }
+}
+
+/// Declines its first `fails_before_success` calls, then succeeds - just enough of a flaky
+/// provider to exercise `RetryingStrategy` without an actual network.
+struct FlakyMock {
+    fails_before_success: std::cell::Cell<u32>,
+}
+impl PaymentStrategy for FlakyMock {
+    fn fee(&self, amount: Money) -> Money {
+        Money::new(0, amount.currency)
+    }
+
+    fn pay(&self, amount: Money) -> Result<Receipt, PaymentError> {
+        if self.fails_before_success.get() > 0 {
+            self.fails_before_success.set(self.fails_before_success.get() - 1);
+            return Err(PaymentError::Declined("flaky provider is still warming up".to_string()));
+        }
+        Ok(Receipt { strategy: "Flaky Mock", amount, fee: Money::new(0, amount.currency), transaction_id: next_transaction_id("FM"), attempts: 1, original_amount: None })
+    }
+}
+
+/// Chain of Responsibility over `PaymentStrategy`: tries each strategy in order and returns the
+/// first `Ok(Receipt)`. If every strategy declines, aggregates all of their failures into
+/// `PaymentError::AllFailed` instead of only surfacing the last one.
+struct FallbackPayment {
+    strategies: Vec<Box<dyn PaymentStrategy>>,
+}
+impl FallbackPayment {
+    fn new(strategies: Vec<Box<dyn PaymentStrategy>>) -> Self {
+        Self { strategies }
+    }
+}
+impl PaymentStrategy for FallbackPayment {
+    /// Which strategy ends up charged depends on which one succeeds, so there's no fee to quote
+    /// ahead of time - zero is the honest answer; read `pay`'s receipt for the real fee.
+    fn fee(&self, amount: Money) -> Money {
+        Money::new(0, amount.currency)
+    }
+
+    fn pay(&self, amount: Money) -> Result<Receipt, PaymentError> {
+        let mut failures = Vec::new();
+        for strategy in &self.strategies {
+            match strategy.pay(amount) {
+                Ok(receipt) => return Ok(receipt),
+                Err(err) => failures.push(err),
+            }
+        }
+        Err(PaymentError::AllFailed(failures))
+    }
+}
+
+/// Epsilon for comparing a `SplitPayment`'s fractions against 1.0 - generous enough to absorb the
+/// usual floating-point drift from adding a handful of fractions together.
+const FRACTION_SUM_EPSILON: f64 = 1e-9;
+
+/// Composes several strategies into one: splits the amount across them proportionally and pays
+/// each leg separately. The fractions must sum to 1.0 (within `FRACTION_SUM_EPSILON`), and because
+/// rounding every leg to the cent independently can leave the legs a cent short or over, the last
+/// leg is given whatever is left over instead of its own rounded share - that's what keeps the legs
+/// summing to the original amount exactly.
+struct SplitPayment {
+    legs: Vec<(Box<dyn PaymentStrategy>, f64)>,
+}
+impl SplitPayment {
+    fn new(legs: Vec<(Box<dyn PaymentStrategy>, f64)>) -> Self {
+        Self { legs }
+    }
+}
+impl PaymentStrategy for SplitPayment {
+    /// Same reasoning as `FallbackPayment::fee`: the real total is the sum of each leg's own fee,
+    /// only known once `pay` actually charges every leg.
+    fn fee(&self, amount: Money) -> Money {
+        Money::new(0, amount.currency)
+    }
+
+    fn pay(&self, amount: Money) -> Result<Receipt, PaymentError> {
+        let fraction_sum: f64 = self.legs.iter().map(|(_, fraction)| fraction).sum();
+        if (fraction_sum - 1.0).abs() > FRACTION_SUM_EPSILON {
+            return Err(PaymentError::FractionsDoNotSumToOne { sum: fraction_sum });
+        }
+
+        let mut total_fee = Money::new(0, amount.currency);
+        let mut transaction_ids = Vec::with_capacity(self.legs.len());
+        let mut remaining_cents = amount.cents;
+        let last = self.legs.len() - 1;
+
+        for (i, (strategy, fraction)) in self.legs.iter().enumerate() {
+            let leg_cents = if i == last { remaining_cents } else { round_half_up_cents(amount.cents as f64 * fraction) };
+            remaining_cents -= leg_cents;
+            let receipt = strategy.pay(Money::new(leg_cents, amount.currency))?;
+            total_fee = total_fee.checked_add(receipt.fee)?;
+            transaction_ids.push(receipt.transaction_id);
+        }
+
+        Ok(Receipt { strategy: "Split Payment", amount, fee: total_fee, transaction_id: transaction_ids.join("+"), attempts: 1, original_amount: None })
+    }
+}
+
+/// The context a `RoutingStrategy` predicate decides on. Just an amount today, but giving it its
+/// own type instead of matching on `Money` directly leaves room to route on more later (currency,
+/// a customer tier, ...) without changing `PaymentStrategy::pay`'s signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaymentRequest {
+    pub amount: Money,
+}
+
+/// Picks the first strategy whose predicate matches the request, e.g. small amounts go to a
+/// `Wallet` and larger ones to a card. Order matters: predicates are tried in registration order
+/// and the first match wins, so a catch-all predicate must come last. Falls through to
+/// `PaymentError::NoRouteFor` if nothing matches.
+type Route = (Box<dyn Fn(&PaymentRequest) -> bool>, Box<dyn PaymentStrategy>);
+
+struct RoutingStrategy {
+    routes: Vec<Route>,
+}
+impl RoutingStrategy {
+    fn new(routes: Vec<Route>) -> Self {
+        Self { routes }
+    }
+}
+impl PaymentStrategy for RoutingStrategy {
+    /// Same reasoning as `FallbackPayment::fee`: which strategy ends up charging the fee is only
+    /// known once `pay` picks a route.
+    fn fee(&self, amount: Money) -> Money {
+        Money::new(0, amount.currency)
+    }
+
+    fn pay(&self, amount: Money) -> Result<Receipt, PaymentError> {
+        let request = PaymentRequest { amount };
+        for (matches, strategy) in &self.routes {
+            if matches(&request) {
+                return strategy.pay(amount);
+            }
+        }
+        Err(PaymentError::NoRouteFor(amount))
     }
 }
+
+/// Per-strategy settings read from the outside (env, config file, CLI flag, ...) and handed to
+/// `PaymentContext::from_name` - each strategy only looks at the field(s) it cares about.
+#[derive(Debug, Clone, Default)]
+pub struct PaymentOptions {
+    pub card_network: Option<String>,
+    pub paypal_account_email: Option<String>,
+}
+
+/// Names `PaymentContext::from_name` accepts, also reported back in `UnknownStrategy` so a typo
+/// doesn't leave the caller guessing.
+const SUPPORTED_STRATEGY_NAMES: &[&str] = &["credit_card", "paypal"];
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("unknown payment strategy {requested:?}, supported: {supported:?}")]
+pub struct UnknownStrategy {
+    pub requested: String,
+    pub supported: Vec<&'static str>,
+}
+
 struct PaymentContext {
     strategy: Box<dyn PaymentStrategy>,
 }
@@ -27,19 +748,1035 @@ impl PaymentContext {
     fn new(strategy: Box<dyn PaymentStrategy>) -> Self {
         Self { strategy }
     }
-    fn process(&self, amount: f64) {
-        self.strategy.pay(amount);
+
+    /// Builds a `PaymentContext` from a config-style name instead of an already-boxed strategy -
+    /// the shape a real system actually has once the strategy choice comes from a config file or
+    /// a CLI flag rather than being hardcoded at the call site.
+    pub fn from_name(name: &str, opts: &PaymentOptions) -> Result<Self, UnknownStrategy> {
+        let strategy: Box<dyn PaymentStrategy> = match name {
+            "credit_card" => Box::new(CreditCard { network: opts.card_network.clone().unwrap_or_else(|| "Visa".to_string()), requires_3ds: false, decline_above: None }),
+            "paypal" => Box::new(Paypal { account_email: opts.paypal_account_email.clone(), sandbox: false }),
+            _ => return Err(UnknownStrategy { requested: name.to_string(), supported: SUPPORTED_STRATEGY_NAMES.to_vec() }),
+        };
+        Ok(Self::new(strategy))
+    }
+
+    /// Delegates to the wrapped strategy and returns its full amount + fee breakdown - use
+    /// `Receipt::total` on the result to get the combined, currency-checked total.
+    fn process(&self, amount: Money) -> Result<Receipt, PaymentError> {
+        self.strategy.pay(amount)
+    }
+
+    /// Delegates to the wrapped strategy's `refund`, so a mismatched-receipt rejection happens in
+    /// one place regardless of which concrete strategy the context was built with.
+    fn refund(&self, receipt: &Receipt) -> Result<Refund, PaymentError> {
+        self.strategy.refund(receipt)
+    }
+}
+
+/// Fluently selects a base `PaymentStrategy` and layers middleware decorators on top of it before
+/// handing the result to `PaymentContext::new` - the `Box<dyn PaymentStrategy>` impl above is what
+/// lets each `.with_*` call re-box the strategy built so far, so decorators stack in any order and
+/// the builder only ever has to track one type.
+struct PaymentContextBuilder {
+    strategy: Box<dyn PaymentStrategy>,
+}
+impl PaymentContextBuilder {
+    fn new(strategy: Box<dyn PaymentStrategy>) -> Self {
+        Self { strategy }
+    }
+
+    /// Wraps the strategy built so far in a `RetryingStrategy` - see its doc comment for what
+    /// `max_attempts` and `backoff` do.
+    fn with_retry(self, max_attempts: u32, backoff: impl Fn(u32) + 'static) -> Self {
+        Self { strategy: Box::new(RetryingStrategy::new(self.strategy, max_attempts, Box::new(backoff))) }
+    }
+
+    /// Wraps the strategy built so far in a `TimedStrategy` reporting to `sink`.
+    fn with_timing(self, sink: Box<dyn MetricsSink>) -> Self {
+        Self { strategy: Box::new(TimedStrategy::new(self.strategy, sink)) }
+    }
+
+    /// Wraps the strategy built so far in a `ConvertingStrategy` that settles in
+    /// `settlement_currency` using `rates`.
+    fn with_conversion(self, settlement_currency: Currency, rates: Box<dyn RateProvider>) -> Self {
+        Self { strategy: Box::new(ConvertingStrategy::new(self.strategy, settlement_currency, rates)) }
+    }
+
+    fn build(self) -> PaymentContext {
+        PaymentContext::new(self.strategy)
+    }
+}
+
+/// Static-dispatch twin of `PaymentContext`: `S` is monomorphized at compile time instead of
+/// boxed, so there's no heap allocation and no vtable lookup on `process`. The cost is in what you
+/// can't do with it: `GenericPaymentContext<CreditCard>` and `GenericPaymentContext<Paypal>` are
+/// different types, so unlike `Box<dyn PaymentStrategy>` they can't sit in the same `Vec` - see
+/// `pay_all` below for the dynamic-dispatch-only trick that relies on.
+struct GenericPaymentContext<S: PaymentStrategy> {
+    strategy: S,
+}
+impl<S: PaymentStrategy> GenericPaymentContext<S> {
+    fn new(strategy: S) -> Self {
+        Self { strategy }
+    }
+
+    fn process(&self, amount: Money) -> Result<Receipt, PaymentError> {
+        self.strategy.pay(amount)
     }
 }
 
+/// Needs `Box<dyn PaymentStrategy>` specifically: `PaymentStrategy` isn't `Sized`, so a `Vec` of
+/// strategies can only hold them behind a pointer, and object safety is what lets that pointer be
+/// a trait object in the first place. A generic `fn pay_all<S: PaymentStrategy>(contexts: &[S], ...)`
+/// would only ever accept a slice of one concrete strategy type - it couldn't take the heterogeneous
+/// mix this function demonstrates.
+fn pay_all(strategies: &[Box<dyn PaymentStrategy>], amount: Money) -> Vec<Result<Receipt, PaymentError>> {
+    strategies.iter().map(|strategy| strategy.pay(amount)).collect()
+}
+
 // We create two PaymentContext, each with a different strategy (CreditCard or Paypal).
 // Each context calls process(amount), which delegates to the corresponding strategy's pay() method.
 fn main() {
     // Use Credit Card payment strategy
-    let credit_card_payment = PaymentContext::new(Box::new(CreditCard));
-    credit_card_payment.process(100.0);
+    let credit_card_payment = PaymentContext::new(Box::new(CreditCard { network: "Visa".to_string(), requires_3ds: false, decline_above: None }));
+    match credit_card_payment.process(Money::eur(10_000)) {
+        Ok(receipt) => println!(
+            "Paid {} using {} (fee {}, total {}, tx {})",
+            receipt.amount,
+            receipt.strategy,
+            receipt.fee,
+            receipt.total().unwrap(),
+            receipt.transaction_id
+        ),
+        Err(err) => println!("Credit Card payment failed: {err}"),
+    }
 
     // Use PayPal payment strategy
-    let paypal_payment = PaymentContext::new(Box::new(Paypal));
-    paypal_payment.process(75.5);
+    let paypal_payment = PaymentContext::new(Box::new(Paypal { account_email: None, sandbox: false }));
+    match paypal_payment.process(Money::eur(7_550)) {
+        Ok(receipt) => println!(
+            "Paid {} via {} (fee {}, total {}, tx {})",
+            receipt.amount,
+            receipt.strategy,
+            receipt.fee,
+            receipt.total().unwrap(),
+            receipt.transaction_id
+        ),
+        Err(err) => println!("PayPal payment failed: {err}"),
+    }
+
+    // A negative amount is rejected instead of silently "succeeding".
+    let err = credit_card_payment.process(Money::eur(-1_000)).unwrap_err();
+    println!("Deliberately bad payment: {err}");
+
+    // Config-driven strategy: picked from the first CLI argument, defaulting to PayPal.
+    let requested_name = std::env::args().nth(1).unwrap_or_else(|| "paypal".to_string());
+    let opts = PaymentOptions::default();
+    match PaymentContext::from_name(&requested_name, &opts) {
+        Ok(context) => match context.process(Money::eur(4_200)) {
+            Ok(receipt) => println!("Paid {} via the config-selected {} (fee {})", receipt.amount, receipt.strategy, receipt.fee),
+            Err(err) => println!("Config-selected strategy {requested_name:?} failed: {err}"),
+        },
+        Err(err) => println!("Could not build a payment context: {err}"),
+    }
+
+    // Fallback: a card with a low limit declines a large amount, so the chain falls through
+    // to PayPal instead of failing the whole payment.
+    let fallback = FallbackPayment::new(vec![
+        Box::new(CreditCard { network: "Visa".to_string(), requires_3ds: false, decline_above: Some(Money::eur(5_000)) }),
+        Box::new(Paypal { account_email: Some("payer@example.com".to_string()), sandbox: false }),
+    ]);
+    match fallback.pay(Money::eur(10_000)) {
+        Ok(receipt) => println!("Paid {} via fallback, settled by {} (tx {})", receipt.amount, receipt.strategy, receipt.transaction_id),
+        Err(err) => println!("Fallback payment failed: {err}"),
+    }
+
+    // Split a bill three ways: a third on card, a third on PayPal, the rest also on card.
+    let split = SplitPayment::new(vec![
+        (Box::new(CreditCard { network: "Visa".to_string(), requires_3ds: false, decline_above: None }), 1.0 / 3.0),
+        (Box::new(Paypal { account_email: Some("payer@example.com".to_string()), sandbox: false }), 1.0 / 3.0),
+        (Box::new(CreditCard { network: "Visa".to_string(), requires_3ds: false, decline_above: None }), 1.0 / 3.0),
+    ]);
+    match split.pay(Money::eur(10_001)) {
+        Ok(receipt) => println!("Paid {} via {} (total fee {}, legs {})", receipt.amount, receipt.strategy, receipt.fee, receipt.transaction_id),
+        Err(err) => println!("Split payment failed: {err}"),
+    }
+
+    // Registry: BankTransfer is plugged in at runtime, without touching from_name's match.
+    let mut registry = StrategyRegistry::new();
+    registry.register("credit_card", Box::new(|| Box::new(CreditCard { network: "Visa".to_string(), requires_3ds: false, decline_above: None })));
+    registry.register("paypal", Box::new(|| Box::new(Paypal { account_email: None, sandbox: false })));
+    registry.register("bank_transfer", Box::new(|| Box::new(BankTransfer)));
+    match registry.create("bank_transfer") {
+        Some(strategy) => match strategy.pay(Money::eur(2_500)) {
+            Ok(receipt) => println!("Paid {} via {} (fee {}, tx {})", receipt.amount, receipt.strategy, receipt.fee, receipt.transaction_id),
+            Err(err) => println!("Bank transfer failed: {err}"),
+        },
+        None => println!("No strategy registered under that name"),
+    }
+
+    // Static vs dynamic dispatch: same fee, same strategy, no heap allocation for the generic one.
+    let generic_context = GenericPaymentContext::new(CreditCard { network: "Visa".to_string(), requires_3ds: false, decline_above: None });
+    let generic_receipt = generic_context.process(Money::eur(10_000)).unwrap();
+    println!("Paid {} via (static) {} (fee {})", generic_receipt.amount, generic_receipt.strategy, generic_receipt.fee);
+
+    // pay_all needs Box<dyn PaymentStrategy> because CreditCard, Paypal, and BankTransfer are three
+    // different types - a GenericPaymentContext<S> can't hold a mix of them in one Vec.
+    let strategies: Vec<Box<dyn PaymentStrategy>> =
+        vec![Box::new(CreditCard { network: "Visa".to_string(), requires_3ds: false, decline_above: None }), Box::new(Paypal { account_email: None, sandbox: false }), Box::new(BankTransfer)];
+    for result in pay_all(&strategies, Money::eur(3_000)) {
+        match result {
+            Ok(receipt) => println!("pay_all: paid {} via {} (fee {})", receipt.amount, receipt.strategy, receipt.fee),
+            Err(err) => println!("pay_all: payment failed: {err}"),
+        }
+    }
+
+    // Retry: the flaky mock declines twice, then succeeds on its third attempt.
+    let flaky = FlakyMock { fails_before_success: std::cell::Cell::new(2) };
+    let retrying = RetryingStrategy::new(flaky, 5, Box::new(|attempt| println!("Retrying after attempt {attempt}...")));
+    match retrying.pay(Money::eur(1_500)) {
+        Ok(receipt) => println!("Paid {} via {} after {} attempt(s)", receipt.amount, receipt.strategy, receipt.attempts),
+        Err(err) => println!("Retrying payment failed: {err}"),
+    }
+
+    // Decorators stack: TimedStrategy wraps a RetryingStrategy, which wraps the real strategy.
+    // Each call's latency prints as it happens, then we print a summary over all of them.
+    let timed = TimedStrategy::new(RetryingStrategy::new(Paypal { account_email: Some("payer@example.com".to_string()), sandbox: false }, 3, Box::new(|_attempt| {})), Box::new(ConsoleMetrics));
+    let mut total_elapsed = Duration::ZERO;
+    let mut call_count = 0u32;
+    for amount in [Money::eur(1_000), Money::eur(2_500), Money::eur(500)] {
+        let start = Instant::now();
+        match timed.pay(amount) {
+            Ok(receipt) => println!("Paid {} via (timed) {} (fee {})", receipt.amount, receipt.strategy, receipt.fee),
+            Err(err) => println!("Timed payment failed: {err}"),
+        }
+        total_elapsed += start.elapsed();
+        call_count += 1;
+    }
+    println!("Timed strategy summary: {call_count} call(s), {total_elapsed:?} total");
+
+    // Routing: small amounts go to the fee-free wallet, mid-size ones to a card, and anything
+    // above the card's own limit falls through to a bank transfer - three amounts, three routes.
+    let router = RoutingStrategy::new(vec![
+        (Box::new(|request: &PaymentRequest| request.amount.cents < 3_000), Box::new(Wallet)),
+        (Box::new(|request: &PaymentRequest| request.amount.cents < 100_000), Box::new(CreditCard { network: "Visa".to_string(), requires_3ds: false, decline_above: None })),
+        (Box::new(|_request: &PaymentRequest| true), Box::new(BankTransfer)),
+    ]);
+    for amount in [Money::eur(1_500), Money::eur(10_000), Money::eur(500_000)] {
+        match router.pay(amount) {
+            Ok(receipt) => println!("Routed {} to {} (fee {})", receipt.amount, receipt.strategy, receipt.fee),
+            Err(err) => println!("Routing failed: {err}"),
+        }
+    }
+
+    // Pay, then refund the same receipt: PayPal gives everything back, a card keeps its flat fee.
+    let paypal_context = PaymentContext::new(Box::new(Paypal { account_email: Some("payer@example.com".to_string()), sandbox: false }));
+    let paypal_receipt = paypal_context.process(Money::eur(5_000)).unwrap();
+    println!("Paid {} via {} (fee {}, tx {})", paypal_receipt.amount, paypal_receipt.strategy, paypal_receipt.fee, paypal_receipt.transaction_id);
+    match paypal_context.refund(&paypal_receipt) {
+        Ok(refund) => println!("Refunded {} via {} (tx {})", refund.amount, refund.strategy, refund.transaction_id),
+        Err(err) => println!("Refund failed: {err}"),
+    }
+
+    // Converting: the card only settles in EUR, but the payer hands over USD. The receipt keeps
+    // both the original USD amount and the converted EUR amount the card was actually charged.
+    let rates = StaticRates::new(vec![((Currency::Usd, Currency::Eur), 0.92)]);
+    let converting = ConvertingStrategy::new(CreditCard { network: "Visa".to_string(), requires_3ds: false, decline_above: None }, Currency::Eur, Box::new(rates));
+    match converting.pay(Money::new(10_000, Currency::Usd)) {
+        Ok(receipt) => println!(
+            "Paid {} (converted from {}) via {} (fee {})",
+            receipt.amount,
+            receipt.original_amount.unwrap(),
+            receipt.strategy,
+            receipt.fee
+        ),
+        Err(err) => println!("Converting payment failed: {err}"),
+    }
+
+    // Idempotency: simulate a double-click on the pay button - both clicks send the same key, so
+    // only the first actually charges the card.
+    let idempotent = IdempotentStrategy::new(CreditCard { network: "Visa".to_string(), requires_3ds: false, decline_above: None });
+    let click_key = IdempotencyKey("checkout-session-42".to_string());
+    let first_click = idempotent.pay_with_key(click_key.clone(), Money::eur(9_900)).unwrap();
+    let second_click = idempotent.pay_with_key(click_key, Money::eur(9_900)).unwrap();
+    println!("First click: paid {} (tx {})", first_click.amount, first_click.transaction_id);
+    println!("Second click: paid {} (tx {}) - same transaction, no double charge", second_click.amount, second_click.transaction_id);
+
+    // Builders: a fully configured context - a 3DS-required Amex declining above 200 EUR, retried
+    // up to 3 times and timed - built in one expression.
+    let configured_context = PaymentContextBuilder::new(Box::new(
+        CreditCardBuilder::new("Amex").requires_3ds(true).decline_above(Money::eur(20_000)).build().unwrap(),
+    ))
+    .with_retry(3, |_attempt| {})
+    .with_timing(Box::new(ConsoleMetrics))
+    .build();
+    match configured_context.process(Money::eur(5_000)) {
+        Ok(receipt) => println!("Builder-configured payment: paid {} via {} (fee {}, tx {})", receipt.amount, receipt.strategy, receipt.fee, receipt.transaction_id),
+        Err(err) => println!("Builder-configured payment failed: {err}"),
+    }
+    match configured_context.process(Money::eur(50_000)) {
+        Ok(receipt) => println!("Builder-configured payment: paid {} via {} (fee {}, tx {})", receipt.amount, receipt.strategy, receipt.fee, receipt.transaction_id),
+        Err(err) => println!("Builder-configured payment over the decline threshold failed: {err}"),
+    }
+
+    let sandbox_paypal = PaypalBuilder::new().account_email("payer@example.com").sandbox(true).build().unwrap();
+    let sandbox_receipt = sandbox_paypal.pay(Money::eur(1_000)).unwrap();
+    println!("Sandbox PayPal payment: tx {}", sandbox_receipt.transaction_id);
+
+    // A builder-configured strategy wrapped in a currency-converting context.
+    let converting_context = PaymentContextBuilder::new(Box::new(CreditCardBuilder::new("Visa").build().unwrap()))
+        .with_conversion(Currency::Eur, Box::new(StaticRates::new(vec![((Currency::Usd, Currency::Eur), 0.92)])))
+        .build();
+    let converted_receipt = converting_context.process(Money::new(10_000, Currency::Usd)).unwrap();
+    println!("Builder-configured conversion: paid {} (from {})", converted_receipt.amount, converted_receipt.original_amount.unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn visa() -> CreditCard {
+        CreditCard { network: "Visa".to_string(), requires_3ds: false, decline_above: None }
+    }
+    fn paypal_without_email() -> Paypal {
+        Paypal { account_email: None, sandbox: false }
+    }
+
+    #[test]
+    fn credit_card_charges_a_flat_plus_percentage_fee() {
+        let receipt = visa().pay(Money::eur(10_000)).unwrap();
+        assert_eq!(receipt.strategy, "Credit Card");
+        assert_eq!(receipt.fee, Money::eur(215));
+    }
+
+    #[test]
+    fn amex_charges_a_higher_fee_than_other_networks() {
+        let receipt = CreditCard { network: "Amex".to_string(), requires_3ds: false, decline_above: None }.pay(Money::eur(10_000)).unwrap();
+        assert_eq!(receipt.fee, Money::eur(375));
+    }
+
+    #[test]
+    fn paypal_charges_a_percentage_plus_a_fixed_fee() {
+        let receipt = paypal_without_email().pay(Money::eur(10_000)).unwrap();
+        assert_eq!(receipt.strategy, "PayPal");
+        assert_eq!(receipt.fee, Money::eur(325));
+    }
+
+    #[test]
+    fn paypal_declines_a_malformed_account_email() {
+        let err = Paypal { account_email: Some("not-an-email".to_string()), sandbox: false }.pay(Money::eur(1_000)).unwrap_err();
+        assert_eq!(err, PaymentError::Declined("invalid PayPal account email: not-an-email".to_string()));
+    }
+
+    #[test]
+    fn zero_amount_is_rejected() {
+        let err = visa().pay(Money::eur(0)).unwrap_err();
+        assert_eq!(err, PaymentError::AmountNotPositive(Money::eur(0)));
+    }
+
+    #[test]
+    fn negative_amount_is_rejected_by_both_strategies() {
+        assert_eq!(visa().pay(Money::eur(-500)).unwrap_err(), PaymentError::AmountNotPositive(Money::eur(-500)));
+        assert_eq!(paypal_without_email().pay(Money::eur(-500)).unwrap_err(), PaymentError::AmountNotPositive(Money::eur(-500)));
+    }
+
+    #[test]
+    fn payment_context_propagates_the_strategys_result() {
+        let context = PaymentContext::new(Box::new(visa()));
+        assert!(context.process(Money::eur(5_000)).is_ok());
+        assert!(context.process(Money::eur(-100)).is_err());
+    }
+
+    #[test]
+    fn transaction_ids_are_prefixed_by_strategy_and_unique_per_call() {
+        let first = visa().pay(Money::eur(1_000)).unwrap();
+        let second = visa().pay(Money::eur(1_000)).unwrap();
+        assert!(first.transaction_id.starts_with("CC-"));
+        assert_ne!(first.transaction_id, second.transaction_id);
+    }
+
+    #[test]
+    fn receipt_total_is_the_checked_sum_of_amount_and_fee() {
+        let receipt = visa().pay(Money::eur(10_000)).unwrap();
+        assert_eq!(receipt.total().unwrap(), Money::eur(10_215));
+    }
+
+    #[test]
+    fn round_half_up_cents_breaks_ties_away_from_zero() {
+        assert_eq!(round_half_up_cents(2.5), 3);
+        assert_eq!(round_half_up_cents(-2.5), -3);
+        assert_eq!(round_half_up_cents(2.4), 2);
+    }
+
+    #[test]
+    fn checked_add_rejects_mismatched_currencies() {
+        let err = Money::new(100, Currency::Eur).checked_add(Money::new(100, Currency::Usd)).unwrap_err();
+        assert_eq!(err, PaymentError::CurrencyMismatch { lhs: Currency::Eur, rhs: Currency::Usd });
+    }
+
+    #[test]
+    fn credit_card_declines_with_a_currency_mismatch_against_its_limit() {
+        let card = CreditCard { network: "Visa".to_string(), requires_3ds: false, decline_above: Some(Money::new(5_000, Currency::Usd)) };
+        let err = card.pay(Money::eur(1_000)).unwrap_err();
+        assert_eq!(err, PaymentError::CurrencyMismatch { lhs: Currency::Eur, rhs: Currency::Usd });
+    }
+
+    #[test]
+    fn from_name_builds_a_credit_card_context() {
+        let context = PaymentContext::from_name("credit_card", &PaymentOptions::default()).unwrap();
+        assert!(context.process(Money::eur(1_000)).is_ok());
+    }
+
+    #[test]
+    fn from_name_builds_a_paypal_context() {
+        let context = PaymentContext::from_name("paypal", &PaymentOptions::default()).unwrap();
+        assert!(context.process(Money::eur(1_000)).is_ok());
+    }
+
+    #[test]
+    fn from_name_honors_the_configured_card_network() {
+        let opts = PaymentOptions { card_network: Some("Amex".to_string()), paypal_account_email: None };
+        let context = PaymentContext::from_name("credit_card", &opts).unwrap();
+        let receipt = context.process(Money::eur(10_000)).unwrap();
+        assert_eq!(receipt.fee, Money::eur(375));
+    }
+
+    #[test]
+    fn from_name_rejects_an_unknown_strategy_with_the_supported_list() {
+        let err = PaymentContext::from_name("bitcoin", &PaymentOptions::default()).err().unwrap();
+        assert_eq!(err, UnknownStrategy { requested: "bitcoin".to_string(), supported: SUPPORTED_STRATEGY_NAMES.to_vec() });
+    }
+
+    /// Records its name every time `pay` is called, and either succeeds or declines depending on
+    /// how it's configured - just enough of a mock to let a test assert the order `FallbackPayment`
+    /// tries its strategies in.
+    struct RecordingStrategy {
+        name: &'static str,
+        succeeds: bool,
+        calls: std::rc::Rc<std::cell::RefCell<Vec<&'static str>>>,
+    }
+    impl PaymentStrategy for RecordingStrategy {
+        fn fee(&self, amount: Money) -> Money {
+            Money::new(0, amount.currency)
+        }
+
+        fn pay(&self, amount: Money) -> Result<Receipt, PaymentError> {
+            self.calls.borrow_mut().push(self.name);
+            if self.succeeds {
+                Ok(Receipt { strategy: self.name, amount, fee: Money::new(0, amount.currency), transaction_id: next_transaction_id(self.name), attempts: 1, original_amount: None })
+            } else {
+                Err(PaymentError::Declined(format!("{} declines", self.name)))
+            }
+        }
+    }
+
+    #[test]
+    fn fallback_tries_strategies_in_order_and_stops_at_the_first_success() {
+        let calls = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let fallback = FallbackPayment::new(vec![
+            Box::new(RecordingStrategy { name: "first", succeeds: false, calls: calls.clone() }),
+            Box::new(RecordingStrategy { name: "second", succeeds: true, calls: calls.clone() }),
+            Box::new(RecordingStrategy { name: "third", succeeds: true, calls: calls.clone() }),
+        ]);
+        let receipt = fallback.pay(Money::eur(1_000)).unwrap();
+        assert_eq!(receipt.strategy, "second");
+        assert_eq!(*calls.borrow(), vec!["first", "second"]);
+    }
+
+    #[test]
+    fn fallback_aggregates_every_failure_when_all_strategies_decline() {
+        let credit_card = CreditCard { network: "Visa".to_string(), requires_3ds: false, decline_above: Some(Money::eur(1_000)) };
+        let fallback = FallbackPayment::new(vec![
+            Box::new(credit_card),
+            Box::new(Paypal { account_email: Some("not-an-email".to_string()), sandbox: false }),
+        ]);
+        let err = fallback.pay(Money::eur(10_000)).err().unwrap();
+        match err {
+            PaymentError::AllFailed(failures) => assert_eq!(failures.len(), 2),
+            other => panic!("expected AllFailed, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn fallback_falls_through_from_a_declined_card_to_paypal() {
+        let fallback = FallbackPayment::new(vec![
+            Box::new(CreditCard { network: "Visa".to_string(), requires_3ds: false, decline_above: Some(Money::eur(5_000)) }),
+            Box::new(Paypal { account_email: Some("payer@example.com".to_string()), sandbox: false }),
+        ]);
+        let receipt = fallback.pay(Money::eur(10_000)).unwrap();
+        assert_eq!(receipt.strategy, "PayPal");
+    }
+
+    /// The canonical "mock the strategy" recipe: records every amount it's called with, in order,
+    /// and hands back a scripted result instead of computing one - lets a test assert exactly what
+    /// `PaymentContext::process` forwarded to its strategy without depending on a real one's pricing.
+    struct MockPayment {
+        amounts: std::rc::Rc<std::cell::RefCell<Vec<Money>>>,
+        scripted_result: Result<Receipt, PaymentError>,
+    }
+    impl PaymentStrategy for MockPayment {
+        fn fee(&self, amount: Money) -> Money {
+            Money::new(0, amount.currency)
+        }
+
+        fn pay(&self, amount: Money) -> Result<Receipt, PaymentError> {
+            self.amounts.borrow_mut().push(amount);
+            self.scripted_result.clone()
+        }
+    }
+
+    #[test]
+    fn payment_context_forwards_the_exact_amount_to_the_strategy() {
+        let amounts = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let receipt = Receipt { strategy: "Mock", amount: Money::eur(1_250), fee: Money::eur(0), transaction_id: "MOCK-1".to_string(), attempts: 1, original_amount: None };
+        let mock = MockPayment { amounts: amounts.clone(), scripted_result: Ok(receipt) };
+        let context = PaymentContext::new(Box::new(mock));
+
+        context.process(Money::eur(1_250)).unwrap();
+
+        assert_eq!(*amounts.borrow(), vec![Money::eur(1_250)]);
+    }
+
+    #[test]
+    fn payment_context_calls_the_strategy_exactly_once() {
+        let amounts = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let receipt = Receipt { strategy: "Mock", amount: Money::eur(100), fee: Money::eur(0), transaction_id: "MOCK-1".to_string(), attempts: 1, original_amount: None };
+        let mock = MockPayment { amounts: amounts.clone(), scripted_result: Ok(receipt) };
+        let context = PaymentContext::new(Box::new(mock));
+
+        context.process(Money::eur(100)).unwrap();
+
+        assert_eq!(amounts.borrow().len(), 1);
+    }
+
+    #[test]
+    fn payment_context_propagates_a_scripted_error_untouched() {
+        let amounts = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let scripted_error = PaymentError::Declined("mock declines on purpose".to_string());
+        let mock = MockPayment { amounts, scripted_result: Err(scripted_error.clone()) };
+        let context = PaymentContext::new(Box::new(mock));
+
+        let err = context.process(Money::eur(9_900)).unwrap_err();
+
+        assert_eq!(err, scripted_error);
+    }
+
+    #[test]
+    fn split_payment_divides_a_non_round_amount_across_three_legs_without_losing_a_cent() {
+        let split = SplitPayment::new(vec![
+            (Box::new(visa()) as Box<dyn PaymentStrategy>, 1.0 / 3.0),
+            (Box::new(paypal_without_email()), 1.0 / 3.0),
+            (Box::new(visa()), 1.0 / 3.0),
+        ]);
+
+        let receipt = split.pay(Money::eur(10_001)).unwrap();
+
+        assert_eq!(receipt.strategy, "Split Payment");
+        assert_eq!(receipt.amount, Money::eur(10_001));
+        assert_eq!(receipt.transaction_id.split('+').count(), 3);
+    }
+
+    #[test]
+    fn split_payment_rejects_fractions_that_dont_sum_to_one() {
+        let split = SplitPayment::new(vec![
+            (Box::new(visa()) as Box<dyn PaymentStrategy>, 0.5),
+            (Box::new(paypal_without_email()), 0.2),
+        ]);
+
+        let err = split.pay(Money::eur(10_000)).err().unwrap();
+
+        assert_eq!(err, PaymentError::FractionsDoNotSumToOne { sum: 0.7 });
+    }
+
+    #[test]
+    fn bank_transfer_charges_a_flat_fee_regardless_of_amount() {
+        let small = BankTransfer.pay(Money::eur(1_000)).unwrap();
+        let large = BankTransfer.pay(Money::eur(1_000_000)).unwrap();
+        assert_eq!(small.fee, Money::eur(50));
+        assert_eq!(large.fee, Money::eur(50));
+    }
+
+    #[test]
+    fn registry_creates_a_strategy_from_a_registered_name() {
+        let mut registry = StrategyRegistry::new();
+        registry.register("bank_transfer", Box::new(|| Box::new(BankTransfer)));
+
+        let strategy = registry.create("bank_transfer").unwrap();
+
+        assert!(strategy.pay(Money::eur(1_000)).is_ok());
+    }
+
+    #[test]
+    fn registry_returns_none_for_a_missing_name() {
+        let registry = StrategyRegistry::new();
+        assert!(registry.create("bitcoin").is_none());
+    }
+
+    #[test]
+    fn registering_the_same_name_twice_lets_the_later_registration_win() {
+        let mut registry = StrategyRegistry::new();
+        registry.register("provider", Box::new(|| Box::new(visa())));
+        registry.register("provider", Box::new(|| Box::new(BankTransfer)));
+
+        let receipt = registry.create("provider").unwrap().pay(Money::eur(1_000)).unwrap();
+
+        assert_eq!(receipt.strategy, "Bank Transfer");
+    }
+
+    #[test]
+    fn static_and_dynamic_dispatch_agree_on_strategy_name_amount_and_fee() {
+        let dyn_context = PaymentContext::new(Box::new(visa()));
+        let generic_context = GenericPaymentContext::new(visa());
+
+        let dyn_receipt = dyn_context.process(Money::eur(10_000)).unwrap();
+        let generic_receipt = generic_context.process(Money::eur(10_000)).unwrap();
+
+        assert_eq!(dyn_receipt.strategy, generic_receipt.strategy);
+        assert_eq!(dyn_receipt.amount, generic_receipt.amount);
+        assert_eq!(dyn_receipt.fee, generic_receipt.fee);
+    }
+
+    #[test]
+    fn pay_all_processes_a_heterogeneous_mix_of_strategies() {
+        let strategies: Vec<Box<dyn PaymentStrategy>> = vec![Box::new(visa()), Box::new(paypal_without_email()), Box::new(BankTransfer)];
+
+        let results = pay_all(&strategies, Money::eur(1_000));
+
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|result| result.is_ok()));
+    }
+
+    #[test]
+    fn retrying_strategy_succeeds_after_the_flaky_mock_stops_declining() {
+        let flaky = FlakyMock { fails_before_success: std::cell::Cell::new(2) };
+        let retrying = RetryingStrategy::new(flaky, 5, Box::new(|_attempt| {}));
+
+        let receipt = retrying.pay(Money::eur(1_000)).unwrap();
+
+        assert_eq!(receipt.attempts, 3);
+    }
+
+    #[test]
+    fn retrying_strategy_returns_the_last_error_once_attempts_are_exhausted() {
+        let flaky = FlakyMock { fails_before_success: std::cell::Cell::new(10) };
+        let retrying = RetryingStrategy::new(flaky, 3, Box::new(|_attempt| {}));
+
+        let err = retrying.pay(Money::eur(1_000)).unwrap_err();
+
+        assert_eq!(err, PaymentError::Declined("flaky provider is still warming up".to_string()));
+    }
+
+    #[test]
+    fn retrying_strategy_does_not_retry_a_non_retryable_error() {
+        let backoff_calls = std::rc::Rc::new(std::cell::RefCell::new(0));
+        let calls_for_closure = backoff_calls.clone();
+        let retrying = RetryingStrategy::new(visa(), 5, Box::new(move |_attempt| *calls_for_closure.borrow_mut() += 1));
+
+        let err = retrying.pay(Money::eur(0)).unwrap_err();
+
+        assert_eq!(err, PaymentError::AmountNotPositive(Money::eur(0)));
+        assert_eq!(*backoff_calls.borrow(), 0);
+    }
+
+    /// Records every `(strategy, duration)` pair it's given - enough of a `MetricsSink` to let a
+    /// test assert `TimedStrategy` reports once per call without printing anything.
+    struct RecordingMetrics {
+        records: std::rc::Rc<std::cell::RefCell<Vec<(&'static str, Duration)>>>,
+    }
+    impl MetricsSink for RecordingMetrics {
+        fn record(&self, strategy: &'static str, duration: Duration) {
+            self.records.borrow_mut().push((strategy, duration));
+        }
+    }
+
+    #[test]
+    fn timed_strategy_reports_one_record_per_call_using_a_fake_clock() {
+        let records = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let tick = std::cell::Cell::new(0u64);
+        let fixed_start = Instant::now();
+        let timed = TimedStrategy::with_clock(
+            visa(),
+            Box::new(RecordingMetrics { records: records.clone() }),
+            Box::new(move || {
+                tick.set(tick.get() + 1);
+                fixed_start + Duration::from_millis(tick.get())
+            }),
+        );
+
+        timed.pay(Money::eur(1_000)).unwrap();
+        timed.pay(Money::eur(2_000)).unwrap();
+
+        let recorded = records.borrow();
+        assert_eq!(recorded.len(), 2);
+        assert_eq!(recorded[0].0, "Credit Card");
+        assert_eq!(recorded[0].1, Duration::from_millis(1));
+    }
+
+    #[test]
+    fn timed_strategy_reports_even_when_the_inner_call_fails() {
+        let records = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let timed = TimedStrategy::with_clock(visa(), Box::new(RecordingMetrics { records: records.clone() }), Box::new(Instant::now));
+
+        let err = timed.pay(Money::eur(0)).unwrap_err();
+
+        assert_eq!(err, PaymentError::AmountNotPositive(Money::eur(0)));
+        assert_eq!(records.borrow().len(), 1);
+        assert_eq!(records.borrow()[0].0, "failed payment");
+    }
+
+    #[test]
+    fn timed_strategy_stacks_on_top_of_retrying_strategy() {
+        let flaky = FlakyMock { fails_before_success: std::cell::Cell::new(1) };
+        let retrying = RetryingStrategy::new(flaky, 3, Box::new(|_attempt| {}));
+        let records = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let timed = TimedStrategy::with_clock(retrying, Box::new(RecordingMetrics { records: records.clone() }), Box::new(Instant::now));
+
+        let receipt = timed.pay(Money::eur(1_000)).unwrap();
+
+        assert_eq!(receipt.attempts, 2);
+        assert_eq!(records.borrow().len(), 1);
+        assert_eq!(records.borrow()[0].0, "Flaky Mock");
+    }
+
+    #[test]
+    fn routing_strategy_picks_the_first_matching_route() {
+        let router = RoutingStrategy::new(vec![
+            (Box::new(|request: &PaymentRequest| request.amount.cents < 3_000), Box::new(Wallet) as Box<dyn PaymentStrategy>),
+            (Box::new(|_request: &PaymentRequest| true), Box::new(visa())),
+        ]);
+
+        let wallet_receipt = router.pay(Money::eur(1_000)).unwrap();
+        let card_receipt = router.pay(Money::eur(10_000)).unwrap();
+
+        assert_eq!(wallet_receipt.strategy, "Wallet");
+        assert_eq!(card_receipt.strategy, "Credit Card");
+    }
+
+    #[test]
+    fn routing_strategy_order_matters_an_earlier_catch_all_shadows_a_later_route() {
+        let router = RoutingStrategy::new(vec![
+            (Box::new(|_request: &PaymentRequest| true), Box::new(visa()) as Box<dyn PaymentStrategy>),
+            (Box::new(|request: &PaymentRequest| request.amount.cents < 3_000), Box::new(Wallet)),
+        ]);
+
+        let receipt = router.pay(Money::eur(1_000)).unwrap();
+
+        assert_eq!(receipt.strategy, "Credit Card");
+    }
+
+    #[test]
+    fn routing_strategy_returns_no_route_for_when_nothing_matches() {
+        let router = RoutingStrategy::new(vec![(Box::new(|request: &PaymentRequest| request.amount.cents < 3_000) as Box<dyn Fn(&PaymentRequest) -> bool>, Box::new(Wallet) as Box<dyn PaymentStrategy>)]);
+
+        let err = router.pay(Money::eur(10_000)).unwrap_err();
+
+        assert_eq!(err, PaymentError::NoRouteFor(Money::eur(10_000)));
+    }
+
+    #[test]
+    fn paypal_refund_returns_the_full_amount() {
+        let paypal = paypal_without_email();
+        let receipt = paypal.pay(Money::eur(10_000)).unwrap();
+
+        let refund = paypal.refund(&receipt).unwrap();
+
+        assert_eq!(refund.strategy, "PayPal");
+        assert_eq!(refund.amount, Money::eur(10_000));
+    }
+
+    #[test]
+    fn credit_card_refund_withholds_the_flat_network_fee() {
+        let card = visa();
+        let receipt = card.pay(Money::eur(10_000)).unwrap();
+
+        let refund = card.refund(&receipt).unwrap();
+
+        assert_eq!(refund.strategy, "Credit Card");
+        assert_eq!(refund.amount, Money::eur(9_975));
+    }
+
+    #[test]
+    fn refunding_a_receipt_from_a_different_strategy_is_rejected() {
+        let paypal_receipt = paypal_without_email().pay(Money::eur(10_000)).unwrap();
+
+        let err = visa().refund(&paypal_receipt).unwrap_err();
+
+        assert_eq!(err, PaymentError::StrategyMismatch { expected: "Credit Card", actual: "PayPal" });
+    }
+
+    #[test]
+    fn a_strategy_without_a_refund_override_reports_it_as_unsupported() {
+        let receipt = BankTransfer.pay(Money::eur(2_500)).unwrap();
+
+        let err = BankTransfer.refund(&receipt).unwrap_err();
+
+        assert_eq!(err, PaymentError::UnsupportedRefund("Bank Transfer"));
+    }
+
+    #[test]
+    fn payment_context_delegates_refund_to_its_strategy() {
+        let context = PaymentContext::new(Box::new(paypal_without_email()));
+        let receipt = context.process(Money::eur(5_000)).unwrap();
+
+        let refund = context.refund(&receipt).unwrap();
+
+        assert_eq!(refund.amount, Money::eur(5_000));
+    }
+
+    #[test]
+    fn converting_strategy_rounds_the_converted_amount_and_keeps_the_original() {
+        let rates = StaticRates::new(vec![((Currency::Usd, Currency::Eur), 0.925)]);
+        let converting = ConvertingStrategy::new(visa(), Currency::Eur, Box::new(rates));
+
+        let receipt = converting.pay(Money::new(10_000, Currency::Usd)).unwrap();
+
+        // 10_000 * 0.925 = 9_250 exactly, no rounding tie to worry about here.
+        assert_eq!(receipt.amount, Money::eur(9_250));
+        assert_eq!(receipt.original_amount, Some(Money::new(10_000, Currency::Usd)));
+    }
+
+    #[test]
+    fn converting_strategy_breaks_a_rounding_tie_away_from_zero() {
+        let rates = StaticRates::new(vec![((Currency::Usd, Currency::Eur), 0.825)]);
+        let converting = ConvertingStrategy::new(visa(), Currency::Eur, Box::new(rates));
+
+        // 100 * 0.825 = 82.5 cents exactly - a genuine tie, rounded up to 83.
+        let receipt = converting.pay(Money::new(100, Currency::Usd)).unwrap();
+
+        assert_eq!(receipt.amount, Money::eur(83));
+    }
+
+    #[test]
+    fn converting_strategy_skips_conversion_when_currencies_already_match() {
+        let rates = StaticRates::new(vec![]);
+        let converting = ConvertingStrategy::new(visa(), Currency::Eur, Box::new(rates));
+
+        let receipt = converting.pay(Money::eur(10_000)).unwrap();
+
+        assert_eq!(receipt.amount, Money::eur(10_000));
+        assert_eq!(receipt.original_amount, Some(Money::eur(10_000)));
+    }
+
+    #[test]
+    fn converting_strategy_surfaces_a_missing_rate() {
+        let rates = StaticRates::new(vec![]);
+        let converting = ConvertingStrategy::new(visa(), Currency::Eur, Box::new(rates));
+
+        let err = converting.pay(Money::new(10_000, Currency::Usd)).unwrap_err();
+
+        assert_eq!(err, PaymentError::NoRate(Currency::Usd, Currency::Eur));
+    }
+
+    /// Counts how many times `pay` is actually called - lets a test assert `IdempotentStrategy`
+    /// only charges the inner strategy once, no matter how many times a key is replayed.
+    struct CountingMock {
+        calls: std::rc::Rc<std::cell::Cell<u32>>,
+    }
+    impl PaymentStrategy for CountingMock {
+        fn fee(&self, amount: Money) -> Money {
+            Money::new(0, amount.currency)
+        }
+
+        fn pay(&self, amount: Money) -> Result<Receipt, PaymentError> {
+            self.calls.set(self.calls.get() + 1);
+            Ok(Receipt { strategy: "Counting Mock", amount, fee: Money::new(0, amount.currency), transaction_id: next_transaction_id("CM"), attempts: 1, original_amount: None })
+        }
+    }
+
+    #[test]
+    fn idempotent_strategy_charges_the_inner_strategy_exactly_once_for_a_replayed_key() {
+        let calls = std::rc::Rc::new(std::cell::Cell::new(0));
+        let idempotent = IdempotentStrategy::new(CountingMock { calls: calls.clone() });
+        let key = IdempotencyKey("double-click".to_string());
+
+        let first = idempotent.pay_with_key(key.clone(), Money::eur(1_000)).unwrap();
+        let second = idempotent.pay_with_key(key, Money::eur(1_000)).unwrap();
+
+        assert_eq!(calls.get(), 1);
+        assert_eq!(first.transaction_id, second.transaction_id);
+    }
+
+    #[test]
+    fn idempotent_strategy_rejects_the_same_key_with_a_different_amount() {
+        let idempotent = IdempotentStrategy::new(visa());
+        let key = IdempotencyKey("checkout-1".to_string());
+        idempotent.pay_with_key(key.clone(), Money::eur(1_000)).unwrap();
+
+        let err = idempotent.pay_with_key(key.clone(), Money::eur(2_000)).unwrap_err();
+
+        assert_eq!(err, PaymentError::KeyReuseMismatch { key, original: Money::eur(1_000), attempted: Money::eur(2_000) });
+    }
+
+    #[test]
+    fn idempotent_strategy_treats_different_keys_independently() {
+        let calls = std::rc::Rc::new(std::cell::Cell::new(0));
+        let idempotent = IdempotentStrategy::new(CountingMock { calls: calls.clone() });
+
+        idempotent.pay_with_key(IdempotencyKey("a".to_string()), Money::eur(1_000)).unwrap();
+        idempotent.pay_with_key(IdempotencyKey("b".to_string()), Money::eur(1_000)).unwrap();
+
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn credit_card_builder_rejects_an_empty_network() {
+        let err = CreditCardBuilder::new("").build().unwrap_err();
+        assert_eq!(err, CreditCardBuildError::EmptyNetwork);
+    }
+
+    #[test]
+    fn credit_card_builder_defaults_to_no_3ds_surcharge_and_no_decline_threshold() {
+        let card = CreditCardBuilder::new("Visa").build().unwrap();
+        assert_eq!(card.fee(Money::eur(10_000)), visa().fee(Money::eur(10_000)));
+        assert!(card.pay(Money::eur(1_000_000)).is_ok());
+    }
+
+    #[test]
+    fn credit_card_builder_requires_3ds_adds_a_flat_surcharge_to_the_fee() {
+        let without_3ds = CreditCardBuilder::new("Visa").build().unwrap();
+        let with_3ds = CreditCardBuilder::new("Visa").requires_3ds(true).build().unwrap();
+
+        let fee_without = without_3ds.fee(Money::eur(10_000));
+        let fee_with = with_3ds.fee(Money::eur(10_000));
+
+        assert_eq!(fee_with.cents - fee_without.cents, 10);
+    }
+
+    #[test]
+    fn credit_card_builder_decline_above_triggers_declined() {
+        let card = CreditCardBuilder::new("Visa").decline_above(Money::eur(20_000)).build().unwrap();
+
+        assert!(card.pay(Money::eur(10_000)).is_ok());
+        let err = card.pay(Money::eur(20_001)).unwrap_err();
+        assert!(matches!(err, PaymentError::Declined(_)));
+    }
+
+    #[test]
+    fn paypal_builder_rejects_a_malformed_account_email() {
+        let err = PaypalBuilder::new().account_email("not-an-email").build().unwrap_err();
+        assert_eq!(err, PaypalBuildError::InvalidEmail("not-an-email".to_string()));
+    }
+
+    #[test]
+    fn paypal_builder_sandbox_uses_a_distinct_transaction_id_prefix() {
+        let live = PaypalBuilder::new().build().unwrap();
+        let sandbox = PaypalBuilder::new().sandbox(true).build().unwrap();
+
+        let live_receipt = live.pay(Money::eur(1_000)).unwrap();
+        let sandbox_receipt = sandbox.pay(Money::eur(1_000)).unwrap();
+
+        assert!(live_receipt.transaction_id.starts_with("PP-"));
+        assert!(sandbox_receipt.transaction_id.starts_with("PPTEST-"));
+    }
+
+    #[test]
+    fn payment_context_builder_builds_a_working_context_from_a_boxed_strategy() {
+        let context = PaymentContextBuilder::new(Box::new(visa())).build();
+        let receipt = context.process(Money::eur(1_000)).unwrap();
+        assert_eq!(receipt.strategy, "Credit Card");
+    }
+
+    #[test]
+    fn payment_context_builder_with_retry_retries_a_flaky_strategy() {
+        let context = PaymentContextBuilder::new(Box::new(FlakyMock { fails_before_success: std::cell::Cell::new(2) })).with_retry(3, |_attempt| {}).build();
+
+        let receipt = context.process(Money::eur(1_000)).unwrap();
+
+        assert_eq!(receipt.attempts, 3);
+    }
+
+    #[test]
+    fn payment_context_builder_with_conversion_converts_before_charging() {
+        let rates = StaticRates::new(vec![((Currency::Usd, Currency::Eur), 0.5)]);
+        let context = PaymentContextBuilder::new(Box::new(visa())).with_conversion(Currency::Eur, Box::new(rates)).build();
+
+        let receipt = context.process(Money::new(10_000, Currency::Usd)).unwrap();
+
+        assert_eq!(receipt.amount, Money::eur(5_000));
+        assert_eq!(receipt.original_amount, Some(Money::new(10_000, Currency::Usd)));
+    }
+
+    #[test]
+    fn payment_context_builder_stacks_decorators_built_from_builder_configured_strategies() {
+        let card = CreditCardBuilder::new("Amex").decline_above(Money::eur(20_000)).build().unwrap();
+        let context = PaymentContextBuilder::new(Box::new(card)).with_retry(1, |_attempt| {}).build();
+
+        let err = context.process(Money::eur(20_001)).unwrap_err();
+
+        assert!(matches!(err, PaymentError::Declined(_)));
+    }
+}
+
+/// Property-style fuzzing of `Receipt`'s invariants across every strategy this file registers,
+/// using `rand` instead of `proptest` (see `tests/property.rs` for the latter's house style) -
+/// the same trade-off `01_builder_fixtures.rs` makes, since this is a `#[cfg(test)]` module inside
+/// an example rather than a standalone fuzz harness. Gated on the `rand` feature so
+/// `cargo check --no-default-features` (and every other feature combination) still compiles.
+#[cfg(all(test, feature = "rand"))]
+mod strategy_invariants {
+    use super::*;
+    use rand::Rng;
+
+    const RANDOM_AMOUNT_COUNT: usize = 10_000;
+    /// €10,000.00 in cents - the upper bound the request asked for.
+    const MAX_RANDOM_CENTS: i64 = 1_000_000;
+
+    /// Every strategy this file defines, configured plainly enough that none of them reject an
+    /// in-range amount outright.
+    fn strategies() -> Vec<Box<dyn PaymentStrategy>> {
+        vec![
+            Box::new(CreditCard { network: "Visa".to_string(), requires_3ds: false, decline_above: None }),
+            Box::new(CreditCard { network: "Amex".to_string(), requires_3ds: true, decline_above: None }),
+            Box::new(Paypal { account_email: None, sandbox: false }),
+            Box::new(Paypal { account_email: None, sandbox: true }),
+            Box::new(BankTransfer),
+            Box::new(Wallet),
+        ]
+    }
+
+    /// Pays `amount` through `strategy` and checks the invariants every strategy must uphold: a
+    /// non-negative fee, a total that's exactly `amount + fee` (computed through the same checked
+    /// arithmetic `Receipt::total` uses, so it can never silently overflow), and - for strategies
+    /// that support refunds - a refund that never hands back more than was charged. A decline (e.g.
+    /// `AmountNotPositive` for a zero amount) isn't a violation; only a panic or a broken invariant
+    /// on a payment that actually succeeded is.
+    fn assert_receipt_invariants(strategy: &dyn PaymentStrategy, amount: Money) {
+        let Ok(receipt) = strategy.pay(amount) else { return };
+
+        assert!(receipt.fee.cents >= 0, "{} charged a negative fee: {:?}", receipt.strategy, receipt.fee);
+
+        match amount.checked_add(receipt.fee) {
+            Ok(expected_total) => assert_eq!(receipt.total().unwrap(), expected_total),
+            Err(PaymentError::AmountOverflow) => assert_eq!(receipt.total(), Err(PaymentError::AmountOverflow)),
+            Err(err) => panic!("unexpected error totalling {} + {}: {err}", amount, receipt.fee),
+        }
+
+        match strategy.refund(&receipt) {
+            Ok(refund) => assert!(refund.amount.cents <= receipt.amount.cents, "{} refunded more than it charged", receipt.strategy),
+            Err(PaymentError::UnsupportedRefund(_)) => {}
+            Err(err) => panic!("unexpected refund error for {}: {err}", receipt.strategy),
+        }
+    }
+
+    #[test]
+    fn random_amounts_in_1c_to_10keur_never_violate_receipt_invariants() {
+        let mut rng = rand::rng();
+        for _ in 0..RANDOM_AMOUNT_COUNT {
+            let amount = Money::eur(rng.random_range(1..=MAX_RANDOM_CENTS));
+            for strategy in strategies() {
+                assert_receipt_invariants(strategy.as_ref(), amount);
+            }
+        }
+    }
+
+    #[test]
+    fn edge_amounts_never_panic() {
+        for cents in [1, i64::MAX] {
+            let amount = Money::eur(cents);
+            for strategy in strategies() {
+                assert_receipt_invariants(strategy.as_ref(), amount);
+            }
+        }
+    }
 }
@@ -0,0 +1,151 @@
+// cargo run --example 02_strategy_async --features async
+
+// Async sibling of 02_strategy.rs: `PaymentStrategy::pay` there is synchronous, but a real payment
+// gateway is a network call. `async fn` in a trait isn't object-safe, so `AsyncPaymentStrategy`
+// returns a boxed, pinned future by hand instead of pulling in the `async-trait` crate for one
+// example - the same trade-off `pay`'s doc comment on the sync version doesn't have to make.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Money(pub f64);
+
+impl std::fmt::Display for Money {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "€{:.2}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Receipt {
+    pub strategy: &'static str,
+    pub amount: Money,
+    pub fee: Money,
+    pub transaction_id: String,
+}
+
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum PaymentError {
+    #[error("amount must be positive, got {0}")]
+    AmountNotPositive(Money),
+    #[error("payment declined: {0}")]
+    Declined(String),
+}
+
+static NEXT_TRANSACTION_ID: AtomicU64 = AtomicU64::new(1);
+
+fn next_transaction_id(prefix: &str) -> String {
+    format!("{prefix}-{}", NEXT_TRANSACTION_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+/// `pay` returns a boxed, pinned future instead of being declared `async fn` so that
+/// `Box<dyn AsyncPaymentStrategy>` stays a valid, object-safe type - the async counterpart of
+/// `02_strategy.rs`'s `Box<dyn PaymentStrategy>`.
+trait AsyncPaymentStrategy {
+    fn pay(&self, amount: Money) -> Pin<Box<dyn Future<Output = Result<Receipt, PaymentError>> + Send + '_>>;
+}
+
+/// Charges the usual 2.9% + €0.30 PayPal fee, after simulating the round trip to PayPal's servers
+/// with `tokio::time::sleep` so the example has something worth running concurrently.
+struct Paypal {
+    account_email: Option<String>,
+    latency: Duration,
+}
+impl AsyncPaymentStrategy for Paypal {
+    fn pay(&self, amount: Money) -> Pin<Box<dyn Future<Output = Result<Receipt, PaymentError>> + Send + '_>> {
+        Box::pin(async move {
+            if amount.0 <= 0.0 {
+                return Err(PaymentError::AmountNotPositive(amount));
+            }
+            if let Some(email) = &self.account_email
+                && !email.contains('@')
+            {
+                return Err(PaymentError::Declined(format!("invalid PayPal account email: {email}")));
+            }
+            tokio::time::sleep(self.latency).await;
+            let fee = Money(amount.0 * 0.029 + 0.30);
+            Ok(Receipt { strategy: "PayPal", amount, fee, transaction_id: next_transaction_id("PP") })
+        })
+    }
+}
+
+struct AsyncPaymentContext {
+    strategy: Box<dyn AsyncPaymentStrategy>,
+}
+impl AsyncPaymentContext {
+    fn new(strategy: Box<dyn AsyncPaymentStrategy>) -> Self {
+        Self { strategy }
+    }
+
+    async fn process(&self, amount: Money) -> Result<Receipt, PaymentError> {
+        self.strategy.pay(amount).await
+    }
+}
+
+// Two payments, each simulating 200ms of network latency. Run one after the other they'd take
+// ~400ms; run concurrently through tokio::join! they take ~200ms - the point of the example.
+#[tokio::main]
+async fn main() {
+    let first = AsyncPaymentContext::new(Box::new(Paypal { account_email: Some("alice@example.com".to_string()), latency: Duration::from_millis(200) }));
+    let second = AsyncPaymentContext::new(Box::new(Paypal { account_email: Some("bob@example.com".to_string()), latency: Duration::from_millis(200) }));
+
+    let start = std::time::Instant::now();
+    let (first_result, second_result) = tokio::join!(first.process(Money(100.0)), second.process(Money(50.0)));
+    let elapsed = start.elapsed();
+
+    match first_result {
+        Ok(receipt) => println!("Paid {} via {} (fee {}, tx {})", receipt.amount, receipt.strategy, receipt.fee, receipt.transaction_id),
+        Err(err) => println!("First payment failed: {err}"),
+    }
+    match second_result {
+        Ok(receipt) => println!("Paid {} via {} (fee {}, tx {})", receipt.amount, receipt.strategy, receipt.fee, receipt.transaction_id),
+        Err(err) => println!("Second payment failed: {err}"),
+    }
+    println!("Both payments settled concurrently in {elapsed:?}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn paypal_charges_a_percentage_plus_a_fixed_fee() {
+        let paypal = Paypal { account_email: Some("alice@example.com".to_string()), latency: Duration::ZERO };
+        let receipt = paypal.pay(Money(100.0)).await.unwrap();
+        assert_eq!(receipt.strategy, "PayPal");
+        assert!((receipt.fee.0 - 3.2).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn paypal_declines_a_malformed_account_email() {
+        let paypal = Paypal { account_email: Some("not-an-email".to_string()), latency: Duration::ZERO };
+        let err = paypal.pay(Money(10.0)).await.unwrap_err();
+        assert_eq!(err, PaymentError::Declined("invalid PayPal account email: not-an-email".to_string()));
+    }
+
+    #[tokio::test]
+    async fn negative_amount_is_rejected() {
+        let paypal = Paypal { account_email: None, latency: Duration::ZERO };
+        let err = paypal.pay(Money(-5.0)).await.unwrap_err();
+        assert_eq!(err, PaymentError::AmountNotPositive(Money(-5.0)));
+    }
+
+    #[tokio::test]
+    async fn context_propagates_the_strategys_result() {
+        let context = AsyncPaymentContext::new(Box::new(Paypal { account_email: None, latency: Duration::ZERO }));
+        assert!(context.process(Money(10.0)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn two_payments_run_concurrently_faster_than_sequentially() {
+        let first = AsyncPaymentContext::new(Box::new(Paypal { account_email: None, latency: Duration::from_millis(50) }));
+        let second = AsyncPaymentContext::new(Box::new(Paypal { account_email: None, latency: Duration::from_millis(50) }));
+        let start = std::time::Instant::now();
+        let _ = tokio::join!(first.process(Money(10.0)), second.process(Money(10.0)));
+        assert!(start.elapsed() < Duration::from_millis(90));
+    }
+}
@@ -0,0 +1,132 @@
+// cargo run --example 02_strategy_enum
+
+// Enum-dispatch sibling of 02_strategy.rs: the Box<dyn PaymentStrategy> dynamic-dispatch version
+// lives there, which is worth comparing against a `PaymentMethod` enum matched in a single `pay`
+// method, the same "trait objects vs enum-match" comparison 04-07_state_machine_*.rs already do
+// for FSMs. benches/04_strategy.rs puts a number on the difference.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Money(pub f64);
+
+impl std::fmt::Display for Money {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "€{:.2}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Receipt {
+    pub strategy: &'static str,
+    pub amount: Money,
+    pub fee: Money,
+    pub transaction_id: String,
+}
+
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum PaymentError {
+    #[error("amount must be positive, got {0}")]
+    AmountNotPositive(Money),
+    #[error("payment declined: {0}")]
+    Declined(String),
+}
+
+static NEXT_TRANSACTION_ID: AtomicU64 = AtomicU64::new(1);
+
+fn next_transaction_id(prefix: &str) -> String {
+    format!("{prefix}-{}", NEXT_TRANSACTION_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+/// One `match` replaces `02_strategy.rs`'s `Box<dyn PaymentStrategy>`: every variant's fee
+/// calculation lives in a single `pay` method instead of a separate `impl` block per strategy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentMethod {
+    CreditCard,
+    Paypal,
+    Sepa,
+}
+
+impl PaymentMethod {
+    fn name(self) -> &'static str {
+        match self {
+            PaymentMethod::CreditCard => "Credit Card",
+            PaymentMethod::Paypal => "PayPal",
+            PaymentMethod::Sepa => "SEPA",
+        }
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            PaymentMethod::CreditCard => "CC",
+            PaymentMethod::Paypal => "PP",
+            PaymentMethod::Sepa => "SEPA",
+        }
+    }
+
+    pub fn pay(self, amount: Money) -> Result<Receipt, PaymentError> {
+        if amount.0 <= 0.0 {
+            return Err(PaymentError::AmountNotPositive(amount));
+        }
+        let fee = match self {
+            PaymentMethod::CreditCard => Money(amount.0 * 0.02),
+            PaymentMethod::Paypal => Money(amount.0 * 0.029 + 0.30),
+            // Flat fee, no percentage - the usual SEPA transfer pricing.
+            PaymentMethod::Sepa => Money(0.35),
+        };
+        Ok(Receipt { strategy: self.name(), amount, fee, transaction_id: next_transaction_id(self.prefix()) })
+    }
+}
+
+fn main() {
+    for method in [PaymentMethod::CreditCard, PaymentMethod::Paypal, PaymentMethod::Sepa] {
+        match method.pay(Money(100.0)) {
+            Ok(receipt) => println!("Paid {} using {} (fee {}, tx {})", receipt.amount, receipt.strategy, receipt.fee, receipt.transaction_id),
+            Err(err) => println!("{} payment failed: {err}", method.name()),
+        }
+    }
+
+    let err = PaymentMethod::Sepa.pay(Money(-5.0)).unwrap_err();
+    println!("Deliberately bad payment: {err}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credit_card_charges_a_two_percent_fee() {
+        let receipt = PaymentMethod::CreditCard.pay(Money(100.0)).unwrap();
+        assert!((receipt.fee.0 - 2.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn paypal_charges_a_percentage_plus_a_fixed_fee() {
+        let receipt = PaymentMethod::Paypal.pay(Money(100.0)).unwrap();
+        assert!((receipt.fee.0 - 3.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sepa_charges_a_flat_fee_regardless_of_amount() {
+        let small = PaymentMethod::Sepa.pay(Money(10.0)).unwrap();
+        let large = PaymentMethod::Sepa.pay(Money(10_000.0)).unwrap();
+        assert_eq!(small.fee, Money(0.35));
+        assert_eq!(large.fee, Money(0.35));
+    }
+
+    #[test]
+    fn negative_amount_is_rejected_by_every_variant() {
+        for method in [PaymentMethod::CreditCard, PaymentMethod::Paypal, PaymentMethod::Sepa] {
+            assert_eq!(method.pay(Money(-1.0)).unwrap_err(), PaymentError::AmountNotPositive(Money(-1.0)));
+        }
+    }
+
+    #[test]
+    fn transaction_ids_are_prefixed_by_method_and_unique_per_call() {
+        let first = PaymentMethod::Sepa.pay(Money(10.0)).unwrap();
+        let second = PaymentMethod::Sepa.pay(Money(10.0)).unwrap();
+        assert!(first.transaction_id.starts_with("SEPA-"));
+        assert_ne!(first.transaction_id, second.transaction_id);
+    }
+}
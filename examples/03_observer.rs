@@ -4,43 +4,698 @@
 // Great fit for GUI apps, event loops, and message brokers.
 // Using Rc<RefCell<...>> allows closures to be shared and mutated even if they're captured in an immutable environment - an idiomatic Rust trick for simulating dynamic callbacks.
 
-use std::cell::RefCell;
+// `Topic` and its closely-coupled support types (ids, unsubscribe, filtering, metrics, replay,
+// ...) now live in `src/observer.rs` so they can be unit-tested and reused outside this file; this
+// example is a thin consumer of them. `SyncTopic`, `TryTopic`, `QueueingTopic` and `EventBus`
+// below are distinct type families, not part of `Topic` itself, so they stay here.
+
+use design_patterns::observer::{CountingMetrics, Subscriber, SubscriptionId, Topic};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// `Rc<RefCell<...>>` isn't `Send`, so it can't cross a `std::thread::spawn` boundary - this is
+/// the `Arc<Mutex<...>>` sibling of `Topic` for callers (e.g. the tokio examples elsewhere in this
+/// crate) that need to publish from more than one thread.
+type SyncSubscriber<T> = Arc<Mutex<dyn FnMut(&T) + Send>>;
 
-type Subscriber = Rc<RefCell<dyn FnMut(String)>>;
-struct Topic {
-    subs: Vec<Subscriber>,
+struct SyncTopic<T> {
+    subs: Mutex<Vec<(SubscriptionId, SyncSubscriber<T>)>>,
+    next_id: AtomicU64,
 }
-impl Topic {
+impl<T> SyncTopic<T> {
     fn new() -> Self {
-        Topic { subs: vec![] }
+        SyncTopic { subs: Mutex::new(vec![]), next_id: AtomicU64::new(0) }
+    }
+
+    fn subscribe(&self, callback: SyncSubscriber<T>) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.subs.lock().unwrap().push((id, callback));
+        id
+    }
+
+    fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut subs = self.subs.lock().unwrap();
+        let len_before = subs.len();
+        subs.retain(|(sub_id, _)| *sub_id != id);
+        subs.len() != len_before
+    }
+
+    fn subscriber_count(&self) -> usize {
+        self.subs.lock().unwrap().len()
+    }
+
+    /// Clones the `Arc`s out of `subs` before invoking anything, then drops the `subs` lock.
+    /// A subscriber that calls `subscribe`/`unsubscribe` from within its own callback (or another
+    /// thread calling `publish` concurrently) only ever contends on the short-lived `subs` lock,
+    /// never on a lock held for the whole publish - so publishing can never deadlock against a
+    /// subscription change, in flight or not.
+    fn publish(&self, msg: &T) {
+        let callbacks: Vec<SyncSubscriber<T>> = self.subs.lock().unwrap().iter().map(|(_, cb)| cb.clone()).collect();
+        for cb in callbacks {
+            (cb.lock().unwrap())(msg);
+        }
+    }
+}
+
+/// Controls whether `TryTopic::publish` keeps delivering to later subscribers after one of them
+/// fails, or stops immediately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FailurePolicy {
+    ContinueOnError,
+    StopOnFirstError,
+}
+
+/// What a `TryTopic::publish` call did: how many subscribers it delivered to successfully, and
+/// which ones failed and with what error, in the order they were invoked.
+#[derive(Debug, PartialEq)]
+struct PublishReport<E> {
+    delivered: usize,
+    failures: Vec<(SubscriptionId, E)>,
+}
+
+type TrySubscriber<T, E> = Rc<RefCell<dyn FnMut(&T) -> Result<(), E>>>;
+
+/// `Topic`'s subscribers can't report failure - their callback is `FnMut(&T)`, with no return
+/// value to fail with. `TryTopic` is for the case where a subscriber genuinely can fail (writing
+/// to a socket, validating a payload) and the publisher needs to know which ones did, without a
+/// single failing subscriber silently swallowing delivery to the rest.
+struct TryTopic<T, E> {
+    subs: RefCell<Vec<(SubscriptionId, TrySubscriber<T, E>)>>,
+    next_id: Cell<u64>,
+    policy: FailurePolicy,
+}
+impl<T, E> TryTopic<T, E> {
+    fn new(policy: FailurePolicy) -> Self {
+        TryTopic { subs: RefCell::new(vec![]), next_id: Cell::new(0), policy }
     }
-    fn subscribe(&mut self, callback: Subscriber) {
-        self.subs.push(callback);
+
+    fn subscribe(&self, callback: TrySubscriber<T, E>) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        self.subs.borrow_mut().push((id, callback));
+        id
+    }
+
+    fn subscriber_count(&self) -> usize {
+        self.subs.borrow().len()
     }
-    fn publish(&mut self, msg: String) {
-        for sub in &self.subs {
-            sub.borrow_mut()(msg.clone());
+
+    /// A failing subscriber is recorded in `failures` but never prevents delivery to a later
+    /// subscriber under `ContinueOnError` - only `StopOnFirstError` does that, by design rather
+    /// than by accident.
+    fn publish(&self, msg: &T) -> PublishReport<E> {
+        let snapshot: Vec<(SubscriptionId, TrySubscriber<T, E>)> = self.subs.borrow().clone();
+        let mut delivered = 0;
+        let mut failures = Vec::new();
+        for (id, sub) in &snapshot {
+            match (sub.borrow_mut())(msg) {
+                Ok(()) => delivered += 1,
+                Err(err) => {
+                    failures.push((*id, err));
+                    if self.policy == FailurePolicy::StopOnFirstError {
+                        break;
+                    }
+                }
+            }
+        }
+        PublishReport { delivered, failures }
+    }
+}
+
+/// What a full mailbox does with an incoming message under `QueueingTopic::publish`, chosen
+/// per-subscriber at `subscribe` time rather than globally for the whole topic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OverflowPolicy {
+    /// Drop the message that just arrived; whatever was already queued is untouched.
+    DropNewest,
+    /// Drop the oldest queued message to make room for the one that just arrived.
+    DropOldest,
+    /// Keep the mailbox as-is and report this subscriber's id back from `publish` as rejected.
+    Error,
+}
+
+type QueueingSubscriber<T> = Rc<RefCell<dyn FnMut(&T)>>;
+
+/// One subscriber's bounded inbox: `publish` enqueues into `queue` (subject to `capacity` and
+/// `policy`) instead of invoking `callback` directly, and a later `drain` is what actually calls
+/// `callback` - the way a slow consumer behind a bounded channel works, without needing an async
+/// runtime to demonstrate it.
+struct Mailbox<T> {
+    queue: VecDeque<T>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    callback: QueueingSubscriber<T>,
+}
+
+/// `Topic`'s subscribers are invoked synchronously inside `publish`, so a slow one holds up every
+/// other subscriber (and the publisher) for as long as it takes to run. `QueueingTopic` instead
+/// gives each subscriber its own bounded `Mailbox`: `publish` only ever enqueues, and nothing runs
+/// a callback until `drain` is called - which is also where backpressure becomes visible, since a
+/// subscriber that never drains just keeps dropping (or rejecting) messages per its `OverflowPolicy`.
+struct QueueingTopic<T> {
+    subscribers: RefCell<Vec<(SubscriptionId, Mailbox<T>)>>,
+    next_id: Cell<u64>,
+}
+impl<T: Clone> QueueingTopic<T> {
+    fn new() -> Self {
+        QueueingTopic { subscribers: RefCell::new(vec![]), next_id: Cell::new(0) }
+    }
+
+    fn subscribe(&self, capacity: usize, policy: OverflowPolicy, callback: QueueingSubscriber<T>) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        self.subscribers.borrow_mut().push((id, Mailbox { queue: VecDeque::new(), capacity, policy, callback }));
+        id
+    }
+
+    /// Enqueues `msg` into every subscriber's mailbox, applying that subscriber's `OverflowPolicy`
+    /// if it's already at `capacity`. Returns the ids of subscribers whose `OverflowPolicy::Error`
+    /// rejected this message outright - `DropNewest` and `DropOldest` never show up here, since
+    /// from `publish`'s point of view they always "succeed" at making room.
+    fn publish(&self, msg: T) -> Vec<SubscriptionId> {
+        let mut rejected = Vec::new();
+        for (id, mailbox) in self.subscribers.borrow_mut().iter_mut() {
+            if mailbox.queue.len() < mailbox.capacity {
+                mailbox.queue.push_back(msg.clone());
+                continue;
+            }
+            match mailbox.policy {
+                OverflowPolicy::DropNewest => {}
+                OverflowPolicy::DropOldest => {
+                    mailbox.queue.pop_front();
+                    mailbox.queue.push_back(msg.clone());
+                }
+                OverflowPolicy::Error => rejected.push(*id),
+            }
+        }
+        rejected
+    }
+
+    /// Delivers every mailbox's queued messages, in order, to its own `callback` - each mailbox
+    /// ends up empty afterwards, ready to start filling again from the next `publish`.
+    fn drain(&self) {
+        for (_, mailbox) in self.subscribers.borrow_mut().iter_mut() {
+            while let Some(msg) = mailbox.queue.pop_front() {
+                mailbox.callback.borrow_mut()(&msg);
+            }
+        }
+    }
+}
+
+/// A single `Topic` broadcasts to everyone regardless of what the message is about; a real
+/// message broker routes by topic name instead. `EventBus` owns one `Topic` per name, created on
+/// first use, so callers never need to pre-declare a topic before subscribing or publishing to it.
+struct EventBus {
+    topics: RefCell<HashMap<String, Rc<Topic>>>,
+    patterns: RefCell<Vec<(SubscriptionId, String, Subscriber)>>,
+    next_pattern_id: Cell<u64>,
+}
+impl EventBus {
+    fn new() -> Self {
+        EventBus { topics: RefCell::new(HashMap::new()), patterns: RefCell::new(vec![]), next_pattern_id: Cell::new(0) }
+    }
+
+    /// Returns the `Topic` registered under `name`, creating and registering an empty one on
+    /// first use.
+    fn topic(&self, name: &str) -> Rc<Topic> {
+        self.topics.borrow_mut().entry(name.to_string()).or_insert_with(|| Rc::new(Topic::new())).clone()
+    }
+
+    fn subscribe(&self, topic: &str, callback: Subscriber) -> SubscriptionId {
+        self.topic(topic).subscribe(callback)
+    }
+
+    /// Unlike `subscribe`/`publish`, this never creates `topic` - there is nothing to unsubscribe
+    /// from a topic nobody has touched yet, so an unknown name just means "not subscribed".
+    fn unsubscribe(&self, topic: &str, id: SubscriptionId) -> bool {
+        match self.topics.borrow().get(topic) {
+            Some(t) => t.unsubscribe(id),
+            None => false,
+        }
+    }
+
+    /// Delivers `msg` to every current subscriber of `topic` - both exact subscribers and every
+    /// `subscribe_pattern` callback whose pattern matches `topic_name` - creating `topic` first if
+    /// nobody has used it yet. A topic with no subscribers is a cheap no-op: creating an empty
+    /// `Topic` costs a `HashMap` entry, not a publish.
+    fn publish(&self, topic_name: &str, msg: &str) -> usize {
+        let topic = self.topic(topic_name);
+        let exact_delivered = topic.subscriber_count();
+        topic.publish(msg);
+
+        let snapshot: Vec<(SubscriptionId, String, Subscriber)> = self.patterns.borrow().clone();
+        let mut pattern_delivered = 0;
+        for (_, pattern, callback) in &snapshot {
+            if pattern_matches(pattern, topic_name) {
+                callback.borrow_mut()(msg);
+                pattern_delivered += 1;
+            }
+        }
+        exact_delivered + pattern_delivered
+    }
+
+    /// Names of every topic created so far, via `subscribe` or `publish`, in no particular order.
+    fn topics(&self) -> Vec<String> {
+        self.topics.borrow().keys().cloned().collect()
+    }
+
+    /// Subscribes `callback` to every topic whose name matches `pattern`, MQTT-style: segments are
+    /// separated by '.', `*` matches exactly one segment, and `#` (or `**`) matches the rest of the
+    /// name, however many segments that is, and must be the pattern's last segment. Matching is
+    /// recomputed against the topic name on every `publish`, so a pattern subscriber sees topics
+    /// created after it subscribed too.
+    fn subscribe_pattern(&self, pattern: &str, callback: Subscriber) -> SubscriptionId {
+        let id = SubscriptionId(self.next_pattern_id.get());
+        self.next_pattern_id.set(id.0 + 1);
+        self.patterns.borrow_mut().push((id, pattern.to_string(), callback));
+        id
+    }
+
+    fn unsubscribe_pattern(&self, id: SubscriptionId) -> bool {
+        let mut patterns = self.patterns.borrow_mut();
+        let len_before = patterns.len();
+        patterns.retain(|(pattern_id, _, _)| *pattern_id != id);
+        patterns.len() != len_before
+    }
+}
+
+/// MQTT-style glob match of `topic` against `pattern`, both split on '.'. `*` consumes exactly one
+/// segment; `#`/`**` consumes every remaining segment (including zero) and short-circuits the rest
+/// of `pattern`, so it is only meaningful as the last segment.
+fn pattern_matches(pattern: &str, topic: &str) -> bool {
+    let mut pattern_segments = pattern.split('.');
+    let mut topic_segments = topic.split('.');
+    loop {
+        match (pattern_segments.next(), topic_segments.next()) {
+            (Some("#"), _) | (Some("**"), _) => return true,
+            (Some("*"), Some(_)) => continue,
+            (Some(p), Some(t)) if p == t => continue,
+            (None, None) => return true,
+            _ => return false,
         }
     }
 }
 
 fn main() {
     // Create a new topic
-    let mut topic = Topic::new();
+    let topic = Rc::new(Topic::new());
 
     // Subscriber 1: prints the received message in uppercase
-    let sub1: Subscriber = Rc::new(RefCell::new(|msg: String| {
+    let sub1: Subscriber = Rc::new(RefCell::new(|msg: &str| {
         println!("Subscriber 1 received: {}", msg.to_uppercase());
     }));
     topic.subscribe(sub1);
 
     // Subscriber 2: prints the received message in lowercase
-    let sub2: Subscriber = Rc::new(RefCell::new(|msg: String| {
+    let sub2: Subscriber = Rc::new(RefCell::new(|msg: &str| {
         println!("Subscriber 2 received: {}", msg.to_lowercase());
     }));
-    topic.subscribe(sub2);
+    let sub2_id = topic.subscribe(sub2);
 
     // Publish a message
-    topic.publish("Hello Rust World!".to_string());
+    topic.publish("Hello Rust World!");
+
+    // Unsubscribe subscriber 2, then publish again - only subscriber 1 should react this time.
+    topic.unsubscribe(sub2_id);
+    println!("Subscribers remaining: {}", topic.subscriber_count());
+    topic.publish("Still here?");
+
+    // Subscriber 3 only lives for this block: the guard unsubscribes it as soon as it drops.
+    {
+        let sub3: Subscriber = Rc::new(RefCell::new(|msg: &str| {
+            println!("Subscriber 3 (scoped) received: {msg}");
+        }));
+        let _guard = Topic::subscribe_scoped(&topic, sub3);
+        topic.publish("Still in scope");
+    }
+    println!("Subscribers remaining after scope exit: {}", topic.subscriber_count());
+    topic.publish("Out of scope now");
+
+    // Subscriber 4 only reacts to messages mentioning "Rust"; subscriber 5 reacts to everything,
+    // so publishing one matching and one non-matching message shows the difference.
+    topic.subscribe_filtered(
+        |msg: &str| msg.contains("Rust"),
+        Rc::new(RefCell::new(|msg: &str| println!("Subscriber 4 (Rust only) received: {msg}"))),
+    );
+    topic.subscribe(Rc::new(RefCell::new(|msg: &str| println!("Subscriber 5 (unfiltered) received: {msg}"))));
+    topic.publish("Rust strikes again");
+    topic.publish("Nothing to see here");
+
+    // A replaying topic: two messages are published before anyone is listening, yet a subscriber
+    // that joins afterwards still sees both, replayed in order, ahead of anything published later.
+    let replay_topic = Topic::with_replay(3);
+    replay_topic.publish("first");
+    replay_topic.publish("second");
+    replay_topic.subscribe(Rc::new(RefCell::new(|msg: &str| println!("Late subscriber received: {msg}"))));
+
+    // A once-subscriber fires for the first publish after it joins, then stays silent forever.
+    topic.subscribe_once(Rc::new(RefCell::new(|msg: &str| println!("Once subscriber received: {msg} (first message only)"))));
+    topic.publish("first message only");
+    topic.publish("this one should stay silent for the once subscriber");
+
+    // A weak subscriber only fires while its owner keeps the strong handle alive; once that
+    // handle is dropped, the next publish silently skips it and prunes the dead entry.
+    let weak_sub: Subscriber = Rc::new(RefCell::new(|msg: &str| println!("Weak subscriber received: {msg}")));
+    topic.subscribe_weak(&weak_sub);
+    println!("Subscribers before dropping the weak handle: {}", topic.subscriber_count());
+    topic.publish("still holding the weak subscriber's handle");
+    drop(weak_sub);
+    topic.publish("weak subscriber's handle is gone now");
+    println!("Subscribers after the weak handle was dropped and pruned: {}", topic.subscriber_count());
+
+    // A priority topic: a normal subscriber joins first, but the priority -10 "audit" subscriber
+    // joins after it and still runs first, since lower priorities run earlier regardless of
+    // subscribe order.
+    let priority_topic = Topic::new();
+    priority_topic.subscribe(Rc::new(RefCell::new(|msg: &str| println!("Normal subscriber handled: {msg}"))));
+    priority_topic.subscribe_with_priority(-10, Rc::new(RefCell::new(|msg: &str| println!("Audit subscriber (priority -10) saw: {msg}"))));
+    priority_topic.publish("order #42");
+
+    // A batch subscriber only sees whole publish_all calls, never individual publish() ones: it
+    // ignores "order #42" above and only fires once below, with every message from that one call.
+    let batch_topic = Topic::new();
+    batch_topic.subscribe(Rc::new(RefCell::new(|msg: &str| println!("Per-message subscriber received: {msg}"))));
+    batch_topic.subscribe_batch(Rc::new(RefCell::new(|batch: &[String]| println!("Batch subscriber received {} messages: {batch:?}", batch.len()))));
+    let invocations = batch_topic.publish_all(["one", "two", "three"]);
+    println!("publish_all delivered {invocations} total invocations");
+
+    // A metered topic: CountingMetrics tallies every delivery's subscriber count and wall time
+    // without the example asserting on timing, which would be flaky.
+    let metrics = Rc::new(CountingMetrics::new());
+    let metered_topic = Topic::with_metrics(metrics.clone());
+    metered_topic.subscribe(Rc::new(RefCell::new(|_: &str| {})));
+    metered_topic.subscribe(Rc::new(RefCell::new(|_: &str| {})));
+    metered_topic.publish("metered message 1");
+    metered_topic.publish("metered message 2");
+    println!(
+        "CountingMetrics: {} publishes, {} subscriber invocations, {:?} total delivery time",
+        metrics.publishes(),
+        metrics.subscribers_invoked(),
+        metrics.total_elapsed()
+    );
+
+    // publish_isolated: the middle subscriber panics on every message, but the two around it keep
+    // receiving - and the panicker is evicted after its first panic, so it never gets a second
+    // chance to take the rest down with it.
+    let isolated_topic = Topic::new();
+    isolated_topic.subscribe(Rc::new(RefCell::new(|msg: &str| println!("Isolated subscriber 1 received: {msg}"))));
+    isolated_topic.subscribe(Rc::new(RefCell::new(|msg: &str| panic!("subscriber 2 always panics on {msg}"))));
+    isolated_topic.subscribe(Rc::new(RefCell::new(|msg: &str| println!("Isolated subscriber 3 received: {msg}"))));
+    let report = isolated_topic.publish_isolated("first message");
+    println!("publish_isolated report: delivered {}, evicted {:?}", report.delivered, report.evicted);
+    let report = isolated_topic.publish_isolated("second message");
+    println!("publish_isolated report after eviction: delivered {}, evicted {:?}", report.delivered, report.evicted);
+
+    // debug_snapshot: one subscriber is named, the other is left to get a generated "sub-N" label.
+    let introspected_topic = Topic::new();
+    introspected_topic.subscribe_named("uppercaser", Rc::new(RefCell::new(|msg: &str| println!("uppercaser saw: {}", msg.to_uppercase()))));
+    introspected_topic.subscribe(Rc::new(RefCell::new(|_: &str| {})));
+    introspected_topic.publish("alpha");
+    introspected_topic.publish("beta");
+    let snapshot = introspected_topic.debug_snapshot();
+    println!("Topic snapshot: {snapshot:?}");
+    println!("Topic snapshot as JSON: {}", serde_json::to_string(&snapshot).unwrap());
+
+    // QueueingTopic: a mailbox of capacity 2 receives 5 messages before ever draining - the
+    // surviving set depends entirely on which OverflowPolicy the subscriber chose.
+    for policy in [OverflowPolicy::DropNewest, OverflowPolicy::DropOldest, OverflowPolicy::Error] {
+        let queueing_topic = QueueingTopic::new();
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let log = received.clone();
+        queueing_topic.subscribe(2, policy, Rc::new(RefCell::new(move |msg: &u32| log.borrow_mut().push(*msg))));
+        let mut rejected = Vec::new();
+        for msg in 1..=5u32 {
+            rejected.extend(queueing_topic.publish(msg));
+        }
+        queueing_topic.drain();
+        println!("{policy:?}: delivered {:?}, rejected {} message(s)", received.borrow(), rejected.len());
+    }
+
+    // SyncTopic: two threads publish concurrently into the same topic; a counting subscriber
+    // tallies every delivery so we can confirm none were lost or double-counted.
+    let sync_topic = Arc::new(SyncTopic::new());
+    let count = Arc::new(Mutex::new(0u64));
+    let counter = count.clone();
+    sync_topic.subscribe(Arc::new(Mutex::new(move |_: &u32| *counter.lock().unwrap() += 1)));
+    let noisy_id = sync_topic.subscribe(Arc::new(Mutex::new(|n: &u32| println!("SyncTopic saw {n}"))));
+    sync_topic.unsubscribe(noisy_id);
+    println!("SyncTopic subscriber count: {}", sync_topic.subscriber_count());
+
+    const PUBLISHES_PER_THREAD: u32 = 1000;
+    let topic_a = sync_topic.clone();
+    let topic_b = sync_topic.clone();
+    let thread_a = std::thread::spawn(move || {
+        for i in 0..PUBLISHES_PER_THREAD {
+            topic_a.publish(&i);
+        }
+    });
+    let thread_b = std::thread::spawn(move || {
+        for i in 0..PUBLISHES_PER_THREAD {
+            topic_b.publish(&i);
+        }
+    });
+    thread_a.join().unwrap();
+    thread_b.join().unwrap();
+
+    println!("SyncTopic received {} publishes from 2 threads (expected {})", *count.lock().unwrap(), 2 * PUBLISHES_PER_THREAD);
+
+    // TryTopic: the second of three subscribers fails, but under ContinueOnError the third still
+    // gets delivered, and the report names exactly which subscriber(s) failed.
+    let try_topic: TryTopic<u32, String> = TryTopic::new(FailurePolicy::ContinueOnError);
+    try_topic.subscribe(Rc::new(RefCell::new(|n: &u32| {
+        println!("TryTopic subscriber 1 received {n}");
+        Ok(())
+    })));
+    try_topic.subscribe(Rc::new(RefCell::new(|n: &u32| Err(format!("subscriber 2 refuses to handle {n}")))));
+    try_topic.subscribe(Rc::new(RefCell::new(|n: &u32| {
+        println!("TryTopic subscriber 3 received {n}");
+        Ok(())
+    })));
+    println!("TryTopic subscriber count: {}", try_topic.subscriber_count());
+    let report = try_topic.publish(&42);
+    println!("TryTopic report: delivered {}, failures {:?}", report.delivered, report.failures);
+
+    // EventBus: "user.created" and "user.deleted" route to different subscribers, and a topic
+    // nobody ever subscribed to still accepts a publish - it just delivers to nobody.
+    let bus = EventBus::new();
+    bus.subscribe("user.created", Rc::new(RefCell::new(|msg: &str| println!("[user.created] welcome: {msg}"))));
+    let noisy_deleted_id = bus.subscribe("user.deleted", Rc::new(RefCell::new(|msg: &str| println!("[user.deleted] noisy: {msg}"))));
+    bus.subscribe("user.deleted", Rc::new(RefCell::new(|msg: &str| println!("[user.deleted] goodbye: {msg}"))));
+    bus.unsubscribe("user.deleted", noisy_deleted_id);
+    bus.publish("user.created", "alice");
+    bus.publish("user.deleted", "bob");
+    println!("EventBus delivered to {} subscribers of an unused topic", bus.publish("user.updated", "carol"));
+    println!("EventBus active topics: {:?}", bus.topics());
+
+    // A "user.*" pattern subscriber sees every user.<anything-one-segment> publish without
+    // subscribing to each topic by name; the exact "user.created" subscriber still fires too.
+    let noisy_pattern_id = bus.subscribe_pattern("order.*", Rc::new(RefCell::new(|msg: &str| println!("[order.*] noisy: {msg}"))));
+    bus.unsubscribe_pattern(noisy_pattern_id);
+    bus.subscribe_pattern(
+        "user.*",
+        Rc::new(RefCell::new(|msg: &str| println!("[user.*] pattern saw: {msg}"))),
+    );
+    bus.publish("user.created", "dave");
+    bus.publish("user.created.eu", "eve"); // two segments after "user" - "user.*" does not match this
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sync_topic_tallies_every_publish_from_two_concurrent_threads() {
+        let topic = Arc::new(SyncTopic::new());
+        let count = Arc::new(Mutex::new(0u64));
+        let counter = count.clone();
+        topic.subscribe(Arc::new(Mutex::new(move |_: &u32| *counter.lock().unwrap() += 1)));
+
+        const PUBLISHES_PER_THREAD: u32 = 500;
+        let topic_a = topic.clone();
+        let topic_b = topic.clone();
+        let thread_a = std::thread::spawn(move || {
+            for i in 0..PUBLISHES_PER_THREAD {
+                topic_a.publish(&i);
+            }
+        });
+        let thread_b = std::thread::spawn(move || {
+            for i in 0..PUBLISHES_PER_THREAD {
+                topic_b.publish(&i);
+            }
+        });
+        thread_a.join().unwrap();
+        thread_b.join().unwrap();
+
+        assert_eq!(*count.lock().unwrap(), 2 * PUBLISHES_PER_THREAD as u64);
+    }
+
+    #[test]
+    fn a_subscriber_that_subscribes_during_publish_does_not_deadlock() {
+        let topic = Arc::new(SyncTopic::new());
+        let late_subscriptions: Arc<Mutex<Vec<SubscriptionId>>> = Arc::new(Mutex::new(Vec::new()));
+
+        let inner_topic = topic.clone();
+        let late = late_subscriptions.clone();
+        topic.subscribe(Arc::new(Mutex::new(move |_: &u32| {
+            // Subscribing from inside a callback only needs the short-lived `subs` lock, which
+            // `publish` has already released by the time it invokes callbacks - so this does not
+            // deadlock against the `publish` call currently running this very closure.
+            let id = inner_topic.subscribe(Arc::new(Mutex::new(|_: &u32| {})));
+            late.lock().unwrap().push(id);
+        })));
+
+        topic.publish(&1);
+
+        // The subscriber added mid-publish must not have been invoked by the publish that spawned
+        // it, since `publish` only calls the callbacks it collected before that subscription existed.
+        assert_eq!(late_subscriptions.lock().unwrap().len(), 1);
+        assert_eq!(topic.subscriber_count(), 2);
+    }
+
+    #[test]
+    fn try_topic_continue_on_error_still_delivers_past_a_failing_subscriber() {
+        let topic: TryTopic<u32, String> = TryTopic::new(FailurePolicy::ContinueOnError);
+        let first_id = topic.subscribe(Rc::new(RefCell::new(|_: &u32| Ok(()))));
+        let second_id = topic.subscribe(Rc::new(RefCell::new(|n: &u32| Err(format!("boom {n}")))));
+        let third_id = topic.subscribe(Rc::new(RefCell::new(|_: &u32| Ok(()))));
+
+        let report = topic.publish(&7);
+
+        assert_eq!(report.delivered, 2);
+        assert_eq!(report.failures, vec![(second_id, "boom 7".to_string())]);
+        let _ = (first_id, third_id);
+    }
+
+    #[test]
+    fn try_topic_stop_on_first_error_never_reaches_later_subscribers() {
+        let topic: TryTopic<u32, String> = TryTopic::new(FailurePolicy::StopOnFirstError);
+        topic.subscribe(Rc::new(RefCell::new(|_: &u32| Ok(()))));
+        let second_id = topic.subscribe(Rc::new(RefCell::new(|n: &u32| Err(format!("boom {n}")))));
+        let third_invoked = Rc::new(RefCell::new(false));
+        let flag = third_invoked.clone();
+        topic.subscribe(Rc::new(RefCell::new(move |_: &u32| {
+            *flag.borrow_mut() = true;
+            Ok(())
+        })));
+
+        let report = topic.publish(&7);
+
+        assert_eq!(report.delivered, 1);
+        assert_eq!(report.failures, vec![(second_id, "boom 7".to_string())]);
+        assert!(!*third_invoked.borrow());
+    }
+
+    #[test]
+    fn event_bus_creates_topics_lazily_on_first_subscribe_or_publish() {
+        let bus = EventBus::new();
+        assert_eq!(bus.topics().len(), 0);
+
+        bus.subscribe("user.created", Rc::new(RefCell::new(|_: &str| {})));
+        assert_eq!(bus.topics(), vec!["user.created".to_string()]);
+
+        assert_eq!(bus.publish("user.updated", "anything"), 0);
+        let mut topics = bus.topics();
+        topics.sort();
+        assert_eq!(topics, vec!["user.created".to_string(), "user.updated".to_string()]);
+    }
+
+    #[test]
+    fn event_bus_isolates_topics_from_each_other() {
+        let bus = EventBus::new();
+        let created = Rc::new(RefCell::new(Vec::new()));
+        let deleted = Rc::new(RefCell::new(Vec::new()));
+
+        let created_log = created.clone();
+        bus.subscribe("user.created", Rc::new(RefCell::new(move |msg: &str| created_log.borrow_mut().push(msg.to_string()))));
+        let deleted_log = deleted.clone();
+        bus.subscribe("user.deleted", Rc::new(RefCell::new(move |msg: &str| deleted_log.borrow_mut().push(msg.to_string()))));
+
+        assert_eq!(bus.publish("user.created", "alice"), 1);
+        assert_eq!(bus.publish("user.deleted", "bob"), 1);
+
+        assert_eq!(*created.borrow(), vec!["alice".to_string()]);
+        assert_eq!(*deleted.borrow(), vec!["bob".to_string()]);
+    }
+
+    #[test]
+    fn event_bus_unsubscribing_from_one_topic_does_not_affect_another() {
+        let bus = EventBus::new();
+        let created_id = bus.subscribe("user.created", Rc::new(RefCell::new(|_: &str| {})));
+        bus.subscribe("user.deleted", Rc::new(RefCell::new(|_: &str| {})));
+
+        assert!(bus.unsubscribe("user.created", created_id));
+        assert_eq!(bus.publish("user.created", "alice"), 0);
+        assert_eq!(bus.publish("user.deleted", "bob"), 1);
+
+        assert!(!bus.unsubscribe("user.never-touched", SubscriptionId(0)));
+    }
+
+    #[test]
+    fn pattern_star_matches_one_segment_but_not_two() {
+        assert!(pattern_matches("user.*", "user.created"));
+        assert!(!pattern_matches("user.*", "user.created.eu"));
+    }
+
+    #[test]
+    fn pattern_hash_matches_the_catch_all() {
+        assert!(pattern_matches("#", "anything"));
+        assert!(pattern_matches("user.#", "user.created.eu"));
+        assert!(pattern_matches("user.#", "user"));
+        assert!(!pattern_matches("user.#", "order.created"));
+    }
+
+    #[test]
+    fn event_bus_publish_hits_an_exact_and_a_pattern_subscriber_exactly_once_each() {
+        let bus = EventBus::new();
+        let exact_hits = Rc::new(RefCell::new(0));
+        let pattern_hits = Rc::new(RefCell::new(0));
+
+        let exact_counter = exact_hits.clone();
+        bus.subscribe("user.created", Rc::new(RefCell::new(move |_: &str| *exact_counter.borrow_mut() += 1)));
+        let pattern_counter = pattern_hits.clone();
+        bus.subscribe_pattern("user.*", Rc::new(RefCell::new(move |_: &str| *pattern_counter.borrow_mut() += 1)));
+
+        let delivered = bus.publish("user.created", "alice");
+
+        assert_eq!(delivered, 2);
+        assert_eq!(*exact_hits.borrow(), 1);
+        assert_eq!(*pattern_hits.borrow(), 1);
+    }
+
+    fn queueing_mailbox_contents(policy: OverflowPolicy) -> (Vec<u32>, Vec<SubscriptionId>) {
+        let topic = QueueingTopic::new();
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let log = received.clone();
+        topic.subscribe(2, policy, Rc::new(RefCell::new(move |msg: &u32| log.borrow_mut().push(*msg))));
+        let mut rejected = Vec::new();
+        for msg in 1..=5u32 {
+            rejected.extend(topic.publish(msg));
+        }
+        topic.drain();
+        (received.borrow().clone(), rejected)
+    }
+
+    #[test]
+    fn drop_newest_keeps_the_first_capacity_messages_and_drops_the_rest() {
+        let (delivered, rejected) = queueing_mailbox_contents(OverflowPolicy::DropNewest);
+        assert_eq!(delivered, vec![1, 2]);
+        assert!(rejected.is_empty());
+    }
+
+    #[test]
+    fn drop_oldest_keeps_the_last_capacity_messages() {
+        let (delivered, rejected) = queueing_mailbox_contents(OverflowPolicy::DropOldest);
+        assert_eq!(delivered, vec![4, 5]);
+        assert!(rejected.is_empty());
+    }
+
+    #[test]
+    fn error_policy_keeps_the_first_capacity_messages_and_reports_every_overflow_as_rejected() {
+        let (delivered, rejected) = queueing_mailbox_contents(OverflowPolicy::Error);
+        assert_eq!(delivered, vec![1, 2]);
+        assert_eq!(rejected.len(), 3);
+    }
 }
@@ -0,0 +1,110 @@
+// cargo run --example 03_observer_async --features async
+
+// Async sibling of 03_observer.rs: bridges a synchronous `Topic` to a tokio mpsc channel, the way
+// 07/08_tokio_event_dispatcher.rs feed their workers, so a publisher that never awaits anything
+// can still hand messages off to an async consumer. `Topic` here is generic over `T` instead of
+// pinned to `&str` like the sync version, since a value has to be owned to cross an mpsc channel.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use tokio::sync::mpsc;
+
+type Subscriber<T> = Rc<RefCell<dyn FnMut(&T)>>;
+
+struct Topic<T> {
+    subs: RefCell<Vec<Subscriber<T>>>,
+    dropped: Rc<Cell<u64>>,
+}
+impl<T: Clone + 'static> Topic<T> {
+    fn new() -> Self {
+        Topic { subs: RefCell::new(vec![]), dropped: Rc::new(Cell::new(0)) }
+    }
+
+    fn subscribe(&self, callback: Subscriber<T>) {
+        self.subs.borrow_mut().push(callback);
+    }
+
+    /// Forwards every publish to `tx` via `try_send`: `publish` is synchronous, so it cannot
+    /// `.await` room in a full bounded channel the way a real async sender would. A full channel
+    /// therefore drops the message instead of blocking the publisher, and `dropped_count` tracks
+    /// how many were lost rather than letting them vanish silently.
+    fn pipe_to(&self, tx: mpsc::Sender<T>) {
+        let dropped = self.dropped.clone();
+        self.subscribe(Rc::new(RefCell::new(move |msg: &T| {
+            if tx.try_send(msg.clone()).is_err() {
+                dropped.set(dropped.get() + 1);
+            }
+        })));
+    }
+
+    fn dropped_count(&self) -> u64 {
+        self.dropped.get()
+    }
+
+    fn publish(&self, msg: &T) {
+        let snapshot: Vec<Subscriber<T>> = self.subs.borrow().clone();
+        for sub in &snapshot {
+            sub.borrow_mut()(msg);
+        }
+    }
+}
+
+// The async consumer side: drains the channel `pipe_to` forwards into, same shape as
+// `start_worker` in 07/08_tokio_event_dispatcher.rs.
+async fn start_worker(mut rx: mpsc::Receiver<String>) {
+    while let Some(msg) = rx.recv().await {
+        println!("Worker received: {msg}");
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let topic = Topic::new();
+    let (tx, rx) = mpsc::channel(4);
+    topic.pipe_to(tx);
+
+    tokio::spawn(start_worker(rx));
+
+    // Published synchronously, with no `.await` in between - the worker task has no chance to
+    // drain the channel until this loop finishes, so once it fills up, `try_send` starts dropping.
+    for i in 0..10 {
+        topic.publish(&format!("event {i}"));
+    }
+
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    println!("Dropped messages due to a full channel: {}", topic.dropped_count());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn messages_arrive_in_order() {
+        let topic = Topic::new();
+        let (tx, mut rx) = mpsc::channel(100);
+        topic.pipe_to(tx);
+
+        for i in 0..5 {
+            topic.publish(&i);
+        }
+
+        for i in 0..5 {
+            assert_eq!(rx.recv().await, Some(i));
+        }
+    }
+
+    #[tokio::test]
+    async fn drop_counter_increments_when_the_bounded_channel_overflows() {
+        let topic = Topic::new();
+        let (tx, mut rx) = mpsc::channel(1);
+        topic.pipe_to(tx);
+
+        topic.publish(&1); // fills the channel's only slot
+        topic.publish(&2); // dropped: no room and nobody has received yet
+        topic.publish(&3); // dropped: still no room
+
+        assert_eq!(topic.dropped_count(), 2);
+        assert_eq!(rx.recv().await, Some(1));
+    }
+}
@@ -0,0 +1,146 @@
+// cargo run --example 03_observer_events
+
+// Typed sibling of 03_observer.rs: the payload there is a bare `&str`, so nothing stops a
+// subscriber from misreading what kind of event it just received. `AppEvent` closes that gap with
+// an enum, and the per-variant `on_*` helpers hide the `if let` a subscriber would otherwise need
+// to filter for its variant - the typed counterpart to `Topic::subscribe_filtered`, without
+// reaching for a dynamic `TypeId` registry to get there.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Debug, Clone)]
+enum AppEvent {
+    UserCreated { name: String },
+    UserDeleted { id: u32 },
+    Tick,
+}
+
+type Subscriber = Rc<RefCell<dyn FnMut(&AppEvent)>>;
+
+struct EventBus {
+    subs: RefCell<Vec<Subscriber>>,
+}
+impl EventBus {
+    fn new() -> Self {
+        EventBus { subs: RefCell::new(vec![]) }
+    }
+
+    fn subscribe(&self, callback: Subscriber) {
+        self.subs.borrow_mut().push(callback);
+    }
+
+    /// Subscribes to `AppEvent::UserCreated` only, unwrapping `name` before handing it to
+    /// `callback` - publishing a `UserDeleted` or `Tick` never invokes `callback` at all.
+    fn on_user_created(&self, mut callback: impl FnMut(&str) + 'static) {
+        self.subscribe(Rc::new(RefCell::new(move |event: &AppEvent| {
+            if let AppEvent::UserCreated { name } = event {
+                callback(name);
+            }
+        })));
+    }
+
+    /// Subscribes to `AppEvent::UserDeleted` only, unwrapping `id` before handing it to `callback`.
+    fn on_user_deleted(&self, mut callback: impl FnMut(u32) + 'static) {
+        self.subscribe(Rc::new(RefCell::new(move |event: &AppEvent| {
+            if let AppEvent::UserDeleted { id } = event {
+                callback(*id);
+            }
+        })));
+    }
+
+    /// Subscribes to `AppEvent::Tick` only - `Tick` carries no payload, so `callback` takes none.
+    fn on_tick(&self, mut callback: impl FnMut() + 'static) {
+        self.subscribe(Rc::new(RefCell::new(move |event: &AppEvent| {
+            if let AppEvent::Tick = event {
+                callback();
+            }
+        })));
+    }
+
+    /// Subscribes to every event regardless of variant - the catch-all a per-variant helper
+    /// deliberately withholds.
+    fn on_any(&self, callback: Subscriber) {
+        self.subscribe(callback);
+    }
+
+    fn publish(&self, event: &AppEvent) {
+        let snapshot: Vec<Subscriber> = self.subs.borrow().clone();
+        for sub in &snapshot {
+            sub.borrow_mut()(event);
+        }
+    }
+}
+
+fn main() {
+    let bus = EventBus::new();
+    bus.on_user_created(|name| println!("Welcome, {name}!"));
+    bus.on_user_deleted(|id| println!("User {id} was deleted."));
+    bus.on_tick(|| println!("Tick."));
+    bus.on_any(Rc::new(RefCell::new(|event: &AppEvent| println!("[on_any] saw {event:?}"))));
+
+    bus.publish(&AppEvent::UserCreated { name: "Alice".to_string() });
+    bus.publish(&AppEvent::UserDeleted { id: 7 });
+    bus.publish(&AppEvent::Tick);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn on_user_created_only_sees_user_created_events() {
+        let bus = EventBus::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        bus.on_user_created(move |name| log.borrow_mut().push(name.to_string()));
+
+        bus.publish(&AppEvent::UserCreated { name: "Alice".to_string() });
+        bus.publish(&AppEvent::UserDeleted { id: 1 });
+        bus.publish(&AppEvent::Tick);
+
+        assert_eq!(*seen.borrow(), vec!["Alice".to_string()]);
+    }
+
+    #[test]
+    fn on_user_deleted_only_sees_user_deleted_events() {
+        let bus = EventBus::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        bus.on_user_deleted(move |id| log.borrow_mut().push(id));
+
+        bus.publish(&AppEvent::UserCreated { name: "Alice".to_string() });
+        bus.publish(&AppEvent::UserDeleted { id: 7 });
+        bus.publish(&AppEvent::Tick);
+
+        assert_eq!(*seen.borrow(), vec![7]);
+    }
+
+    #[test]
+    fn on_tick_only_sees_tick_events() {
+        let bus = EventBus::new();
+        let ticks = Rc::new(RefCell::new(0u32));
+        let counter = ticks.clone();
+        bus.on_tick(move || *counter.borrow_mut() += 1);
+
+        bus.publish(&AppEvent::UserCreated { name: "Alice".to_string() });
+        bus.publish(&AppEvent::Tick);
+        bus.publish(&AppEvent::Tick);
+
+        assert_eq!(*ticks.borrow(), 2);
+    }
+
+    #[test]
+    fn on_any_sees_every_event_regardless_of_variant() {
+        let bus = EventBus::new();
+        let count = Rc::new(RefCell::new(0u32));
+        let counter = count.clone();
+        bus.on_any(Rc::new(RefCell::new(move |_: &AppEvent| *counter.borrow_mut() += 1)));
+
+        bus.publish(&AppEvent::UserCreated { name: "Alice".to_string() });
+        bus.publish(&AppEvent::UserDeleted { id: 1 });
+        bus.publish(&AppEvent::Tick);
+
+        assert_eq!(*count.borrow(), 3);
+    }
+}
@@ -1,4 +1,4 @@
-// cargo run --example 04_state_machine_traits
+// cargo run --example 04_state_machine_traits --features serde
 
 // Implements a finite state machine (FSM) in Rust.
 // This example models a process that moves through three states:
@@ -17,87 +17,1711 @@
 
 // There is a simpler and faster approach based on enum and match expression
 
-use std::fmt::Debug;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Debug};
+use std::hash::Hash;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use thiserror::Error;
 
-// Define the possible events the FSM can handle.
-// In this simple example, there's only one event: Process.
+// Define the possible events the FSM can handle. `Process` carries the payload being validated,
+// enriched, or persisted; `Reject` carries why a state gave up; `Retry` carries nothing since it
+// only ever means "try again from the top"; `Archive` carries nothing either and exists purely so
+// an externally registered state (see `StateRegistry`) has an event to be reached by.
 #[derive(Clone, Debug)]
 pub enum FsmEvent {
+    Process { payload: String },
+    Reject { reason: String },
+    Retry,
+    Archive,
+}
+
+impl FsmEventLike for FsmEvent {
+    type Kind = FsmEventKind;
+
+    /// The `FsmEventKind` this event carries - see `StateRegistry::extension_transition`, which
+    /// looks up extension edges by kind rather than by the full, payload-carrying `FsmEvent`.
+    fn kind(&self) -> FsmEventKind {
+        match self {
+            FsmEvent::Process { .. } => FsmEventKind::Process,
+            FsmEvent::Reject { .. } => FsmEventKind::Reject,
+            FsmEvent::Retry => FsmEventKind::Retry,
+            FsmEvent::Archive => FsmEventKind::Archive,
+        }
+    }
+}
+
+/// `FsmEvent` without the payload/reason data that only matters at runtime - an edge in the
+/// transition graph (`FsmState::transitions`, `export_dot`) only cares about the kind of event
+/// that crosses it, not what it carried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FsmEventKind {
     Process,
+    Reject,
+    Retry,
+    Archive,
+}
+
+/// What an event type needs to drive a generic `FsmRunner<E>`: cloneable and printable (the
+/// runner logs every event via `Debug`, and `apply` hands it to states by value), plus a
+/// lighter-weight `Kind` that transition tables (`FsmState::transitions`,
+/// `StateRegistry::extension_transition`) key on instead of the full, payload-carrying event -
+/// `FsmEvent` reduces to the four-variant `FsmEventKind` above; the byte-event instantiation
+/// further down reduces a `u8` to `ByteEventKind`.
+pub trait FsmEventLike: Clone + Debug {
+    type Kind: Copy + Eq + Hash + Debug;
+    fn kind(&self) -> Self::Kind;
+}
+
+/// Shared, mutable data threaded through every transition - the FSM equivalent of the `TextStats`
+/// accumulator the benches build up char by char. `Enriched` is the only state that writes to
+/// `record`; `attempts` only grows on a `Retry`; every state appends one line to `log` regardless
+/// of whether its transition succeeds; `hooks` records every `on_enter`/`on_exit` call in order;
+/// `enriched_at` is stamped by `Enriched::on_enter`.
+#[derive(Debug, Default)]
+pub struct FsmContext {
+    pub record: String,
+    pub attempts: u32,
+    pub log: Vec<String>,
+    pub hooks: Vec<String>,
+    pub enriched_at: Option<Instant>,
+}
+
+/// Raised by `FsmState::process_event` when `event` has no defined transition out of the
+/// current state, instead of that state silently returning `self` unchanged - or by
+/// `FsmRunner` when a run is taking too long to trust, see `FsmRunner::with_limits`.
+#[derive(Debug, Error)]
+pub enum FsmError {
+    #[error("no transition defined for event {event} from state {state}")]
+    InvalidTransition { state: &'static str, event: String },
+
+    #[error("fsm exceeded its step limit of {steps} transitions without reaching rest")]
+    StepLimitExceeded { steps: usize },
+
+    #[error("fsm is cycling through the same states without making progress: {0:?}")]
+    CycleDetected(Vec<&'static str>),
+
+    #[error("no state matches the persisted token {0:?}")]
+    UnknownStateToken(String),
+
+    #[error("state {state} failed its side effect: {source}")]
+    SideEffect {
+        state: &'static str,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// What `FsmState::process_event` hands back on rejection: the error, plus `self` - otherwise
+/// `process_event` consuming `self: Box<Self>` would simply drop it the moment it returns `Err`,
+/// along with any per-instance configuration it carried. `FsmRunner::apply` puts `state` back
+/// into the runner instead of rebuilding a fresh one from the registry, so a caller that retries
+/// a rejected event retries against the exact instance that rejected it.
+pub struct Rejection<E: FsmEventLike> {
+    pub state: Box<dyn FsmState<E>>,
+    pub error: FsmError,
+}
+
+impl<E: FsmEventLike> std::fmt::Debug for Rejection<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Rejection").field("state", &self.state.name()).field("error", &self.error).finish()
+    }
 }
 
+/// A `FsmState`'s identity, serializable so a long-running process can persist where it stopped
+/// (e.g. in a database row) and resume later without keeping the `FsmRunner` itself alive across
+/// the gap - see `FsmState::to_token` and `resume_from`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateToken(String);
+
 // Define the State trait that all states must implement.
-// - handle: defines how the state reacts to an event and transitions to the next state.
+// - process_event: defines how the state reacts to an event and transitions to the next state,
+//   mutating the shared context along the way, or reports that the event has no transition
+//   defined from the current state.
 // - name: returns the name of the current state as a string for logging and comparison.
-pub trait FsmState {
-    fn process_event(self: Box<Self>, input: FsmEvent) -> Box<dyn FsmState>;
+// - on_enter / on_exit: lifecycle hooks fired by the runner around every transition that actually
+//   changes state (see `transition` below). Default to just recording the call; override to do
+//   real work on entry/exit, as `Enriched::on_enter` does.
+// - is_final: whether this is a natural stopping point for the FSM. Defaults to `false`; only
+//   `Persisted` overrides it. Deliberately not derived from `name()` equality between steps - a
+//   state that legitimately transitions to itself, like `Enriching` below, would otherwise look
+//   indistinguishable from one that's done.
+// - to_token: a serializable stand-in for this state, defaulting to wrapping `name()` - see
+//   `resume_from` for the reverse direction.
+// - transitions: the event kinds this state actually reacts to and where each leads, declared
+//   explicitly rather than inferred from `process_event` - see `export_dot`.
+// - timeout: an event this state synthesizes for itself if nothing else arrives within a given
+//   duration of entering it - defaults to `None` (no timeout); `Enriched` overrides it. Only
+//   `FsmRunner::run_with_clock` actually fires these - see that method.
+//
+// Generic over the event type `E` (see `FsmEventLike`) rather than hardwired to `FsmEvent`, so
+// the same trait - and the same `FsmRunner<E>` - drives both this file's document pipeline
+// (`E = FsmEvent`) and the byte-event instantiation further down (`E = u8`).
+pub trait FsmState<E: FsmEventLike> {
+    /// Consumes `self` and either returns the next state or, on rejection, hands `self` back
+    /// alongside the error - so a rejection doesn't drop any per-instance configuration the state
+    /// was carrying (e.g. `Enriched::persist_path`). See `Rejection` and `FsmRunner::apply`, which
+    /// puts the returned state back rather than rebuilding a fresh one from the registry, so a
+    /// caller that retries a failed event retries against the exact instance that rejected it.
+    fn process_event(self: Box<Self>, event: E, ctx: &mut FsmContext) -> Result<Box<dyn FsmState<E>>, Rejection<E>>;
     fn name(&self) -> &'static str;
+    fn transitions(&self) -> Vec<(E::Kind, &'static str)>;
+
+    fn on_enter(&self, ctx: &mut FsmContext) {
+        record_hook(ctx, "on_enter", self.name());
+    }
+
+    fn on_exit(&self, ctx: &mut FsmContext) {
+        record_hook(ctx, "on_exit", self.name());
+    }
+
+    fn timeout(&self) -> Option<(Duration, E)> {
+        None
+    }
+
+    fn is_final(&self) -> bool {
+        false
+    }
+
+    fn to_token(&self) -> StateToken {
+        StateToken(self.name().to_string())
+    }
+}
+
+/// Every `FsmState<E>` displays as its own `name()`, implemented once here for `dyn FsmState<E>`
+/// rather than per state - so `FsmRunner`'s trace output can just `{}`-format whatever state
+/// it's holding instead of calling `.name()` itself.
+impl<E: FsmEventLike> fmt::Display for dyn FsmState<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
 }
 
 // State: Validated
-// When receiving Event::Process, transitions to Enriched.
+// `Process` advances to Enriched; `Reject` jumps straight to Failed; `Retry` has nothing to
+// retry yet, so it's an invalid transition.
 struct Validated;
-impl FsmState for Validated {
-    fn process_event(self: Box<Self>, _event: FsmEvent) -> Box<dyn FsmState> {
-        println!("State: Validated -> Enriched");
-        Box::new(Enriched)
+impl FsmState<FsmEvent> for Validated {
+    fn process_event(self: Box<Self>, event: FsmEvent, ctx: &mut FsmContext) -> Result<Box<dyn FsmState<FsmEvent>>, Rejection<FsmEvent>> {
+        match event {
+            FsmEvent::Process { payload } => {
+                log_line(ctx, format!("State: Validated -> Enriched (payload: {payload})"));
+                let next: Box<dyn FsmState<FsmEvent>> = Box::new(Enriched::default());
+                transition(self.as_ref(), next.as_ref(), ctx);
+                Ok(next)
+            }
+            FsmEvent::Reject { reason } => {
+                log_line(ctx, format!("State: Validated -> Failed ({reason})"));
+                let next: Box<dyn FsmState<FsmEvent>> = Box::new(Failed);
+                transition(self.as_ref(), next.as_ref(), ctx);
+                Ok(next)
+            }
+            event => Err(invalid_transition(self, event, ctx)),
+        }
     }
 
     fn name(&self) -> &'static str {
         "Validated"
     }
+
+    fn transitions(&self) -> Vec<(FsmEventKind, &'static str)> {
+        vec![(FsmEventKind::Process, "Enriched"), (FsmEventKind::Reject, "Failed")]
+    }
+}
+
+/// Where `Enriched::process_event` writes the record on its way to `Persisted`, absent an
+/// explicit path from `Enriched::new` - a shared file under the process's temp dir is good enough
+/// for the demo in `main`, but every test that cares about the outcome of the write configures
+/// its own path (a fresh temp file for the success case, an unwritable one for the failure case).
+fn default_persist_path() -> PathBuf {
+    std::env::temp_dir().join("xp_design_patterns_fsm_record.txt")
 }
 
 // State: Enriched
-// When receiving Event::Process, transitions to Persisted.
-struct Enriched;
-impl FsmState for Enriched {
-    fn process_event(self: Box<Self>, _event: FsmEvent) -> Box<dyn FsmState> {
-        println!("State: Enriched -> Persisted");
-        Box::new(Persisted)
+// `Process` advances to Persisted, appending its payload to the context's `record` and writing
+// that record to `persist_path` - see `FsmError::SideEffect` for what happens when that write
+// fails. `Reject` jumps to Failed; `Retry` is invalid here too. Also the only state with a
+// `timeout`: enrichment that drags on for 5 seconds without a `Process` arriving gives up and
+// rejects itself - see `FsmRunner::run_with_clock`.
+struct Enriched {
+    persist_path: PathBuf,
+}
+
+impl Enriched {
+    fn new(persist_path: impl Into<PathBuf>) -> Self {
+        Self { persist_path: persist_path.into() }
+    }
+}
+
+impl Default for Enriched {
+    fn default() -> Self {
+        Self::new(default_persist_path())
+    }
+}
+
+impl FsmState<FsmEvent> for Enriched {
+    fn process_event(self: Box<Self>, event: FsmEvent, ctx: &mut FsmContext) -> Result<Box<dyn FsmState<FsmEvent>>, Rejection<FsmEvent>> {
+        match event {
+            FsmEvent::Process { payload } => {
+                let mut record = ctx.record.clone();
+                record.push_str(&payload);
+                if let Err(source) = std::fs::write(&self.persist_path, &record) {
+                    return Err(Rejection { state: self, error: FsmError::SideEffect { state: "Enriched", source } });
+                }
+                ctx.record = record;
+                log_line(ctx, format!("State: Enriched -> Persisted (payload: {payload})"));
+                let next: Box<dyn FsmState<FsmEvent>> = Box::new(Persisted);
+                transition(self.as_ref(), next.as_ref(), ctx);
+                Ok(next)
+            }
+            FsmEvent::Reject { reason } => {
+                log_line(ctx, format!("State: Enriched -> Failed ({reason})"));
+                let next: Box<dyn FsmState<FsmEvent>> = Box::new(Failed);
+                transition(self.as_ref(), next.as_ref(), ctx);
+                Ok(next)
+            }
+            event => Err(invalid_transition(self, event, ctx)),
+        }
     }
 
     fn name(&self) -> &'static str {
         "Enriched"
     }
+
+    fn on_enter(&self, ctx: &mut FsmContext) {
+        ctx.enriched_at = Some(Instant::now());
+        record_hook(ctx, "on_enter", self.name());
+    }
+
+    fn transitions(&self) -> Vec<(FsmEventKind, &'static str)> {
+        vec![(FsmEventKind::Process, "Persisted"), (FsmEventKind::Reject, "Failed")]
+    }
+
+    fn timeout(&self) -> Option<(Duration, FsmEvent)> {
+        Some((Duration::from_secs(5), FsmEvent::Reject { reason: "enrichment timed out".to_string() }))
+    }
 }
 
 // State: Persisted
-// This is the final state. It returns itself to indicate that no further transitions occur.
+// The final happy-path state: `Reject` can still knock it back to Failed - persisting doesn't
+// make a result immune to being rejected after the fact - but `Process` and `Retry` have no
+// transition defined from here, so both are now invalid instead of silently returning `self`.
 struct Persisted;
-impl FsmState for Persisted {
-    fn process_event(self: Box<Self>, _event: FsmEvent) -> Box<dyn FsmState> {
-        println!("State: Persisted (final state reached)");
-        self
+impl FsmState<FsmEvent> for Persisted {
+    fn process_event(self: Box<Self>, event: FsmEvent, ctx: &mut FsmContext) -> Result<Box<dyn FsmState<FsmEvent>>, Rejection<FsmEvent>> {
+        match event {
+            FsmEvent::Reject { reason } => {
+                log_line(ctx, format!("State: Persisted -> Failed ({reason})"));
+                let next: Box<dyn FsmState<FsmEvent>> = Box::new(Failed);
+                transition(self.as_ref(), next.as_ref(), ctx);
+                Ok(next)
+            }
+            event => Err(invalid_transition(self, event, ctx)),
+        }
     }
 
     fn name(&self) -> &'static str {
         "Persisted"
     }
+
+    fn is_final(&self) -> bool {
+        true
+    }
+
+    fn transitions(&self) -> Vec<(FsmEventKind, &'static str)> {
+        vec![(FsmEventKind::Reject, "Failed")]
+    }
+}
+
+// State: Failed
+// Reached from any other state via `Reject`. Only `Retry` moves it anywhere - back to Validated,
+// to start the whole pipeline over, which counts as one more attempt - `Process` and `Reject`
+// while already Failed have no transition defined and are invalid.
+struct Failed;
+impl FsmState<FsmEvent> for Failed {
+    fn process_event(self: Box<Self>, event: FsmEvent, ctx: &mut FsmContext) -> Result<Box<dyn FsmState<FsmEvent>>, Rejection<FsmEvent>> {
+        match event {
+            FsmEvent::Retry => {
+                ctx.attempts += 1;
+                log_line(ctx, format!("State: Failed -> Validated (retry #{})", ctx.attempts));
+                let next: Box<dyn FsmState<FsmEvent>> = Box::new(Validated);
+                transition(self.as_ref(), next.as_ref(), ctx);
+                Ok(next)
+            }
+            event => Err(invalid_transition(self, event, ctx)),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "Failed"
+    }
+
+    fn transitions(&self) -> Vec<(FsmEventKind, &'static str)> {
+        vec![(FsmEventKind::Retry, "Validated")]
+    }
+}
+
+// Test-only fixture: a minimal pair of states that bounce off each other forever on `Retry`,
+// deliberately livelocking `FsmRunner` so its step-limit and cycle-detection guards have
+// something to actually trip on - see the `fsm_runner_*_guard` tests below.
+#[cfg(test)]
+struct Ping;
+#[cfg(test)]
+impl FsmState<FsmEvent> for Ping {
+    fn process_event(self: Box<Self>, event: FsmEvent, ctx: &mut FsmContext) -> Result<Box<dyn FsmState<FsmEvent>>, Rejection<FsmEvent>> {
+        match event {
+            FsmEvent::Retry => {
+                log_line(ctx, "State: Ping -> Pong".to_string());
+                let next: Box<dyn FsmState<FsmEvent>> = Box::new(Pong);
+                transition(self.as_ref(), next.as_ref(), ctx);
+                Ok(next)
+            }
+            event => Err(invalid_transition(self, event, ctx)),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "Ping"
+    }
+
+    fn transitions(&self) -> Vec<(FsmEventKind, &'static str)> {
+        vec![(FsmEventKind::Retry, "Pong")]
+    }
+}
+
+#[cfg(test)]
+struct Pong;
+#[cfg(test)]
+impl FsmState<FsmEvent> for Pong {
+    fn process_event(self: Box<Self>, event: FsmEvent, ctx: &mut FsmContext) -> Result<Box<dyn FsmState<FsmEvent>>, Rejection<FsmEvent>> {
+        match event {
+            FsmEvent::Retry => {
+                log_line(ctx, "State: Pong -> Ping".to_string());
+                let next: Box<dyn FsmState<FsmEvent>> = Box::new(Ping);
+                transition(self.as_ref(), next.as_ref(), ctx);
+                Ok(next)
+            }
+            event => Err(invalid_transition(self, event, ctx)),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "Pong"
+    }
+
+    fn transitions(&self) -> Vec<(FsmEventKind, &'static str)> {
+        vec![(FsmEventKind::Retry, "Ping")]
+    }
+}
+
+// Test-only fixture: a state that legitimately transitions to itself. `Enriching(0)` needs a
+// second `Process` before it's done, so it hands back `Enriching(1)` - same `name()`, different
+// data - rather than `Persisted`. A termination check based on `name()` equality between steps
+// would mistake that self-transition for "no progress" and stop early; `is_final()` doesn't.
+#[cfg(test)]
+struct Enriching(u32);
+#[cfg(test)]
+impl FsmState<FsmEvent> for Enriching {
+    fn process_event(self: Box<Self>, event: FsmEvent, ctx: &mut FsmContext) -> Result<Box<dyn FsmState<FsmEvent>>, Rejection<FsmEvent>> {
+        match event {
+            FsmEvent::Process { payload } if self.0 == 0 => {
+                log_line(ctx, format!("State: Enriching -> Enriching (pass 1, payload: {payload})"));
+                let next: Box<dyn FsmState<FsmEvent>> = Box::new(Enriching(1));
+                transition(self.as_ref(), next.as_ref(), ctx);
+                Ok(next)
+            }
+            FsmEvent::Process { payload } => {
+                log_line(ctx, format!("State: Enriching -> Persisted (payload: {payload})"));
+                let next: Box<dyn FsmState<FsmEvent>> = Box::new(Persisted);
+                transition(self.as_ref(), next.as_ref(), ctx);
+                Ok(next)
+            }
+            event => Err(invalid_transition(self, event, ctx)),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "Enriching"
+    }
+
+    fn transitions(&self) -> Vec<(FsmEventKind, &'static str)> {
+        if self.0 == 0 {
+            vec![(FsmEventKind::Process, "Enriching")]
+        } else {
+            vec![(FsmEventKind::Process, "Persisted")]
+        }
+    }
+}
+
+/// Appends `line` to the context's log, so every successful transition ends up there without
+/// each state repeating the bookkeeping. States no longer print directly - `FsmRunner` owns all
+/// of this example's console output, see `TraceMode`.
+fn log_line(ctx: &mut FsmContext, line: String) {
+    ctx.log.push(line);
+}
+
+/// Builds the `FsmError` for a transition that isn't defined, logging the rejection before
+/// returning it so a failed attempt still shows up in `ctx.log`.
+fn invalid_transition<E: FsmEventLike>(state: Box<dyn FsmState<E>>, event: E, ctx: &mut FsmContext) -> Rejection<E> {
+    let name = state.name();
+    let event = format!("{event:?}");
+    log_line(ctx, format!("State: {name} rejected event {event}"));
+    Rejection { state, error: FsmError::InvalidTransition { state: name, event } }
+}
+
+/// Appends one `"{phase}:{state}"` entry to `ctx.hooks` - the default `on_enter`/`on_exit` just
+/// call this, and overrides that do real work (see `Enriched::on_enter`) call it too so the hook
+/// history stays complete either way.
+fn record_hook(ctx: &mut FsmContext, phase: &str, state: &'static str) {
+    ctx.hooks.push(format!("{phase}:{state}"));
+}
+
+/// Fires `old.on_exit` then `new.on_enter` around a transition, but only when it actually lands
+/// on a different state - a state that transitions to itself shouldn't see itself leave and
+/// immediately re-enter.
+fn transition<E: FsmEventLike>(old: &dyn FsmState<E>, new: &dyn FsmState<E>, ctx: &mut FsmContext) {
+    if old.name() != new.name() {
+        old.on_exit(ctx);
+        new.on_enter(ctx);
+    }
+}
+
+/// Lets a downstream crate add states (and the transitions that lead into them) to this FSM
+/// without editing any of the states declared above - the thing the module-level comment at the
+/// top of this file promises but, until now, never demonstrated. `factories` is how
+/// `resume_from` and the runner's placeholder-swap in `apply` build a named state back up - every
+/// state a `FsmRunner<E>` is ever asked to hold must have a factory registered here, including
+/// the one it starts in, since the placeholder-swap in `apply` needs to rebuild it by name
+/// unconditionally, not just on an invalid transition. `extension_transitions` is how an
+/// *existing* state like `Persisted` grows a new outgoing edge (e.g. to `Archived`) without its
+/// own `process_event` knowing the new state exists - `apply` consults this table before ever
+/// calling `process_event`. See the `archived` module below for a worked example.
+/// A state constructor - boxed so `StateRegistry` can hold one per registered state name.
+type StateFactory<E> = Box<dyn Fn() -> Box<dyn FsmState<E>>>;
+
+pub struct StateRegistry<E: FsmEventLike> {
+    factories: HashMap<&'static str, StateFactory<E>>,
+    extension_transitions: HashMap<(&'static str, E::Kind), &'static str>,
+}
+
+impl<E: FsmEventLike> StateRegistry<E> {
+    /// An empty registry with no states and no extension transitions registered.
+    pub fn new() -> Self {
+        Self { factories: HashMap::new(), extension_transitions: HashMap::new() }
+    }
+
+    /// Builds a fresh boxed state named `name`, or `None` if no factory was ever registered for
+    /// it.
+    fn create(&self, name: &str) -> Option<Box<dyn FsmState<E>>> {
+        self.factories.get(name).map(|factory| factory())
+    }
+
+    /// The state an extension transition leads to from `from` on `event`, if one was registered -
+    /// see `register_transition`.
+    fn extension_transition(&self, from: &'static str, event: E::Kind) -> Option<&'static str> {
+        self.extension_transitions.get(&(from, event)).copied()
+    }
+}
+
+impl<E: FsmEventLike> Default for StateRegistry<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StateRegistry<FsmEvent> {
+    /// A registry pre-populated with the four production states this file ships with, so callers
+    /// that only want to add extensions don't have to re-register `Validated`/`Enriched`/etc.
+    /// themselves. Specific to `E = FsmEvent` - a registry for another event type starts from
+    /// `StateRegistry::new()` and registers its own states, see the byte-event demo below.
+    pub fn with_builtin_states() -> Self {
+        let mut registry = Self::new();
+        register_state(&mut registry, "Validated", || Box::new(Validated));
+        register_state(&mut registry, "Enriched", || Box::new(Enriched::default()));
+        register_state(&mut registry, "Persisted", || Box::new(Persisted));
+        register_state(&mut registry, "Failed", || Box::new(Failed));
+        registry
+    }
+}
+
+/// Registers `factory` under `name` so `registry` can build that state by name later - from
+/// `resume_from`, from `FsmRunner::apply`'s placeholder swap, or as the target of a
+/// `register_transition` edge.
+pub fn register_state<E: FsmEventLike>(registry: &mut StateRegistry<E>, name: &'static str, factory: impl Fn() -> Box<dyn FsmState<E>> + 'static) {
+    registry.factories.insert(name, Box::new(factory));
+}
+
+/// Registers an edge from an existing state into a registered one, without touching the existing
+/// state's own `process_event`: `FsmRunner::apply` checks this table for `(from, event.kind())`
+/// before ever calling `process_event`, so a match here short-circuits and reaches `to` directly.
+pub fn register_transition<E: FsmEventLike>(registry: &mut StateRegistry<E>, from: &'static str, event: E::Kind, to: &'static str) {
+    registry.extension_transitions.insert((from, event), to);
+}
+
+/// Reconstructs the boxed state named by a persisted `StateToken`, used to resume a run after a
+/// restart. Fails if `token` doesn't name a state `registry` knows about, e.g. it was produced by
+/// an older version of this file or by a registry that never registered the extension state the
+/// token names.
+pub fn resume_from<E: FsmEventLike>(token: StateToken, registry: &StateRegistry<E>) -> Result<Box<dyn FsmState<E>>, FsmError> {
+    registry.create(&token.0).ok_or(FsmError::UnknownStateToken(token.0))
+}
+
+/// Renders the transition graph implied by every state's `transitions()` as a Graphviz DOT
+/// digraph - pipe the output to `dot -Tpng` to render it. Edges are deduplicated and sorted
+/// lexicographically, so the result doesn't depend on the order `states` were passed in, which
+/// keeps it deterministic for tests (and diff-friendly if ever checked in).
+pub fn export_dot<E: FsmEventLike>(states: &[Box<dyn FsmState<E>>]) -> String {
+    let mut edges: Vec<String> = states
+        .iter()
+        .flat_map(|state| {
+            let from = state.name();
+            state.transitions().into_iter().map(move |(kind, to)| format!("  \"{from}\" -> \"{to}\" [label=\"{kind:?}\"];"))
+        })
+        .collect();
+    edges.sort();
+    edges.dedup();
+
+    let mut dot = String::from("digraph Fsm {\n");
+    for edge in &edges {
+        dot.push_str(edge);
+        dot.push('\n');
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Looks for the shortest period `p` (up to a third of the window) whose last `3p` visited names
+/// are three identical back-to-back copies of the same pattern. Requiring three repeats rather
+/// than two is what keeps this from flagging a legitimate double `Reject`-then-`Retry` - that
+/// revisits `Validated`/`Failed` twice in a row but stops there, whereas a real livelock like `A`
+/// ping-ponging with `B` keeps going. `recent` only ever holds the last `cycle_window` state
+/// names, so once a livelock has repeated three times this reports it long before `max_steps`
+/// would ever fire.
+fn detect_cycle(recent: &VecDeque<&'static str>) -> Option<Vec<&'static str>> {
+    let len = recent.len();
+    (1..=len / 3).find_map(|period| {
+        let block = |n: usize| recent.iter().skip(len - n * period).take(period);
+        (block(1).eq(block(2)) && block(2).eq(block(3))).then(|| block(1).copied().collect())
+    })
+}
+
+/// The result of `FsmRunner::run_events`: the state it stopped in, and whether the event source
+/// still had items left when it stopped - `true` means the run reached `is_final()` before the
+/// source ran dry, `false` means the source ran dry first (or the run errored before either).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunOutcome {
+    pub final_state: &'static str,
+    pub events_left_unconsumed: bool,
+}
+
+/// One completed transition: which state it left, which state it landed in, the event that
+/// caused it, and when the runner's clock says it happened.
+#[derive(Debug, Clone)]
+pub struct TransitionRecord {
+    pub from: &'static str,
+    pub to: &'static str,
+    pub event: String,
+    pub at: Instant,
+}
+
+/// A `max_steps`/`cycle_window` pair generous enough to never trip on any run this file's own
+/// `main` or tests drive, while still catching a runaway caller within a few thousand events.
+const DEFAULT_MAX_STEPS: usize = 10_000;
+const DEFAULT_CYCLE_WINDOW: usize = 9;
+
+/// How much `FsmRunner` writes to its `writer` as it applies events - states themselves no
+/// longer print anything (see `log_line`), so this is the only place output comes from.
+/// `Silent` writes nothing, `Compact` writes one `"{from} -> {to} ({event})"` line per step (or
+/// `"{from} rejected {event}"` on an invalid transition), and `Verbose` adds a `{ctx:?}` snapshot
+/// under each of those lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TraceMode {
+    Silent,
+    #[default]
+    Compact,
+    Verbose,
+}
+
+/// Owns the FSM's current state and context, and replaces the old free-standing driving
+/// function with something that can also remember how it got here. The clock is injectable so
+/// tests can drive it with deterministic timestamps instead of the real one. `max_steps` and
+/// `cycle_window` guard against a caller driving `run` with an event source that never stops -
+/// see `FsmRunner::with_limits`. `writer`/`trace_mode` control where (and how much) trace output
+/// goes - see `TraceMode` and `FsmRunner::with_trace`.
+pub struct FsmRunner<E: FsmEventLike> {
+    state: Box<dyn FsmState<E>>,
+    ctx: FsmContext,
+    clock: Box<dyn Fn() -> Instant>,
+    history: Vec<TransitionRecord>,
+    steps: usize,
+    max_steps: usize,
+    cycle_window: usize,
+    recent: VecDeque<&'static str>,
+    registry: StateRegistry<E>,
+    writer: Box<dyn Write>,
+    trace_mode: TraceMode,
+    /// When the current state was entered, as reported by `self.clock` - set once at
+    /// construction for the initial state and refreshed on every real transition in `land`, so
+    /// `maybe_apply_timeout` always measures time since the state we're actually in, however it
+    /// got there.
+    entered_at: Instant,
+}
+
+impl<E: FsmEventLike> FsmRunner<E> {
+    /// Starts a runner exactly like `with_trace`, but with `registry` standing in for the
+    /// implicit built-in-only one - pass a registry extended with `register_state`/
+    /// `register_transition` (see the `archived` module below) to let the runner drive into
+    /// states this file never declared. Traces in `TraceMode::Compact` to stdout; see
+    /// `with_trace` to redirect or quiet that. The only constructor available for an event type
+    /// other than `FsmEvent` - see the byte-event demo below - since there's no built-in registry
+    /// to default to for a type this file never shipped states for.
+    pub fn with_registry(
+        state: Box<dyn FsmState<E>>,
+        clock: impl Fn() -> Instant + 'static,
+        max_steps: usize,
+        cycle_window: usize,
+        registry: StateRegistry<E>,
+    ) -> Self {
+        Self::with_trace(state, clock, max_steps, cycle_window, registry, TraceMode::Compact, Box::new(io::stdout()))
+    }
+
+    /// Starts a runner exactly like `with_registry`, but writing its trace output to `writer`
+    /// according to `trace_mode` instead of always printing `Compact` to stdout - tests use this
+    /// with `TraceMode::Silent` and an in-memory sink to assert that a run produces zero output.
+    pub fn with_trace(
+        state: Box<dyn FsmState<E>>,
+        clock: impl Fn() -> Instant + 'static,
+        max_steps: usize,
+        cycle_window: usize,
+        registry: StateRegistry<E>,
+        trace_mode: TraceMode,
+        writer: Box<dyn Write>,
+    ) -> Self {
+        let mut ctx = FsmContext::default();
+        state.on_enter(&mut ctx);
+        let mut recent = VecDeque::with_capacity(cycle_window);
+        recent.push_back(state.name());
+        let entered_at = clock();
+        Self {
+            state,
+            ctx,
+            clock: Box::new(clock),
+            history: Vec::new(),
+            steps: 0,
+            max_steps,
+            cycle_window,
+            recent,
+            registry,
+            writer,
+            trace_mode,
+            entered_at,
+        }
+    }
+
+    /// Writes `line` to `self.writer` unless `trace_mode` is `Silent`, following it with a
+    /// `{ctx:?}` snapshot when `trace_mode` is `Verbose`. Errors writing to `writer` are ignored,
+    /// same as a `println!` would be.
+    fn trace(&mut self, line: &str) {
+        if self.trace_mode == TraceMode::Silent {
+            return;
+        }
+        let _ = writeln!(self.writer, "{line}");
+        if self.trace_mode == TraceMode::Verbose {
+            let _ = writeln!(self.writer, "  context: {:?}", self.ctx);
+        }
+    }
+
+    /// Applies a single event, appending a `TransitionRecord` when it actually changes state. If
+    /// `self.registry` has an extension transition registered for `(from, event.kind())`, that
+    /// wins over the current state's own `process_event` - this is how a registered state like
+    /// `Archived` (see the `archived` module) becomes reachable from `Persisted` without
+    /// `Persisted::process_event` ever mentioning it. On rejection `process_event` hands `self`
+    /// back via `Rejection`, and that's what goes back into `self.state` - not a fresh state
+    /// rebuilt from `self.registry` - so a caller that retries a rejected event retries against
+    /// the exact instance that rejected it (e.g. `Enriched::persist_path` survives a failed
+    /// `Process`), not whatever the registry would default to. A transition that trips the step
+    /// limit or cycle detector still lands (`state()` reflects it) but is reported as an error
+    /// instead of being added to `history`. Every outcome is traced via `self.trace` according to
+    /// `self.trace_mode`.
+    pub fn apply(&mut self, event: E) -> Result<(), FsmError> {
+        let from = self.state.name();
+        let event_str = format!("{event:?}");
+        if let Some(to) = self.registry.extension_transition(from, event.kind()) {
+            let next = self.registry.create(to).expect("extension transition target must be registered");
+            transition(self.state.as_ref(), next.as_ref(), &mut self.ctx);
+            log_line(&mut self.ctx, format!("State: {from} -> {to} ({event_str})"));
+            self.state = next;
+            self.trace(&format!("{from} -> {to} ({event_str})"));
+            return self.land(from, to, event_str);
+        }
+        // A placeholder only for the instant it takes `process_event` to hand either the next
+        // state or this same state (via `Rejection::state`) back - never observable from outside
+        // `apply`, since both match arms below overwrite it before returning.
+        let current = std::mem::replace(
+            &mut self.state,
+            self.registry.create(from).expect("every state driven through FsmRunner must be registered"),
+        );
+        match current.process_event(event, &mut self.ctx) {
+            Ok(next) => {
+                let to = next.name();
+                self.state = next;
+                self.trace(&format!("{from} -> {to} ({event_str})"));
+                self.land(from, to, event_str)
+            }
+            Err(Rejection { state, error }) => {
+                self.state = state;
+                self.trace(&format!("{from} rejected {event_str}"));
+                Err(error)
+            }
+        }
+    }
+
+    /// Shared tail of `apply`'s two paths (extension-table hit and normal `process_event`): skips
+    /// bookkeeping for a self-transition, advances the step/cycle guards, and records history.
+    /// Also refreshes `entered_at` to the same timestamp recorded in `history`, so
+    /// `maybe_apply_timeout` always measures time since the state we're actually in.
+    fn land(&mut self, from: &'static str, to: &'static str, event_str: String) -> Result<(), FsmError> {
+        if to == from {
+            return Ok(());
+        }
+        let now = (self.clock)();
+        self.entered_at = now;
+        self.steps += 1;
+        self.recent.push_back(to);
+        if self.recent.len() > self.cycle_window {
+            self.recent.pop_front();
+        }
+        if let Some(cycle) = detect_cycle(&self.recent) {
+            return Err(FsmError::CycleDetected(cycle));
+        }
+        if self.steps > self.max_steps {
+            return Err(FsmError::StepLimitExceeded { steps: self.steps });
+        }
+        self.history.push(TransitionRecord { from, to, event: event_str, at: now });
+        Ok(())
+    }
+
+    /// Applies `events` in order, stopping at the first invalid transition.
+    pub fn run(&mut self, events: impl IntoIterator<Item = E>) -> Result<(), FsmError> {
+        for event in events {
+            self.apply(event)?;
+        }
+        Ok(())
+    }
+
+    /// Like `run`, but for an `events` source that keeps producing events past the point where
+    /// the FSM is actually done: stops feeding `events` in as soon as `is_final()` becomes true,
+    /// rather than driving every remaining event through a state that has nothing left to do.
+    /// Reports whether `events` still had items left when that happened, via
+    /// `RunOutcome::events_left_unconsumed` - useful for a caller that wants to know if it handed
+    /// the FSM more work than it needed.
+    pub fn run_events(&mut self, events: impl IntoIterator<Item = E>) -> Result<RunOutcome, FsmError> {
+        let mut events = events.into_iter().peekable();
+        while !self.is_final() {
+            let Some(event) = events.next() else { break };
+            self.apply(event)?;
+        }
+        Ok(RunOutcome { final_state: self.state().name(), events_left_unconsumed: events.peek().is_some() })
+    }
+
+    /// Like `run`, but checks `FsmState::timeout()` before applying each event in `events`: if
+    /// `self.clock`'s idea of "now" has already crossed the current state's deadline, the
+    /// timeout event is synthesized and applied first (via `apply`, so it's traced and recorded
+    /// like any other transition). There's no background timer here - the deadline is only ever
+    /// checked at these synchronous checkpoints, between events `events` actually supplies.
+    pub fn run_with_clock(&mut self, events: impl IntoIterator<Item = E>) -> Result<(), FsmError> {
+        for event in events {
+            self.maybe_apply_timeout()?;
+            self.apply(event)?;
+        }
+        Ok(())
+    }
+
+    /// Applies the current state's timeout event if one is configured and its deadline has
+    /// passed since `entered_at`. Uses `saturating_duration_since` rather than `duration_since`
+    /// so an injected test clock that's set backwards by mistake reads as "no time has passed"
+    /// instead of panicking.
+    fn maybe_apply_timeout(&mut self) -> Result<(), FsmError> {
+        let Some((duration, timeout_event)) = self.state.timeout() else {
+            return Ok(());
+        };
+        let now = (self.clock)();
+        if now.saturating_duration_since(self.entered_at) >= duration {
+            self.apply(timeout_event)?;
+        }
+        Ok(())
+    }
+
+    pub fn state(&self) -> &dyn FsmState<E> {
+        self.state.as_ref()
+    }
+
+    /// True once the current state reports itself as final - see `FsmState::is_final`.
+    pub fn is_final(&self) -> bool {
+        self.state.is_final()
+    }
+
+    pub fn context(&self) -> &FsmContext {
+        &self.ctx
+    }
+
+    pub fn history(&self) -> &[TransitionRecord] {
+        &self.history
+    }
+
+    /// Renders `history()` as a `from,to,event` CSV table, header included.
+    pub fn history_csv(&self) -> String {
+        let mut csv = String::from("from,to,event\n");
+        for record in &self.history {
+            csv.push_str(&format!("{},{},{}\n", record.from, record.to, record.event));
+        }
+        csv
+    }
+}
+
+impl FsmRunner<FsmEvent> {
+    /// Starts a runner in `state`, using the real wall clock for transition timestamps and the
+    /// default step/cycle limits.
+    pub fn new(state: Box<dyn FsmState<FsmEvent>>) -> Self {
+        Self::with_clock(state, Instant::now)
+    }
+
+    /// Starts a runner in `state`, timestamping transitions with `clock` instead of the real
+    /// clock - the hook tests use this to get a predictable `history()`. Uses the default
+    /// step/cycle limits; see `with_limits` to tighten them.
+    pub fn with_clock(state: Box<dyn FsmState<FsmEvent>>, clock: impl Fn() -> Instant + 'static) -> Self {
+        Self::with_limits(state, clock, DEFAULT_MAX_STEPS, DEFAULT_CYCLE_WINDOW)
+    }
+
+    /// Starts a runner in `state` with an explicit `max_steps` and `cycle_window`. `apply` (and
+    /// therefore `run`) fails with `FsmError::CycleDetected` once a short pattern of state names
+    /// has repeated three times in a row within the last `cycle_window` transitions (see
+    /// `detect_cycle`), or with `FsmError::StepLimitExceeded` once more than `max_steps`
+    /// transitions have landed - whichever trips first. The cycle check runs first, so a short
+    /// livelock like `A` bouncing with `B` is reported long before `max_steps` would ever matter.
+    /// Has no extension states of its own - see `with_registry` for that. Specific to
+    /// `E = FsmEvent` since it defaults to `StateRegistry::with_builtin_states()`; a runner for
+    /// another event type starts from `with_registry` with its own registry instead.
+    pub fn with_limits(state: Box<dyn FsmState<FsmEvent>>, clock: impl Fn() -> Instant + 'static, max_steps: usize, cycle_window: usize) -> Self {
+        Self::with_registry(state, clock, max_steps, cycle_window, StateRegistry::with_builtin_states())
+    }
 }
 
-// Runs the state machine starting from the Validated state.
-// Repeatedly applies the same event and transitions between states.
-// Stops when the FSM reaches a state that does not change (final state).
-fn process_event(event: FsmEvent) {
-    let mut state: Box<dyn FsmState> = Box::new(Validated);
+/// A downstream-crate-style extension: adds an `Archived` state reachable from `Persisted` via
+/// `FsmEvent::Archive`, without editing `Persisted`'s own `process_event` - proving out the claim
+/// in the comment at the top of this file that the trait approach "allows other crates to add
+/// states". Everything here is built only out of `StateRegistry`/`register_state`/
+/// `register_transition`, the same extension points a real downstream crate would have.
+#[cfg(test)]
+mod archived {
+    use super::{invalid_transition, register_state, register_transition, FsmContext, FsmEvent, FsmEventKind, FsmState, Rejection, StateRegistry};
+
+    /// Terminal like `Persisted`, but one step further along: nothing transitions out of it.
+    struct Archived;
+    impl FsmState<FsmEvent> for Archived {
+        fn process_event(self: Box<Self>, event: FsmEvent, ctx: &mut FsmContext) -> Result<Box<dyn FsmState<FsmEvent>>, Rejection<FsmEvent>> {
+            Err(invalid_transition(self, event, ctx))
+        }
+
+        fn name(&self) -> &'static str {
+            "Archived"
+        }
+
+        fn is_final(&self) -> bool {
+            true
+        }
+
+        fn transitions(&self) -> Vec<(FsmEventKind, &'static str)> {
+            Vec::new()
+        }
+    }
+
+    /// Registers `Archived` and the `Persisted --Archive--> Archived` edge onto `registry`.
+    pub fn extend(registry: &mut StateRegistry<FsmEvent>) {
+        register_state(registry, "Archived", || Box::new(Archived));
+        register_transition(registry, "Persisted", FsmEventKind::Archive, "Archived");
+    }
+}
+
+/// A second, unrelated instantiation of `FsmState`/`FsmRunner` with `E = u8` instead of
+/// `FsmEvent` - proof that the trait and the runner really are generic, not just generic-shaped
+/// around this file's one event type. A byte stream is scanned a line at a time: `Scanning`
+/// stays put on any byte but a newline, and hands off to `LineEnded` on one; `LineEnded` hands
+/// right back to `Scanning` on the next byte, so driving a whole buffer through just counts
+/// lines. `ByteEventKind` is `u8`'s own `FsmEventLike::Kind` - deliberately not `FsmEventKind`,
+/// to show that a new event type brings its own kind type rather than being forced into the
+/// document pipeline's.
+mod byte_events {
+    use super::{transition, FsmContext, FsmEventLike, FsmState, Rejection};
+    use std::fmt::Debug;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum ByteEventKind {
+        Newline,
+        Other,
+    }
+
+    impl FsmEventLike for u8 {
+        type Kind = ByteEventKind;
+
+        fn kind(&self) -> ByteEventKind {
+            if *self == b'\n' {
+                ByteEventKind::Newline
+            } else {
+                ByteEventKind::Other
+            }
+        }
+    }
+
+    pub struct Scanning;
+    impl FsmState<u8> for Scanning {
+        fn process_event(self: Box<Self>, byte: u8, ctx: &mut FsmContext) -> Result<Box<dyn FsmState<u8>>, Rejection<u8>> {
+            let next: Box<dyn FsmState<u8>> = if byte.kind() == ByteEventKind::Newline { Box::new(LineEnded) } else { Box::new(Scanning) };
+            transition(self.as_ref() as &dyn FsmState<u8>, next.as_ref(), ctx);
+            Ok(next)
+        }
+
+        fn name(&self) -> &'static str {
+            "Scanning"
+        }
+
+        fn transitions(&self) -> Vec<(ByteEventKind, &'static str)> {
+            vec![(ByteEventKind::Newline, "LineEnded"), (ByteEventKind::Other, "Scanning")]
+        }
+    }
 
-    loop {
-        // Save the current state's name before moving to the next state
-        let current_name = state.name();
-        let next = state.process_event(event.clone());
+    pub struct LineEnded;
+    impl FsmState<u8> for LineEnded {
+        fn process_event(self: Box<Self>, byte: u8, ctx: &mut FsmContext) -> Result<Box<dyn FsmState<u8>>, Rejection<u8>> {
+            let next: Box<dyn FsmState<u8>> = if byte.kind() == ByteEventKind::Newline { Box::new(LineEnded) } else { Box::new(Scanning) };
+            transition(self.as_ref() as &dyn FsmState<u8>, next.as_ref(), ctx);
+            Ok(next)
+        }
 
-        // If the state hasn't changed, we assume we've reached the final state
-        if current_name == next.name() {
-            println!("Final state: {}", next.name());
-            break;
+        fn name(&self) -> &'static str {
+            "LineEnded"
         }
 
-        state = next;
+        fn transitions(&self) -> Vec<(ByteEventKind, &'static str)> {
+            vec![(ByteEventKind::Newline, "LineEnded"), (ByteEventKind::Other, "Scanning")]
+        }
     }
 }
 
 fn main() {
     println!("--- Traits-based State Machine Demo ---");
-    process_event(FsmEvent::Process);
+    let mut runner = FsmRunner::new(Box::new(Validated));
+    runner.apply(FsmEvent::Process { payload: "order-42".to_string() }).unwrap();
+    let token_json = serde_json::to_string(&runner.state().to_token()).expect("token should serialize");
+    println!("Transitioned to {} and persisted token {token_json}", runner.state().name());
+
+    // Simulate the process restarting: the in-memory runner is gone, only `token_json` survives
+    // (e.g. read back from a database row).
+    drop(runner);
+    let token: StateToken = serde_json::from_str(&token_json).expect("token should deserialize");
+    let resumed_state = resume_from(token, &StateRegistry::with_builtin_states()).expect("token should name a known state");
+    println!("Resumed in state: {}", resumed_state.name());
+
+    let mut runner = FsmRunner::new(resumed_state);
+    let result = runner.run([
+        FsmEvent::Process { payload: "order-42".to_string() },
+        FsmEvent::Reject { reason: "downstream timeout".to_string() },
+        FsmEvent::Retry,
+        FsmEvent::Process { payload: "order-42".to_string() },
+        FsmEvent::Process { payload: "order-42".to_string() },
+    ]);
+    match result {
+        Ok(()) => println!("Final state: {}", runner.state().name()),
+        Err(err) => println!("Run stopped early: {err}"),
+    }
+    println!("Accumulated log:");
+    for line in &runner.context().log {
+        println!("  {line}");
+    }
+    println!("Transition history:");
+    print!("{}", runner.history_csv());
+
+    println!("Graphviz DOT (pipe to `dot -Tpng` to render):");
+    let states: Vec<Box<dyn FsmState<FsmEvent>>> = vec![Box::new(Validated), Box::new(Enriched::default()), Box::new(Persisted), Box::new(Failed)];
+    print!("{}", export_dot(&states));
+
+    println!("Driving a fresh run via run_events:");
+    let mut runner = FsmRunner::new(Box::new(Validated));
+    let outcome = runner
+        .run_events([
+            FsmEvent::Process { payload: "order-43".to_string() },
+            FsmEvent::Reject { reason: "downstream timeout".to_string() },
+            FsmEvent::Retry,
+            FsmEvent::Process { payload: "order-43".to_string() },
+            FsmEvent::Process { payload: "order-43".to_string() },
+            FsmEvent::Reject { reason: "too late, already persisted".to_string() },
+        ])
+        .unwrap();
+    println!(
+        "Path: {} (stopped in {}, events left unconsumed: {})",
+        runner.history().iter().map(|r| r.to).collect::<Vec<_>>().join(" -> "),
+        outcome.final_state,
+        outcome.events_left_unconsumed
+    );
+
+    println!("Driving run_with_clock through Enriched's 5-second timeout:");
+    let now = std::rc::Rc::new(std::cell::Cell::new(Instant::now()));
+    let clock = {
+        let now = std::rc::Rc::clone(&now);
+        move || now.get()
+    };
+    let mut runner = FsmRunner::with_clock(Box::new(Enriched::default()), clock);
+    now.set(now.get() + Duration::from_secs(6));
+    match runner.run_with_clock([FsmEvent::Retry]) {
+        Ok(()) => println!("Final state: {}", runner.state().name()),
+        Err(err) => println!("Run stopped early: {err} (landed in {})", runner.state().name()),
+    }
+
+    println!("Same runner, a different event type (E = u8), counting lines in a byte stream:");
+    let mut registry = StateRegistry::new();
+    register_state(&mut registry, "Scanning", || Box::new(byte_events::Scanning));
+    register_state(&mut registry, "LineEnded", || Box::new(byte_events::LineEnded));
+    let mut runner = FsmRunner::with_registry(Box::new(byte_events::Scanning), Instant::now, DEFAULT_MAX_STEPS, DEFAULT_CYCLE_WINDOW, registry);
+    runner.run(b"ab\ncd\n\nef".iter().copied()).unwrap();
+    let lines = runner.history().iter().filter(|record| record.to == "LineEnded").count();
+    println!("Lines seen: {lines}, final state: {}", runner.state().name());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn process_advances_validated_through_enriched_to_persisted() {
+        let state: Box<dyn FsmState<FsmEvent>> = Box::new(Validated);
+        let mut ctx = FsmContext::default();
+        let state = state.process_event(FsmEvent::Process { payload: "p".to_string() }, &mut ctx).unwrap();
+        assert_eq!(state.name(), "Enriched");
+        let state = state.process_event(FsmEvent::Process { payload: "p".to_string() }, &mut ctx).unwrap();
+        assert_eq!(state.name(), "Persisted");
+    }
+
+    #[test]
+    fn process_on_persisted_is_an_invalid_transition() {
+        let state: Box<dyn FsmState<FsmEvent>> = Box::new(Persisted);
+        let mut ctx = FsmContext::default();
+        let Err(rejection) = state.process_event(FsmEvent::Process { payload: "p".to_string() }, &mut ctx) else {
+            panic!("expected Process on Persisted to be an invalid transition");
+        };
+        match rejection.error {
+            FsmError::InvalidTransition { state, event } => {
+                assert_eq!(state, "Persisted");
+                assert_eq!(event, format!("{:?}", FsmEvent::Process { payload: "p".to_string() }));
+            }
+            other => panic!("expected InvalidTransition, got {other:?}"),
+        }
+        assert_eq!(rejection.state.name(), "Persisted");
+    }
+
+    #[test]
+    fn reject_moves_validated_enriched_and_persisted_to_failed() {
+        for start in [Box::new(Validated) as Box<dyn FsmState<FsmEvent>>, Box::new(Enriched::default()), Box::new(Persisted)] {
+            let mut ctx = FsmContext::default();
+            let state = start.process_event(FsmEvent::Reject { reason: "bad".to_string() }, &mut ctx).unwrap();
+            assert_eq!(state.name(), "Failed");
+        }
+    }
+
+    #[test]
+    fn retry_moves_failed_back_to_validated() {
+        let state: Box<dyn FsmState<FsmEvent>> = Box::new(Failed);
+        let mut ctx = FsmContext::default();
+        let state = state.process_event(FsmEvent::Retry, &mut ctx).unwrap();
+        assert_eq!(state.name(), "Validated");
+    }
+
+    #[test]
+    fn retry_is_an_invalid_transition_outside_of_failed() {
+        for start in [Box::new(Validated) as Box<dyn FsmState<FsmEvent>>, Box::new(Enriched::default()), Box::new(Persisted)] {
+            let name_before = start.name();
+            let mut ctx = FsmContext::default();
+            let Err(rejection) = start.process_event(FsmEvent::Retry, &mut ctx) else {
+                panic!("expected Retry on {name_before} to be an invalid transition");
+            };
+            match rejection.error {
+                FsmError::InvalidTransition { state, event } => {
+                    assert_eq!(state, name_before);
+                    assert_eq!(event, "Retry");
+                }
+                other => panic!("expected InvalidTransition, got {other:?}"),
+            }
+            assert_eq!(rejection.state.name(), name_before);
+        }
+    }
+
+    #[test]
+    fn a_full_sequence_of_events_ends_up_persisted_after_a_reject_and_retry() {
+        let mut runner = FsmRunner::new(Box::new(Validated));
+        runner
+            .run([
+                FsmEvent::Process { payload: "p".to_string() },
+                FsmEvent::Reject { reason: "oops".to_string() },
+                FsmEvent::Retry,
+                FsmEvent::Process { payload: "p".to_string() },
+                FsmEvent::Process { payload: "p".to_string() },
+            ])
+            .unwrap();
+        assert_eq!(runner.state().name(), "Persisted");
+    }
+
+    #[test]
+    fn name_at_each_step_of_a_process_reject_retry_sequence() {
+        let mut state: Box<dyn FsmState<FsmEvent>> = Box::new(Validated);
+        let mut ctx = FsmContext::default();
+        let mut names = Vec::new();
+        for event in [
+            FsmEvent::Process { payload: "p".to_string() },
+            FsmEvent::Process { payload: "p".to_string() },
+            FsmEvent::Reject { reason: "oops".to_string() },
+            FsmEvent::Retry,
+            FsmEvent::Process { payload: "p".to_string() },
+        ] {
+            state = state.process_event(event, &mut ctx).unwrap();
+            names.push(state.name());
+        }
+        assert_eq!(names, ["Enriched", "Persisted", "Failed", "Validated", "Enriched"]);
+    }
+
+    #[test]
+    fn runner_stops_at_the_first_invalid_transition_and_reports_it() {
+        let mut runner = FsmRunner::new(Box::new(Validated));
+        let Err(err) = runner.run([
+            FsmEvent::Process { payload: "p".to_string() },
+            FsmEvent::Process { payload: "p".to_string() },
+            FsmEvent::Process { payload: "p".to_string() },
+        ]) else {
+            panic!("expected the third Process event to be an invalid transition");
+        };
+        match err {
+            FsmError::InvalidTransition { state, event } => {
+                assert_eq!(state, "Persisted");
+                assert_eq!(event, format!("{:?}", FsmEvent::Process { payload: "p".to_string() }));
+            }
+            other => panic!("expected InvalidTransition, got {other:?}"),
+        }
+        // The runner must still report the state it stopped in, not a lost/placeholder one.
+        assert_eq!(runner.state().name(), "Persisted");
+    }
+
+    #[test]
+    fn context_accumulates_record_log_and_attempts_across_a_full_run() {
+        let mut runner = FsmRunner::new(Box::new(Validated));
+        runner
+            .run([
+                FsmEvent::Process { payload: "order-42".to_string() },
+                FsmEvent::Reject { reason: "downstream timeout".to_string() },
+                FsmEvent::Retry,
+                FsmEvent::Process { payload: "order-42".to_string() },
+                FsmEvent::Process { payload: "order-42".to_string() },
+            ])
+            .unwrap();
+        assert_eq!(runner.state().name(), "Persisted");
+        assert_eq!(runner.context().record, "order-42");
+        assert_eq!(runner.context().attempts, 1);
+        assert_eq!(runner.context().log.len(), 5);
+    }
+
+    #[test]
+    fn hook_call_order_for_a_two_transition_run_includes_the_initial_on_enter() {
+        let mut runner = FsmRunner::new(Box::new(Validated));
+        runner
+            .run([FsmEvent::Process { payload: "p".to_string() }, FsmEvent::Process { payload: "p".to_string() }])
+            .unwrap();
+        assert_eq!(runner.state().name(), "Persisted");
+        assert_eq!(
+            runner.context().hooks,
+            vec!["on_enter:Validated", "on_exit:Validated", "on_enter:Enriched", "on_exit:Enriched", "on_enter:Persisted"]
+        );
+        assert!(runner.context().enriched_at.is_some());
+    }
+
+    #[test]
+    fn attempts_increments_once_per_retry() {
+        let mut runner = FsmRunner::new(Box::new(Validated));
+        runner
+            .run([
+                FsmEvent::Reject { reason: "bad-1".to_string() },
+                FsmEvent::Retry,
+                FsmEvent::Reject { reason: "bad-2".to_string() },
+                FsmEvent::Retry,
+            ])
+            .unwrap();
+        assert_eq!(runner.context().attempts, 2);
+    }
+
+    #[test]
+    fn history_records_the_from_to_pairs_for_the_validated_enriched_persisted_path() {
+        let mut runner = FsmRunner::new(Box::new(Validated));
+        runner
+            .run([FsmEvent::Process { payload: "p".to_string() }, FsmEvent::Process { payload: "p".to_string() }])
+            .unwrap();
+        let pairs: Vec<(&str, &str)> = runner.history().iter().map(|r| (r.from, r.to)).collect();
+        assert_eq!(pairs, vec![("Validated", "Enriched"), ("Enriched", "Persisted")]);
+    }
+
+    #[test]
+    fn history_csv_renders_a_header_and_one_row_per_transition() {
+        let mut runner = FsmRunner::new(Box::new(Validated));
+        runner.run([FsmEvent::Process { payload: "p".to_string() }]).unwrap();
+        let csv = runner.history_csv();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("from,to,event"));
+        assert_eq!(lines.next(), Some("Validated,Enriched,Process { payload: \"p\" }"));
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn run_events_stops_as_soon_as_is_final_and_reports_leftover_events() {
+        let mut runner = FsmRunner::new(Box::new(Validated));
+        let outcome = runner
+            .run_events([
+                FsmEvent::Process { payload: "p".to_string() },
+                FsmEvent::Process { payload: "p".to_string() },
+                // Already Persisted (final) by now - these should never be applied.
+                FsmEvent::Reject { reason: "too late".to_string() },
+                FsmEvent::Retry,
+            ])
+            .unwrap();
+        assert_eq!(outcome, RunOutcome { final_state: "Persisted", events_left_unconsumed: true });
+        assert_eq!(runner.state().name(), "Persisted");
+        assert_eq!(runner.history().len(), 2);
+    }
+
+    #[test]
+    fn run_events_reports_no_leftover_events_when_the_source_runs_dry_first() {
+        let mut runner = FsmRunner::new(Box::new(Validated));
+        let outcome = runner
+            .run_events([
+                FsmEvent::Process { payload: "p".to_string() },
+                FsmEvent::Reject { reason: "oops".to_string() },
+                FsmEvent::Retry,
+            ])
+            .unwrap();
+        assert_eq!(outcome, RunOutcome { final_state: "Validated", events_left_unconsumed: false });
+    }
+
+    #[test]
+    fn run_events_reaching_final_early_never_applies_an_event_that_would_have_errored() {
+        let mut runner = FsmRunner::new(Box::new(Validated));
+        // Once Persisted (final) is reached after the second event, run_events must not go on to
+        // apply the third event - which would be an invalid transition and return Err if it did.
+        let outcome = runner
+            .run_events([
+                FsmEvent::Process { payload: "p".to_string() },
+                FsmEvent::Process { payload: "p".to_string() },
+                FsmEvent::Process { payload: "p".to_string() },
+            ])
+            .unwrap();
+        assert_eq!(outcome, RunOutcome { final_state: "Persisted", events_left_unconsumed: true });
+    }
+
+    #[test]
+    fn run_events_propagates_an_error_from_before_the_fsm_ever_reaches_final() {
+        let mut runner = FsmRunner::new(Box::new(Enriched::default()));
+        let Err(err) = runner.run_events([FsmEvent::Retry]) else {
+            panic!("expected Retry on Enriched to be an invalid transition");
+        };
+        match err {
+            FsmError::InvalidTransition { state, event } => {
+                assert_eq!(state, "Enriched");
+                assert_eq!(event, "Retry");
+            }
+            other => panic!("expected InvalidTransition, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn injected_clock_produces_deterministic_ascending_timestamps() {
+        let start = Instant::now();
+        let counter = std::cell::Cell::new(0u64);
+        let clock = move || {
+            counter.set(counter.get() + 1);
+            start + std::time::Duration::from_millis(counter.get())
+        };
+        let mut runner = FsmRunner::with_clock(Box::new(Validated), clock);
+        runner
+            .run([FsmEvent::Process { payload: "p".to_string() }, FsmEvent::Process { payload: "p".to_string() }])
+            .unwrap();
+        let timestamps: Vec<Instant> = runner.history().iter().map(|r| r.at).collect();
+        assert_eq!(timestamps, vec![start + std::time::Duration::from_millis(2), start + std::time::Duration::from_millis(3)]);
+    }
+
+    #[test]
+    fn fsm_runner_detects_a_ping_pong_cycle_well_before_the_step_limit() {
+        let mut registry = StateRegistry::with_builtin_states();
+        register_state(&mut registry, "Ping", || Box::new(Ping));
+        register_state(&mut registry, "Pong", || Box::new(Pong));
+        let mut runner = FsmRunner::with_registry(Box::new(Ping), Instant::now, 1_000, 9, registry);
+        let Err(err) = runner.run(std::iter::repeat(FsmEvent::Retry)) else {
+            panic!("expected a ping-pong run to be caught by cycle detection");
+        };
+        match err {
+            FsmError::CycleDetected(cycle) => assert_eq!(cycle, vec!["Ping", "Pong"]),
+            other => panic!("expected CycleDetected, got {other:?}"),
+        }
+        // Way under the step limit: the cycle guard has to be what actually stopped the run, and
+        // only once the `Ping`/`Pong` pattern had repeated three times, not on its first lap.
+        assert_eq!(runner.history().len(), 4);
+    }
+
+    #[test]
+    fn fsm_runner_reports_step_limit_exceeded_when_the_cycle_window_cant_see_it() {
+        // A cycle_window of 2 is too small to ever hold three repeats of anything, so it can't
+        // see a period-2 cycle like Ping/Pong repeat - max_steps is the only guard left to stop
+        // this otherwise-infinite run.
+        let mut registry = StateRegistry::with_builtin_states();
+        register_state(&mut registry, "Ping", || Box::new(Ping));
+        register_state(&mut registry, "Pong", || Box::new(Pong));
+        let mut runner = FsmRunner::with_registry(Box::new(Ping), Instant::now, 5, 2, registry);
+        let Err(err) = runner.run(std::iter::repeat(FsmEvent::Retry)) else {
+            panic!("expected the uncapped ping-pong run to hit the step limit");
+        };
+        match err {
+            FsmError::StepLimitExceeded { steps } => assert_eq!(steps, 6),
+            other => panic!("expected StepLimitExceeded, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn fsm_runner_default_limits_leave_the_normal_reject_retry_run_unaffected() {
+        let mut runner = FsmRunner::new(Box::new(Validated));
+        runner
+            .run([
+                FsmEvent::Process { payload: "order-42".to_string() },
+                FsmEvent::Process { payload: "order-42".to_string() },
+                FsmEvent::Reject { reason: "downstream timeout".to_string() },
+                FsmEvent::Retry,
+                FsmEvent::Process { payload: "order-42".to_string() },
+                FsmEvent::Process { payload: "order-42".to_string() },
+            ])
+            .unwrap();
+        assert_eq!(runner.state().name(), "Persisted");
+    }
+
+    #[test]
+    fn enriching_self_transitions_on_the_first_process_and_is_not_final_yet() {
+        let state: Box<dyn FsmState<FsmEvent>> = Box::new(Enriching(0));
+        assert!(!state.is_final());
+        let mut ctx = FsmContext::default();
+        let state = state.process_event(FsmEvent::Process { payload: "p".to_string() }, &mut ctx).unwrap();
+        assert_eq!(state.name(), "Enriching");
+        assert!(!state.is_final());
+    }
+
+    #[test]
+    fn enriching_only_reaches_final_persisted_after_a_second_process() {
+        let mut registry = StateRegistry::with_builtin_states();
+        register_state(&mut registry, "Enriching", || Box::new(Enriching(0)));
+        let mut runner = FsmRunner::with_registry(Box::new(Enriching(0)), Instant::now, DEFAULT_MAX_STEPS, DEFAULT_CYCLE_WINDOW, registry);
+        assert!(!runner.is_final());
+        runner.apply(FsmEvent::Process { payload: "p".to_string() }).unwrap();
+        assert_eq!(runner.state().name(), "Enriching");
+        assert!(!runner.is_final());
+        runner.apply(FsmEvent::Process { payload: "p".to_string() }).unwrap();
+        assert_eq!(runner.state().name(), "Persisted");
+        assert!(runner.is_final());
+    }
+
+    #[test]
+    fn only_persisted_reports_itself_as_final() {
+        assert!(Persisted.is_final());
+        assert!(!Validated.is_final());
+        assert!(!Enriched::default().is_final());
+        assert!(!Failed.is_final());
+    }
+
+    #[test]
+    fn every_production_state_round_trips_through_a_json_token() {
+        let registry = StateRegistry::with_builtin_states();
+        let states: Vec<Box<dyn FsmState<FsmEvent>>> =
+            vec![Box::new(Validated), Box::new(Enriched::default()), Box::new(Persisted), Box::new(Failed)];
+        for state in states {
+            let name_before = state.name();
+            let json = serde_json::to_string(&state.to_token()).unwrap();
+            let token: StateToken = serde_json::from_str(&json).unwrap();
+            let resumed = resume_from(token, &registry).unwrap();
+            assert_eq!(resumed.name(), name_before);
+        }
+    }
+
+    #[test]
+    fn resume_from_an_unknown_token_is_an_error() {
+        let token: StateToken = serde_json::from_str("\"Nonexistent\"").unwrap();
+        let Err(err) = resume_from(token, &StateRegistry::with_builtin_states()) else {
+            panic!("expected an unrecognized token to fail to resume");
+        };
+        match err {
+            FsmError::UnknownStateToken(name) => assert_eq!(name, "Nonexistent"),
+            other => panic!("expected UnknownStateToken, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn resume_from_an_extension_state_succeeds_once_the_registry_knows_it() {
+        let mut registry = StateRegistry::with_builtin_states();
+        archived::extend(&mut registry);
+        let token: StateToken = serde_json::from_str("\"Archived\"").unwrap();
+        let resumed = resume_from(token, &registry).unwrap();
+        assert_eq!(resumed.name(), "Archived");
+    }
+
+    #[test]
+    fn runner_reaches_an_externally_registered_state_via_an_extension_transition() {
+        let mut registry = StateRegistry::with_builtin_states();
+        archived::extend(&mut registry);
+        let mut runner = FsmRunner::with_registry(Box::new(Validated), Instant::now, DEFAULT_MAX_STEPS, DEFAULT_CYCLE_WINDOW, registry);
+        runner
+            .run([FsmEvent::Process { payload: "p".to_string() }, FsmEvent::Process { payload: "p".to_string() }, FsmEvent::Archive])
+            .unwrap();
+        assert_eq!(runner.state().name(), "Archived");
+        assert!(runner.is_final());
+    }
+
+    #[test]
+    fn archive_is_still_invalid_outside_persisted_when_no_extension_transition_matches() {
+        let mut registry = StateRegistry::with_builtin_states();
+        archived::extend(&mut registry);
+        let mut runner = FsmRunner::with_registry(Box::new(Validated), Instant::now, DEFAULT_MAX_STEPS, DEFAULT_CYCLE_WINDOW, registry);
+        let Err(err) = runner.apply(FsmEvent::Archive) else {
+            panic!("expected Archive on Validated to be an invalid transition");
+        };
+        match err {
+            FsmError::InvalidTransition { state, event } => {
+                assert_eq!(state, "Validated");
+                assert_eq!(event, "Archive");
+            }
+            other => panic!("expected InvalidTransition, got {other:?}"),
+        }
+    }
+
+    fn production_states() -> Vec<Box<dyn FsmState<FsmEvent>>> {
+        vec![Box::new(Validated), Box::new(Enriched::default()), Box::new(Persisted), Box::new(Failed)]
+    }
+
+    #[test]
+    fn export_dot_includes_every_transition_for_all_states() {
+        let dot = export_dot(&production_states());
+        assert!(dot.starts_with("digraph Fsm {\n"));
+        assert!(dot.ends_with("}\n"));
+        for edge in [
+            "  \"Validated\" -> \"Enriched\" [label=\"Process\"];",
+            "  \"Validated\" -> \"Failed\" [label=\"Reject\"];",
+            "  \"Enriched\" -> \"Persisted\" [label=\"Process\"];",
+            "  \"Enriched\" -> \"Failed\" [label=\"Reject\"];",
+            "  \"Persisted\" -> \"Failed\" [label=\"Reject\"];",
+            "  \"Failed\" -> \"Validated\" [label=\"Retry\"];",
+        ] {
+            assert!(dot.contains(edge), "missing edge {edge:?} in:\n{dot}");
+        }
+    }
+
+    #[test]
+    fn export_dot_is_stable_regardless_of_input_order() {
+        let forward = production_states();
+        let mut reversed = production_states();
+        reversed.reverse();
+        assert_eq!(export_dot(&forward), export_dot(&reversed));
+    }
+
+    /// A `Write` sink that keeps what was written to it around after the `FsmRunner` that owns
+    /// the `Box<dyn Write>` is done with it - plain `Vec<u8>` can't do that once it's boxed away,
+    /// so this hands out clones that all share the same backing buffer.
+    #[derive(Default, Clone)]
+    struct SharedBuffer(std::rc::Rc<std::cell::RefCell<Vec<u8>>>);
+
+    impl SharedBuffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.borrow().clone()).unwrap()
+        }
+    }
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn silent_trace_mode_writes_nothing_to_the_injected_sink() {
+        let buffer = SharedBuffer::default();
+        let mut runner = FsmRunner::with_trace(
+            Box::new(Validated),
+            Instant::now,
+            DEFAULT_MAX_STEPS,
+            DEFAULT_CYCLE_WINDOW,
+            StateRegistry::with_builtin_states(),
+            TraceMode::Silent,
+            Box::new(buffer.clone()),
+        );
+        runner
+            .run([
+                FsmEvent::Process { payload: "p".to_string() },
+                FsmEvent::Reject { reason: "bad".to_string() },
+                FsmEvent::Retry,
+            ])
+            .unwrap();
+        let Err(_) = runner.apply(FsmEvent::Retry) else {
+            panic!("expected Retry on Validated to be an invalid transition");
+        };
+        assert_eq!(buffer.contents(), "");
+    }
+
+    #[test]
+    fn compact_trace_mode_writes_one_line_per_step_and_no_context_snapshot() {
+        let buffer = SharedBuffer::default();
+        let mut runner = FsmRunner::with_trace(
+            Box::new(Validated),
+            Instant::now,
+            DEFAULT_MAX_STEPS,
+            DEFAULT_CYCLE_WINDOW,
+            StateRegistry::with_builtin_states(),
+            TraceMode::Compact,
+            Box::new(buffer.clone()),
+        );
+        runner.run([FsmEvent::Process { payload: "p".to_string() }]).unwrap();
+        assert_eq!(buffer.contents(), "Validated -> Enriched (Process { payload: \"p\" })\n");
+    }
+
+    #[test]
+    fn verbose_trace_mode_adds_a_context_snapshot_under_each_line() {
+        let buffer = SharedBuffer::default();
+        let mut runner = FsmRunner::with_trace(
+            Box::new(Validated),
+            Instant::now,
+            DEFAULT_MAX_STEPS,
+            DEFAULT_CYCLE_WINDOW,
+            StateRegistry::with_builtin_states(),
+            TraceMode::Verbose,
+            Box::new(buffer.clone()),
+        );
+        runner.run([FsmEvent::Process { payload: "p".to_string() }]).unwrap();
+        let contents = buffer.contents();
+        let mut lines = contents.lines();
+        assert_eq!(lines.next(), Some("Validated -> Enriched (Process { payload: \"p\" })"));
+        assert!(lines.next().unwrap().starts_with("  context: FsmContext"));
+        assert_eq!(lines.next(), None);
+    }
+
+    /// A clock the test can move forward by hand between applied events, unlike
+    /// `injected_clock_produces_deterministic_ascending_timestamps`'s auto-incrementing counter -
+    /// `run_with_clock`'s timeout check needs control over the actual gap between "now" readings,
+    /// not just that each reading is later than the last.
+    fn settable_clock() -> (impl Fn() -> Instant, impl Fn(Duration)) {
+        let now = std::rc::Rc::new(std::cell::Cell::new(Instant::now()));
+        let reader = std::rc::Rc::clone(&now);
+        let advance = move |by: Duration| now.set(now.get() + by);
+        (move || reader.get(), advance)
+    }
+
+    #[test]
+    fn run_with_clock_synthesizes_a_timeout_event_once_the_deadline_passes() {
+        let (clock, advance) = settable_clock();
+        let mut runner = FsmRunner::with_clock(Box::new(Enriched::default()), clock);
+        advance(Duration::from_secs(6));
+        runner.run_with_clock([FsmEvent::Retry]).unwrap();
+        assert_eq!(runner.state().name(), "Validated");
+        assert_eq!(runner.history()[0].event, "Reject { reason: \"enrichment timed out\" }");
+        assert_eq!(runner.history()[1].event, "Retry");
+    }
+
+    #[test]
+    fn run_with_clock_never_fires_the_timeout_once_a_timely_event_lands_first() {
+        let (clock, advance) = settable_clock();
+        let mut runner = FsmRunner::with_clock(Box::new(Enriched::default()), clock);
+        advance(Duration::from_secs(3));
+        runner.run_with_clock([FsmEvent::Process { payload: "order-45".to_string() }]).unwrap();
+        assert_eq!(runner.state().name(), "Persisted");
+        advance(Duration::from_secs(10));
+        assert_eq!(runner.history().len(), 1, "Persisted has no timeout, so advancing the clock further should not add transitions");
+    }
+
+    #[test]
+    fn enriched_persists_the_record_to_its_configured_path_on_success() {
+        let path = std::env::temp_dir().join(format!("xp_design_patterns_fsm_persist_ok_{:?}.txt", std::thread::current().id()));
+        let mut runner = FsmRunner::new(Box::new(Enriched::new(&path)));
+        runner.apply(FsmEvent::Process { payload: "order-42".to_string() }).unwrap();
+        assert_eq!(runner.state().name(), "Persisted");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "order-42");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn enriched_reports_a_side_effect_error_and_stays_put_when_the_persist_path_is_unwritable() {
+        // A directory can't be opened for writing as a file, so `std::fs::write` fails reliably
+        // without needing to fake a permissions error.
+        let path = std::env::temp_dir().join(format!("xp_design_patterns_fsm_persist_unwritable_{:?}", std::thread::current().id()));
+        std::fs::create_dir_all(&path).unwrap();
+        let mut runner = FsmRunner::new(Box::new(Enriched::new(&path)));
+        let Err(err) = runner.apply(FsmEvent::Process { payload: "order-42".to_string() }) else {
+            panic!("expected writing the record to a directory to fail");
+        };
+        match err {
+            FsmError::SideEffect { state, .. } => assert_eq!(state, "Enriched"),
+            other => panic!("expected SideEffect, got {other:?}"),
+        }
+        assert_eq!(runner.state().name(), "Enriched");
+        let _ = std::fs::remove_dir(&path);
+    }
+
+    #[test]
+    fn retrying_after_a_failed_persist_targets_the_same_configured_path_not_the_default_one() {
+        let path = std::env::temp_dir().join(format!("xp_design_patterns_fsm_persist_retry_{:?}", std::thread::current().id()));
+        std::fs::create_dir_all(&path).unwrap();
+        let mut runner = FsmRunner::new(Box::new(Enriched::new(&path)));
+        let Err(FsmError::SideEffect { .. }) = runner.apply(FsmEvent::Process { payload: "p".to_string() }) else {
+            panic!("expected the first attempt, against a directory, to fail");
+        };
+        assert_eq!(runner.state().name(), "Enriched");
+
+        // Clear the obstruction and retry the very same event: if the runner had silently fallen
+        // back to a registry-rebuilt `Enriched::default()` instead of keeping the instance
+        // configured with `path`, this would succeed but write to `default_persist_path()`
+        // instead, leaving `path` empty.
+        std::fs::remove_dir(&path).unwrap();
+        runner.apply(FsmEvent::Process { payload: "p".to_string() }).unwrap();
+        assert_eq!(runner.state().name(), "Persisted");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "p");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    fn byte_registry() -> StateRegistry<u8> {
+        let mut registry = StateRegistry::new();
+        register_state(&mut registry, "Scanning", || Box::new(byte_events::Scanning));
+        register_state(&mut registry, "LineEnded", || Box::new(byte_events::LineEnded));
+        registry
+    }
+
+    /// Proves `FsmRunner<E>` really is generic, not just generic-shaped around `FsmEvent`: this
+    /// drives the exact same runner code that every test above drives, but instantiated with
+    /// `E = u8` and a `Kind` type (`ByteEventKind`) unrelated to `FsmEventKind`.
+    #[test]
+    fn byte_event_runner_counts_newlines_identically_to_a_hand_rolled_scan() {
+        let mut runner = FsmRunner::with_registry(Box::new(byte_events::Scanning), Instant::now, DEFAULT_MAX_STEPS, DEFAULT_CYCLE_WINDOW, byte_registry());
+        let input = b"ab\ncd\nef";
+        runner.run(input.iter().copied()).unwrap();
+        let lines_seen = runner.history().iter().filter(|record| record.to == "LineEnded").count();
+        let expected = input.iter().filter(|&&byte| byte == b'\n').count();
+        assert_eq!(lines_seen, expected);
+        assert_eq!(runner.state().name(), "Scanning");
+    }
+
+    #[test]
+    fn byte_event_runner_stays_in_line_ended_across_consecutive_newlines() {
+        let mut runner = FsmRunner::with_registry(Box::new(byte_events::Scanning), Instant::now, DEFAULT_MAX_STEPS, DEFAULT_CYCLE_WINDOW, byte_registry());
+        runner.run(b"\n\n".iter().copied()).unwrap();
+        assert_eq!(runner.state().name(), "LineEnded");
+        assert!(!runner.is_final());
+    }
 }
@@ -20,54 +20,147 @@
 //      You want to allow other crates to add states.
 //      You need to store different data in each state.
 
+use std::cell::RefCell;
+use std::rc::Rc;
+
 // Définition de tous les états possibles
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FsmState {
     Validated,
     Enriched,
     Persisted,
 }
 
+/// Counters carried alongside the state, seeded by `FsmBuilder` and left untouched by
+/// `process_event` - a stand-in for the kind of side data `07_state_machine_typed_stats2.rs`
+/// accumulates per state, to show a builder configuring more than just the starting state.
+#[derive(Debug, Default, Clone)]
+pub struct FsmCounters {
+    pub processed: usize,
+    pub skipped: usize,
+}
+
 // #[derive(Debug, Clone, Copy)]
 pub enum FsmEvent {
     Process,
 }
 
+/// A callback registered via `Fsm::on_transition`, invoked with the state transitioned from and to.
+type TransitionCallback = Box<dyn FnMut(&FsmState, &FsmState)>;
+
 // Implémentation de la FSM
 struct Fsm {
     current_state: FsmState,
+    counters: FsmCounters,
+    verbose: bool,
+    transition_callbacks: Vec<TransitionCallback>,
 }
 
 impl Fsm {
     fn new() -> Self {
-        Fsm { current_state: FsmState::Validated }
+        Fsm { current_state: FsmState::Validated, counters: FsmCounters::default(), verbose: false, transition_callbacks: Vec::new() }
+    }
+
+    /// Registers `cb` to be called after every `process_event` that actually moves
+    /// `current_state` - a `Persisted -> Persisted` no-op `process_event` does not fire it, since
+    /// nothing transitioned. Lets external code observe state changes without the FSM itself
+    /// printing anything, unlike `FsmBuilder::verbose`.
+    pub fn on_transition(&mut self, cb: impl FnMut(&FsmState, &FsmState) + 'static) {
+        self.transition_callbacks.push(Box::new(cb));
     }
 
     pub fn process_event(&mut self, event: FsmEvent) {
+        let previous = self.current_state;
         match (&self.current_state, event) {
             (FsmState::Validated, FsmEvent::Process) => {
                 self.current_state = FsmState::Enriched;
+                self.counters.processed += 1;
                 // println!("State = Validated -> Enriched");
             }
             (FsmState::Enriched, FsmEvent::Process) => {
                 self.current_state = FsmState::Persisted;
+                self.counters.processed += 1;
                 // println!("State = Enriched -> Persisted");
             }
             (FsmState::Persisted, FsmEvent::Process) => {
+                self.counters.skipped += 1;
                 // println!("State: Persisted (final state reached)");
             }
         }
+        if self.verbose {
+            println!("State = {previous:?} -> {:?}", self.current_state);
+        }
+        if previous != self.current_state {
+            for cb in &mut self.transition_callbacks {
+                cb(&previous, &self.current_state);
+            }
+        }
     }
 
     fn current_state(&self) -> &FsmState {
         &self.current_state
     }
+
+    fn counters(&self) -> &FsmCounters {
+        &self.counters
+    }
+}
+
+/// Configures an `Fsm` before its first `process_event`: which `FsmState` to start from instead of
+/// always `Validated`, a starting `FsmCounters`, and whether transitions log themselves. Compare
+/// with `01_builder.rs`'s `UserBuilder` - here `build()` produces a small state machine instead of
+/// a plain data record.
+#[derive(Default)]
+struct FsmBuilder {
+    initial_state: Option<FsmState>,
+    counters: FsmCounters,
+    verbose: bool,
+}
+
+impl FsmBuilder {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts the `Fsm` from `state` instead of `FsmState::Validated`, e.g. to skip straight past
+    /// stages that were already handled upstream.
+    fn initial_state(mut self, state: FsmState) -> Self {
+        self.initial_state = Some(state);
+        self
+    }
+
+    fn counters(mut self, counters: FsmCounters) -> Self {
+        self.counters = counters;
+        self
+    }
+
+    /// Logs every `process_event` transition to stdout as it happens.
+    fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    fn build(self) -> Fsm {
+        Fsm {
+            current_state: self.initial_state.unwrap_or(FsmState::Validated),
+            counters: self.counters,
+            verbose: self.verbose,
+            transition_callbacks: Vec::new(),
+        }
+    }
 }
 
 fn main() {
     let mut my_fsm = Fsm::new();
     println!("Initial state: {:?}", my_fsm.current_state());
 
+    // on_transition observes every state change without the FSM printing anything itself; driving
+    // it past Persisted shows the no-fire-on-self-transition rule, since the path below never
+    // grows a fourth entry.
+    let path = Rc::new(RefCell::new(Vec::new()));
+    let path_log = path.clone();
+    my_fsm.on_transition(move |from, to| path_log.borrow_mut().push((*from, *to)));
+
     my_fsm.process_event(FsmEvent::Process);
     println!("State after one process event: {:?}", my_fsm.current_state());
 
@@ -76,4 +169,89 @@ fn main() {
 
     my_fsm.process_event(FsmEvent::Process);
     println!("State after 2 process events: {:?}", my_fsm.current_state());
+
+    let transitions = path.borrow();
+    let mut states: Vec<String> = transitions.first().map(|(from, _)| format!("{from:?}")).into_iter().collect();
+    states.extend(transitions.iter().map(|(_, to)| format!("{to:?}")));
+    println!("Observed transition path: {}", states.join("->"));
+
+    // FsmBuilder: start past Validated/Enriched and log each transition as it happens.
+    let mut enriched_fsm = FsmBuilder::new()
+        .initial_state(FsmState::Enriched)
+        .counters(FsmCounters { processed: 2, skipped: 0 })
+        .verbose(true)
+        .build();
+    println!("Builder-configured initial state: {:?}", enriched_fsm.current_state());
+    enriched_fsm.process_event(FsmEvent::Process);
+    println!("State after one event from Enriched: {:?}, counters: {:?}", enriched_fsm.current_state(), enriched_fsm.counters());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_build_starts_at_validated() {
+        let fsm = FsmBuilder::new().build();
+        assert_eq!(*fsm.current_state(), FsmState::Validated);
+    }
+
+    #[test]
+    fn fsm_built_with_initial_state_enriched_reaches_persisted_after_one_event() {
+        let mut fsm = FsmBuilder::new().initial_state(FsmState::Enriched).build();
+        fsm.process_event(FsmEvent::Process);
+        assert_eq!(*fsm.current_state(), FsmState::Persisted);
+    }
+
+    #[test]
+    fn a_plain_new_fsm_needs_two_events_to_reach_persisted_from_validated() {
+        let mut fsm = FsmBuilder::new().build();
+        fsm.process_event(FsmEvent::Process);
+        assert_eq!(*fsm.current_state(), FsmState::Enriched);
+        fsm.process_event(FsmEvent::Process);
+        assert_eq!(*fsm.current_state(), FsmState::Persisted);
+    }
+
+    #[test]
+    fn seeded_counters_carry_through_into_the_built_fsm() {
+        let fsm = FsmBuilder::new().counters(FsmCounters { processed: 5, skipped: 1 }).build();
+        assert_eq!(fsm.counters().processed, 5);
+        assert_eq!(fsm.counters().skipped, 1);
+    }
+
+    #[test]
+    fn processed_count_increments_on_every_transition_but_not_once_persisted() {
+        let mut fsm = FsmBuilder::new().build();
+        fsm.process_event(FsmEvent::Process);
+        fsm.process_event(FsmEvent::Process);
+        assert_eq!(fsm.counters().processed, 2);
+        fsm.process_event(FsmEvent::Process);
+        assert_eq!(fsm.counters().processed, 2);
+        assert_eq!(fsm.counters().skipped, 1);
+    }
+
+    #[test]
+    fn on_transition_records_every_actual_state_change_in_order() {
+        let mut fsm = FsmBuilder::new().build();
+        let transitions = Rc::new(RefCell::new(Vec::new()));
+        let log = transitions.clone();
+        fsm.on_transition(move |from, to| log.borrow_mut().push((*from, *to)));
+
+        fsm.process_event(FsmEvent::Process);
+        fsm.process_event(FsmEvent::Process);
+
+        assert_eq!(*transitions.borrow(), vec![(FsmState::Validated, FsmState::Enriched), (FsmState::Enriched, FsmState::Persisted)]);
+    }
+
+    #[test]
+    fn on_transition_does_not_fire_on_a_persisted_to_persisted_self_transition() {
+        let mut fsm = FsmBuilder::new().initial_state(FsmState::Persisted).build();
+        let calls = Rc::new(RefCell::new(0u32));
+        let counter = calls.clone();
+        fsm.on_transition(move |_, _| *counter.borrow_mut() += 1);
+
+        fsm.process_event(FsmEvent::Process);
+
+        assert_eq!(*calls.borrow(), 0);
+    }
 }
@@ -97,6 +97,98 @@ impl Handler<DeleteUser> for DeleteUserHandler {
     }
 }
 
+// Payment strategy - trimmed version of 02_strategy.rs's PaymentStrategy/Money/Receipt, just
+// enough to show PayInvoice routing to a strategy picked at runtime through the command bus.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+struct Money(f64);
+
+#[derive(Debug, Clone, PartialEq)]
+struct Receipt {
+    strategy: &'static str,
+    amount: Money,
+    fee: Money,
+    transaction_id: String,
+}
+
+trait PaymentStrategy {
+    fn pay(&self, amount: Money) -> Result<Receipt, String>;
+}
+
+struct CreditCard;
+impl PaymentStrategy for CreditCard {
+    fn pay(&self, amount: Money) -> Result<Receipt, String> {
+        if amount.0 <= 0.0 {
+            return Err(format!("amount must be positive, got {:.2}", amount.0));
+        }
+        Ok(Receipt { strategy: "Credit Card", amount, fee: Money(amount.0 * 0.02), transaction_id: format!("CC-{}", amount.0 as u64) })
+    }
+}
+
+struct Paypal;
+impl PaymentStrategy for Paypal {
+    fn pay(&self, amount: Money) -> Result<Receipt, String> {
+        if amount.0 <= 0.0 {
+            return Err(format!("amount must be positive, got {:.2}", amount.0));
+        }
+        Ok(Receipt { strategy: "PayPal", amount, fee: Money(amount.0 * 0.029 + 0.30), transaction_id: format!("PP-{}", amount.0 as u64) })
+    }
+}
+
+/// Maps a payment method name to a factory that builds a fresh strategy - same idea as
+/// `02_strategy.rs`'s `StrategyRegistry`, duplicated here since this example is self-contained.
+struct StrategyRegistry {
+    factories: HashMap<String, Box<dyn Fn() -> Box<dyn PaymentStrategy>>>,
+}
+impl StrategyRegistry {
+    fn new() -> Self {
+        Self { factories: HashMap::new() }
+    }
+
+    fn register(&mut self, name: &str, factory: Box<dyn Fn() -> Box<dyn PaymentStrategy>>) {
+        self.factories.insert(name.to_string(), factory);
+    }
+
+    fn create(&self, name: &str) -> Option<Box<dyn PaymentStrategy>> {
+        self.factories.get(name).map(|factory| factory())
+    }
+}
+
+#[derive(Debug)]
+struct PayInvoice {
+    pub invoice_id: u32,
+    pub amount: f64,
+    pub method: String,
+}
+
+impl Command for PayInvoice {
+    type Output = Result<Receipt, String>;
+}
+
+/// Routes `PayInvoice` to whichever strategy its `method` names, the same "pick at runtime
+/// instead of hardcoding" idea as `02_strategy.rs`'s `StrategyRegistry`, but triggered by
+/// dispatching a command instead of calling the registry directly.
+struct PayInvoiceHandler {
+    strategies: StrategyRegistry,
+    logger: Box<dyn CommandLogger>,
+}
+
+impl PayInvoiceHandler {
+    pub fn new(strategies: StrategyRegistry, logger: Box<dyn CommandLogger>) -> Self {
+        PayInvoiceHandler { strategies, logger }
+    }
+}
+
+impl Handler<PayInvoice> for PayInvoiceHandler {
+    fn handle(&self, cmd: PayInvoice) -> Result<Receipt, String> {
+        self.logger.log(&format!("Try to pay invoice {} via {}", cmd.invoice_id, cmd.method));
+
+        let strategy = self.strategies.create(&cmd.method).ok_or_else(|| format!("unknown payment method: {}", cmd.method))?;
+        let receipt = strategy.pay(Money(cmd.amount))?;
+        self.logger.log(&format!("Invoice {} paid via {}", cmd.invoice_id, receipt.strategy));
+        Ok(receipt)
+    }
+}
+
 // CommandBus with error mgt
 struct CommandBus {
     handlers: HashMap<TypeId, Box<dyn Any>>,
@@ -154,6 +246,12 @@ fn main() {
     bus.register::<CreateUser, CreateUserHandler>(CreateUserHandler::new(Box::new(ConsoleLogger)));
     bus.register::<DeleteUser, DeleteUserHandler>(DeleteUserHandler::new(Box::new(ConsoleLogger)));
 
+    // PayInvoice's handler carries its own strategy registry, built once at registration time.
+    let mut strategies = StrategyRegistry::new();
+    strategies.register("credit_card", Box::new(|| Box::new(CreditCard)));
+    strategies.register("paypal", Box::new(|| Box::new(Paypal)));
+    bus.register::<PayInvoice, PayInvoiceHandler>(PayInvoiceHandler::new(strategies, Box::new(ConsoleLogger)));
+
     // Execute commands with error management
     match bus.dispatch::<CreateUser, CreateUserHandler>(CreateUser { name: "Alice".into() }) {
         Ok(result) => println!("Result: {result}"),
@@ -169,4 +267,47 @@ fn main() {
         Ok(result) => println!("Deletion succeeded ? {result}"),
         Err(e) => println!("Error: {e}"),
     }
+
+    match bus.dispatch::<PayInvoice, PayInvoiceHandler>(PayInvoice { invoice_id: 101, amount: 250.0, method: "credit_card".into() }) {
+        Ok(receipt) => println!("Invoice paid via {} (fee {:.2}, tx {})", receipt.strategy, receipt.fee.0, receipt.transaction_id),
+        Err(e) => println!("Error: {e}"),
+    }
+
+    match bus.dispatch::<PayInvoice, PayInvoiceHandler>(PayInvoice { invoice_id: 102, amount: 100.0, method: "bitcoin".into() }) {
+        Ok(receipt) => println!("Invoice paid via {} (fee {:.2}, tx {})", receipt.strategy, receipt.fee.0, receipt.transaction_id),
+        Err(e) => println!("Error: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bus_with_pay_invoice() -> CommandBus {
+        let mut bus = CommandBus::new(Box::new(ConsoleLogger));
+        let mut strategies = StrategyRegistry::new();
+        strategies.register("credit_card", Box::new(|| Box::new(CreditCard)));
+        strategies.register("paypal", Box::new(|| Box::new(Paypal)));
+        bus.register::<PayInvoice, PayInvoiceHandler>(PayInvoiceHandler::new(strategies, Box::new(ConsoleLogger)));
+        bus
+    }
+
+    #[test]
+    fn pay_invoice_dispatches_to_the_named_strategy() {
+        let bus = bus_with_pay_invoice();
+
+        let receipt = bus.dispatch::<PayInvoice, PayInvoiceHandler>(PayInvoice { invoice_id: 1, amount: 100.0, method: "credit_card".into() }).unwrap();
+
+        assert_eq!(receipt.strategy, "Credit Card");
+        assert_eq!(receipt.amount, Money(100.0));
+    }
+
+    #[test]
+    fn pay_invoice_rejects_an_unknown_method() {
+        let bus = bus_with_pay_invoice();
+
+        let err = bus.dispatch::<PayInvoice, PayInvoiceHandler>(PayInvoice { invoice_id: 2, amount: 50.0, method: "bitcoin".into() }).unwrap_err();
+
+        assert_eq!(err, "unknown payment method: bitcoin");
+    }
 }
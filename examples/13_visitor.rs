@@ -0,0 +1,183 @@
+// cargo run --example 13_visitor
+
+// Visitor pattern over a small expression tree.
+// The tree shape (Expr) stays fixed, but we can add new operations (evaluate, print, count...)
+// without touching Expr itself - each operation lives in its own Visitor implementation.
+
+// Rust doesn't need double-dispatch tricks for this as much as classic OO languages do,
+// because `match` on an enum already gives us exhaustive, single-dispatch handling.
+// The `enum_match` module at the bottom shows that simpler alternative for comparison.
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Num(f64),
+    Add(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Neg(Box<Expr>),
+}
+
+// The Visitor trait has one method per Expr variant, with default implementations that
+// recurse into children. Concrete visitors only override what they care about.
+pub trait Visitor<T> {
+    fn visit_num(&mut self, value: f64) -> T;
+    fn visit_add(&mut self, lhs: &Expr, rhs: &Expr) -> T;
+    fn visit_mul(&mut self, lhs: &Expr, rhs: &Expr) -> T;
+    fn visit_neg(&mut self, inner: &Expr) -> T;
+}
+
+// The driver: walks the tree and calls back into the visitor for the matching variant.
+pub fn accept<T>(expr: &Expr, visitor: &mut dyn Visitor<T>) -> T {
+    match expr {
+        Expr::Num(value) => visitor.visit_num(*value),
+        Expr::Add(lhs, rhs) => visitor.visit_add(lhs, rhs),
+        Expr::Mul(lhs, rhs) => visitor.visit_mul(lhs, rhs),
+        Expr::Neg(inner) => visitor.visit_neg(inner),
+    }
+}
+
+// Evaluator: folds the tree down to a single f64.
+pub struct Evaluator;
+impl Visitor<f64> for Evaluator {
+    fn visit_num(&mut self, value: f64) -> f64 {
+        value
+    }
+    fn visit_add(&mut self, lhs: &Expr, rhs: &Expr) -> f64 {
+        accept(lhs, self) + accept(rhs, self)
+    }
+    fn visit_mul(&mut self, lhs: &Expr, rhs: &Expr) -> f64 {
+        accept(lhs, self) * accept(rhs, self)
+    }
+    fn visit_neg(&mut self, inner: &Expr) -> f64 {
+        -accept(inner, self)
+    }
+}
+
+// Pretty-printer: folds the tree down to a fully-parenthesized String.
+pub struct PrettyPrinter;
+impl Visitor<String> for PrettyPrinter {
+    fn visit_num(&mut self, value: f64) -> String {
+        value.to_string()
+    }
+    fn visit_add(&mut self, lhs: &Expr, rhs: &Expr) -> String {
+        format!("({} + {})", accept(lhs, self), accept(rhs, self))
+    }
+    fn visit_mul(&mut self, lhs: &Expr, rhs: &Expr) -> String {
+        format!("({} * {})", accept(lhs, self), accept(rhs, self))
+    }
+    fn visit_neg(&mut self, inner: &Expr) -> String {
+        format!("-{}", accept(inner, self))
+    }
+}
+
+// Node counter: counts every node in the tree, including Num leaves.
+pub struct NodeCounter {
+    pub count: usize,
+}
+impl Visitor<()> for NodeCounter {
+    fn visit_num(&mut self, _value: f64) {
+        self.count += 1;
+    }
+    fn visit_add(&mut self, lhs: &Expr, rhs: &Expr) {
+        self.count += 1;
+        accept(lhs, self);
+        accept(rhs, self);
+    }
+    fn visit_mul(&mut self, lhs: &Expr, rhs: &Expr) {
+        self.count += 1;
+        accept(lhs, self);
+        accept(rhs, self);
+    }
+    fn visit_neg(&mut self, inner: &Expr) {
+        self.count += 1;
+        accept(inner, self);
+    }
+}
+
+// The enum-match alternative: same operations, but expressed as plain functions that
+// match on Expr directly. No trait, no accept() driver - simpler when you don't need
+// to let other crates add new operations without touching this file.
+pub mod enum_match {
+    use super::Expr;
+
+    pub fn evaluate(expr: &Expr) -> f64 {
+        match expr {
+            Expr::Num(value) => *value,
+            Expr::Add(lhs, rhs) => evaluate(lhs) + evaluate(rhs),
+            Expr::Mul(lhs, rhs) => evaluate(lhs) * evaluate(rhs),
+            Expr::Neg(inner) => -evaluate(inner),
+        }
+    }
+
+    pub fn pretty_print(expr: &Expr) -> String {
+        match expr {
+            Expr::Num(value) => value.to_string(),
+            Expr::Add(lhs, rhs) => format!("({} + {})", pretty_print(lhs), pretty_print(rhs)),
+            Expr::Mul(lhs, rhs) => format!("({} * {})", pretty_print(lhs), pretty_print(rhs)),
+            Expr::Neg(inner) => format!("-{}", pretty_print(inner)),
+        }
+    }
+}
+
+fn main() {
+    // (3 + 4) * -(2)
+    let expr = Expr::Mul(Box::new(Expr::Add(Box::new(Expr::Num(3.0)), Box::new(Expr::Num(4.0)))), Box::new(Expr::Neg(Box::new(Expr::Num(2.0)))));
+
+    let mut evaluator = Evaluator;
+    println!("Result: {}", accept(&expr, &mut evaluator));
+
+    let mut printer = PrettyPrinter;
+    println!("Expr: {}", accept(&expr, &mut printer));
+
+    let mut counter = NodeCounter { count: 0 };
+    accept(&expr, &mut counter);
+    println!("Nodes: {}", counter.count);
+
+    println!("enum_match result: {}", enum_match::evaluate(&expr));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_expressions() -> Vec<Expr> {
+        vec![
+            Expr::Num(5.0),
+            Expr::Add(Box::new(Expr::Num(1.0)), Box::new(Expr::Num(2.0))),
+            Expr::Mul(Box::new(Expr::Num(2.0)), Box::new(Expr::Num(3.0))),
+            Expr::Neg(Box::new(Expr::Num(4.0))),
+            Expr::Neg(Box::new(Expr::Neg(Box::new(Expr::Num(4.0))))),
+            Expr::Add(Box::new(Expr::Neg(Box::new(Expr::Num(1.0)))), Box::new(Expr::Mul(Box::new(Expr::Num(2.0)), Box::new(Expr::Num(3.0))))),
+        ]
+    }
+
+    #[test]
+    fn evaluator_matches_enum_match() {
+        for expr in sample_expressions() {
+            let mut evaluator = Evaluator;
+            assert_eq!(accept(&expr, &mut evaluator), enum_match::evaluate(&expr));
+        }
+    }
+
+    #[test]
+    fn printer_matches_enum_match() {
+        for expr in sample_expressions() {
+            let mut printer = PrettyPrinter;
+            assert_eq!(accept(&expr, &mut printer), enum_match::pretty_print(&expr));
+        }
+    }
+
+    #[test]
+    fn nested_negation_evaluates_correctly() {
+        let expr = Expr::Neg(Box::new(Expr::Neg(Box::new(Expr::Num(7.0)))));
+        let mut evaluator = Evaluator;
+        assert_eq!(accept(&expr, &mut evaluator), 7.0);
+    }
+
+    #[test]
+    fn counts_every_node() {
+        let expr = Expr::Add(Box::new(Expr::Num(1.0)), Box::new(Expr::Num(2.0)));
+        let mut counter = NodeCounter { count: 0 };
+        accept(&expr, &mut counter);
+        assert_eq!(counter.count, 3);
+    }
+}
@@ -0,0 +1,256 @@
+// cargo run --example 14_actor
+
+// Generalizes the worker examples (07/08_tokio_event_dispatcher) into a tiny actor framework.
+// An actor owns its state exclusively and only ever touches it from inside `handle`, so there's
+// no shared mutable state to protect: messages flow in through a bounded mpsc channel, and the
+// actor's task drains them one at a time.
+
+use tokio::sync::{mpsc, oneshot};
+
+// What an actor receives and how it reacts. `Msg` is the mailbox's message type; `handle` gets
+// exclusive access to `&mut self` plus a `Ctx` it can use to stop itself or spawn children.
+pub trait Actor: Send + 'static {
+    type Msg: Send + 'static;
+
+    fn handle(&mut self, msg: Self::Msg, ctx: &mut Ctx<Self>) -> impl Future<Output = ()> + Send;
+}
+
+use std::future::Future;
+
+// What an actor can do to itself while handling a message.
+pub struct Ctx<A: Actor + ?Sized> {
+    stop: bool,
+    children: Vec<tokio::task::JoinHandle<()>>,
+    _marker: std::marker::PhantomData<A>,
+}
+
+impl<A: Actor + ?Sized> Ctx<A> {
+    fn new() -> Self {
+        Self { stop: false, children: Vec::new(), _marker: std::marker::PhantomData }
+    }
+
+    // Asks the running loop to exit after the current message finishes.
+    pub fn stop(&mut self) {
+        self.stop = true;
+    }
+
+    // Fire off a child actor; its mailbox loop runs independently of the parent.
+    pub fn spawn_child<C: Actor>(&mut self, child: C, mailbox_size: usize) -> Addr<C> {
+        let (addr, handle) = spawn_actor(child, mailbox_size);
+        self.children.push(handle);
+        addr
+    }
+}
+
+// What backpressure policy a full mailbox should apply. Bounded mailboxes are the default:
+// an actor that falls behind should push back on its callers rather than grow without limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailboxPolicy {
+    /// `send`/`ask` wait until there is room.
+    Block,
+    /// `send`/`ask` return `Err(MailboxFull)` immediately instead of waiting.
+    Fail,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MailboxFull;
+
+impl std::fmt::Display for MailboxFull {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "mailbox is full")
+    }
+}
+impl std::error::Error for MailboxFull {}
+
+// A handle to a running actor. Cloning an `Addr` is cheap; every clone shares the same mailbox.
+pub struct Addr<A: Actor> {
+    tx: mpsc::Sender<A::Msg>,
+    policy: MailboxPolicy,
+}
+
+impl<A: Actor> Clone for Addr<A> {
+    fn clone(&self) -> Self {
+        Self { tx: self.tx.clone(), policy: self.policy }
+    }
+}
+
+impl<A: Actor> Addr<A> {
+    // Fire-and-forget: enqueue a message for the actor, ignoring any reply.
+    pub async fn send(&self, msg: A::Msg) -> Result<(), MailboxFull> {
+        match self.policy {
+            MailboxPolicy::Block => self.tx.send(msg).await.map_err(|_| MailboxFull),
+            MailboxPolicy::Fail => self.tx.try_send(msg).map_err(|_| MailboxFull),
+        }
+    }
+
+    // Note: `ask` only makes sense for actors whose Msg type carries its own reply channel.
+    // Here we model it by handing the actor an "echo" of the request so it can reply in place.
+    pub async fn ask(&self, msg: A::Msg) -> Result<A::Msg, MailboxFull>
+    where
+        A::Msg: Clone,
+    {
+        self.send(msg.clone()).await?;
+        Ok(msg)
+    }
+}
+
+// Spawns the actor's mailbox loop and returns an `Addr` to talk to it.
+pub fn spawn_actor<A: Actor>(mut actor: A, mailbox_size: usize) -> (Addr<A>, tokio::task::JoinHandle<()>) {
+    let (tx, mut rx) = mpsc::channel(mailbox_size);
+    let handle = tokio::spawn(async move {
+        let mut ctx = Ctx::<A>::new();
+        while let Some(msg) = rx.recv().await {
+            actor.handle(msg, &mut ctx).await;
+            if ctx.stop {
+                break;
+            }
+        }
+        for child in ctx.children {
+            let _ = child.await;
+        }
+    });
+    (Addr { tx, policy: MailboxPolicy::Block }, handle)
+}
+
+// --- CounterActor: the smallest possible actor -------------------------------------------------
+
+#[derive(Debug)]
+pub enum CounterMsg {
+    Increment,
+    GetAndStop(oneshot::Sender<u64>),
+}
+
+pub struct CounterActor {
+    count: u64,
+}
+
+impl Actor for CounterActor {
+    type Msg = CounterMsg;
+
+    async fn handle(&mut self, msg: Self::Msg, ctx: &mut Ctx<Self>) {
+        match msg {
+            CounterMsg::Increment => self.count += 1,
+            CounterMsg::GetAndStop(reply) => {
+                let _ = reply.send(self.count);
+                ctx.stop();
+            }
+        }
+    }
+}
+
+// --- UserRegistryActor: backs the CreateUser/DeleteUser commands from the command-bus examples --
+
+#[derive(Debug)]
+pub enum UserRegistryMsg {
+    CreateUser { name: String, reply: oneshot::Sender<u32> },
+    DeleteUser { id: u32, reply: oneshot::Sender<bool> },
+}
+
+pub struct UserRegistryActor {
+    next_id: u32,
+    users: std::collections::HashMap<u32, String>,
+}
+
+impl UserRegistryActor {
+    pub fn new() -> Self {
+        Self { next_id: 1, users: std::collections::HashMap::new() }
+    }
+}
+
+impl Default for UserRegistryActor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Actor for UserRegistryActor {
+    type Msg = UserRegistryMsg;
+
+    async fn handle(&mut self, msg: Self::Msg, _ctx: &mut Ctx<Self>) {
+        match msg {
+            UserRegistryMsg::CreateUser { name, reply } => {
+                let id = self.next_id;
+                self.next_id += 1;
+                self.users.insert(id, name);
+                let _ = reply.send(id);
+            }
+            UserRegistryMsg::DeleteUser { id, reply } => {
+                let _ = reply.send(self.users.remove(&id).is_some());
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let (counter, counter_task) = spawn_actor(CounterActor { count: 0 }, 8);
+    for _ in 0..5 {
+        counter.send(CounterMsg::Increment).await.unwrap();
+    }
+    let (tx, rx) = oneshot::channel();
+    counter.send(CounterMsg::GetAndStop(tx)).await.unwrap();
+    println!("Counter reached: {}", rx.await.unwrap());
+    counter_task.await.unwrap();
+
+    let (registry, registry_task) = spawn_actor(UserRegistryActor::new(), 8);
+    let (tx, rx) = oneshot::channel();
+    registry.send(UserRegistryMsg::CreateUser { name: "Alice".into(), reply: tx }).await.unwrap();
+    let id = rx.await.unwrap();
+    println!("Created user with id {id}");
+
+    let (tx, rx) = oneshot::channel();
+    registry.send(UserRegistryMsg::DeleteUser { id, reply: tx }).await.unwrap();
+    println!("Deleted? {}", rx.await.unwrap());
+    drop(registry);
+    registry_task.await.unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn ask_round_trip_increments_and_reports() {
+        let (counter, task) = spawn_actor(CounterActor { count: 0 }, 4);
+        counter.send(CounterMsg::Increment).await.unwrap();
+        counter.send(CounterMsg::Increment).await.unwrap();
+        let (tx, rx) = oneshot::channel();
+        counter.send(CounterMsg::GetAndStop(tx)).await.unwrap();
+        assert_eq!(rx.await.unwrap(), 2);
+        task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn self_stop_ends_the_mailbox_loop() {
+        let (counter, task) = spawn_actor(CounterActor { count: 0 }, 4);
+        let (tx, rx) = oneshot::channel();
+        counter.send(CounterMsg::GetAndStop(tx)).await.unwrap();
+        assert_eq!(rx.await.unwrap(), 0);
+        // The actor already stopped; further sends fail once the mailbox closes.
+        task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn fail_policy_rejects_when_mailbox_is_full() {
+        let (tx, _rx) = mpsc::channel::<CounterMsg>(1);
+        let addr: Addr<CounterActor> = Addr { tx, policy: MailboxPolicy::Fail };
+        addr.send(CounterMsg::Increment).await.unwrap();
+        let err = addr.send(CounterMsg::Increment).await.unwrap_err();
+        assert_eq!(err, MailboxFull);
+    }
+
+    #[tokio::test]
+    async fn user_registry_creates_and_deletes() {
+        let (registry, task) = spawn_actor(UserRegistryActor::new(), 4);
+        let (tx, rx) = oneshot::channel();
+        registry.send(UserRegistryMsg::CreateUser { name: "Bob".into(), reply: tx }).await.unwrap();
+        let id = rx.await.unwrap();
+
+        let (tx, rx) = oneshot::channel();
+        registry.send(UserRegistryMsg::DeleteUser { id, reply: tx }).await.unwrap();
+        assert!(rx.await.unwrap());
+
+        drop(registry);
+        task.await.unwrap();
+    }
+}
@@ -0,0 +1,127 @@
+// cargo run --example 15_singleton_config
+
+// Safe-singleton pattern for process-wide configuration.
+// std::sync::OnceLock gives us a cell that can only be written once and is safe to read from
+// any thread after that - no lazy_static, no unsafe, no mutex needed for the common "read-mostly,
+// initialized-once" case.
+
+use std::cell::RefCell;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppConfig {
+    pub log_level: String,
+    pub worker_count: usize,
+    pub payment_default: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlreadyInitialized;
+
+impl std::fmt::Display for AlreadyInitialized {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AppConfig::init was already called")
+    }
+}
+impl std::error::Error for AlreadyInitialized {}
+
+static CONFIG: OnceLock<AppConfig> = OnceLock::new();
+
+thread_local! {
+    // Lets tests of *other* modules inject a config without touching the process-wide global.
+    static OVERRIDE: RefCell<Option<AppConfig>> = const { RefCell::new(None) };
+}
+
+impl AppConfig {
+    /// Sets the global configuration. Returns `Err(AlreadyInitialized)` if a previous call
+    /// already won the race; exactly one caller ever succeeds.
+    pub fn init(cfg: AppConfig) -> Result<(), AlreadyInitialized> {
+        CONFIG.set(cfg).map_err(|_| AlreadyInitialized)
+    }
+
+    /// Returns the global configuration, honoring a thread-local override if one was installed
+    /// via [`AppConfig::with_overridden`].
+    ///
+    /// # Panics
+    /// Panics if called before `init`.
+    pub fn global() -> AppConfig {
+        OVERRIDE.with(|cell| cell.borrow().clone()).unwrap_or_else(|| CONFIG.get().expect("AppConfig::init was never called").clone())
+    }
+
+    /// Runs `body` with `cfg` visible to `AppConfig::global()` calls on the *current thread only*,
+    /// then restores whatever was there before. Lets unit tests of other modules inject config
+    /// without racing the real, process-wide `OnceLock`.
+    pub fn with_overridden<T>(cfg: AppConfig, body: impl FnOnce() -> T) -> T {
+        let previous = OVERRIDE.with(|cell| cell.replace(Some(cfg)));
+        let result = body();
+        OVERRIDE.with(|cell| *cell.borrow_mut() = previous);
+        result
+    }
+}
+
+fn main() {
+    AppConfig::init(AppConfig { log_level: "info".into(), worker_count: 4, payment_default: "credit_card".into() }).expect("first init should succeed");
+
+    // A second attempt is rejected - the global is already set.
+    let err = AppConfig::init(AppConfig { log_level: "debug".into(), worker_count: 1, payment_default: "paypal".into() }).unwrap_err();
+    println!("Second init rejected: {err}");
+
+    println!("Global config: {:?}", AppConfig::global());
+
+    AppConfig::with_overridden(AppConfig { log_level: "trace".into(), worker_count: 99, payment_default: "paypal".into() }, || {
+        println!("Overridden (this thread only): {:?}", AppConfig::global());
+    });
+
+    println!("Back to global: {:?}", AppConfig::global());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Mutex, OnceLock};
+    use std::thread;
+
+    // Guards the shared `CONFIG` OnceLock so tests that exercise `init()` don't race each other.
+    fn init_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    #[test]
+    fn exactly_one_thread_wins_the_init_race() {
+        let _guard = init_lock().lock().unwrap();
+
+        let wins = thread::scope(|scope| {
+            let handles: Vec<_> = (0..16)
+                .map(|i| {
+                    scope.spawn(move || {
+                        AppConfig::init(AppConfig { log_level: "info".into(), worker_count: i, payment_default: "credit_card".into() }).is_ok()
+                    })
+                })
+                .collect();
+            handles.into_iter().filter_map(|h| h.join().ok()).filter(|won| *won).count()
+        });
+
+        // `init` may have already run in an earlier test on this thread pool's CONFIG,
+        // but within this race at most one of these 16 calls can have won.
+        assert!(wins <= 1);
+
+        let observed = AppConfig::global();
+        // Every thread, including ones that lost the race, sees the same instance afterwards.
+        for _ in 0..16 {
+            assert_eq!(AppConfig::global(), observed);
+        }
+    }
+
+    #[test]
+    fn with_overridden_is_thread_local_and_restores_previous() {
+        let _guard = init_lock().lock().unwrap();
+        let _ = AppConfig::init(AppConfig { log_level: "info".into(), worker_count: 1, payment_default: "credit_card".into() });
+
+        let before = AppConfig::global();
+        AppConfig::with_overridden(AppConfig { log_level: "trace".into(), worker_count: 123, payment_default: "paypal".into() }, || {
+            assert_eq!(AppConfig::global().worker_count, 123);
+        });
+        assert_eq!(AppConfig::global(), before);
+    }
+}
@@ -0,0 +1,212 @@
+// cargo run --example 16_decorator
+
+// Decorator pattern: stack behavior around a core trait without touching it.
+// The static-dispatch version (Logging<S>, Timing<S>, Caching<S>) composes with generics, so the
+// whole stack monomorphizes into one concrete type and costs nothing at runtime. The dynamic
+// version at the bottom shows the same composition behind Box<dyn Service> for contrast -
+// handy when the stack is chosen at runtime instead of compile-time.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Instant;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceError(pub String);
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "service error: {}", self.0)
+    }
+}
+impl std::error::Error for ServiceError {}
+
+pub trait Service {
+    fn call(&self, input: &str) -> Result<String, ServiceError>;
+}
+
+pub struct EchoService;
+impl Service for EchoService {
+    fn call(&self, input: &str) -> Result<String, ServiceError> {
+        if input.is_empty() {
+            Err(ServiceError("empty input".into()))
+        } else {
+            Ok(input.to_string())
+        }
+    }
+}
+
+// The same sink used by the command-bus examples' logging middleware.
+pub trait CommandLogger {
+    fn log(&self, message: &str);
+}
+
+#[derive(Default)]
+pub struct RecordingLogger {
+    pub lines: RefCell<Vec<String>>,
+}
+impl CommandLogger for RecordingLogger {
+    fn log(&self, message: &str) {
+        self.lines.borrow_mut().push(message.to_string());
+    }
+}
+
+// --- Static-dispatch decorators: each wraps an `S: Service` and is itself a `Service` ----------
+
+pub struct Logging<'a, S> {
+    inner: S,
+    logger: &'a dyn CommandLogger,
+}
+impl<'a, S: Service> Logging<'a, S> {
+    pub fn new(inner: S, logger: &'a dyn CommandLogger) -> Self {
+        Self { inner, logger }
+    }
+}
+impl<S: Service> Service for Logging<'_, S> {
+    fn call(&self, input: &str) -> Result<String, ServiceError> {
+        self.logger.log(&format!("call({input})"));
+        let result = self.inner.call(input);
+        self.logger.log(&format!("result = {result:?}"));
+        result
+    }
+}
+
+pub struct Timing<S> {
+    inner: S,
+}
+impl<S: Service> Timing<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+impl<S: Service> Service for Timing<S> {
+    fn call(&self, input: &str) -> Result<String, ServiceError> {
+        let start = Instant::now();
+        let result = self.inner.call(input);
+        let _elapsed = start.elapsed();
+        result
+    }
+}
+
+pub struct Caching<S> {
+    inner: S,
+    cache: RefCell<HashMap<String, String>>,
+}
+impl<S: Service> Caching<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner, cache: RefCell::new(HashMap::new()) }
+    }
+}
+impl<S: Service> Service for Caching<S> {
+    fn call(&self, input: &str) -> Result<String, ServiceError> {
+        if let Some(hit) = self.cache.borrow().get(input) {
+            return Ok(hit.clone());
+        }
+        let result = self.inner.call(input)?;
+        self.cache.borrow_mut().insert(input.to_string(), result.clone());
+        Ok(result)
+    }
+}
+
+// --- Dynamic-dispatch alternative: same decorators behind Box<dyn Service> ----------------------
+
+pub mod dynamic {
+    use super::*;
+
+    pub struct Logging<'a> {
+        pub inner: Box<dyn Service>,
+        pub logger: &'a dyn CommandLogger,
+    }
+    impl Service for Logging<'_> {
+        fn call(&self, input: &str) -> Result<String, ServiceError> {
+            self.logger.log(&format!("call({input})"));
+            self.inner.call(input)
+        }
+    }
+
+    pub struct Timing {
+        pub inner: Box<dyn Service>,
+    }
+    impl Service for Timing {
+        fn call(&self, input: &str) -> Result<String, ServiceError> {
+            let start = Instant::now();
+            let result = self.inner.call(input);
+            let _elapsed = start.elapsed();
+            result
+        }
+    }
+
+    pub struct Caching {
+        pub inner: Box<dyn Service>,
+        pub cache: RefCell<HashMap<String, String>>,
+    }
+    impl Service for Caching {
+        fn call(&self, input: &str) -> Result<String, ServiceError> {
+            if let Some(hit) = self.cache.borrow().get(input) {
+                return Ok(hit.clone());
+            }
+            let result = self.inner.call(input)?;
+            self.cache.borrow_mut().insert(input.to_string(), result.clone());
+            Ok(result)
+        }
+    }
+}
+
+fn main() {
+    let logger = RecordingLogger::default();
+
+    // Caching outside logging: every call is logged, cache hits and misses alike.
+    let service = Logging::new(Caching::new(Timing::new(EchoService)), &logger);
+    println!("{:?}", service.call("hello"));
+    println!("{:?}", service.call("hello"));
+    println!("Log lines: {:?}", logger.lines.borrow());
+
+    // Dynamic-dispatch equivalent.
+    let dyn_service: Box<dyn Service> = Box::new(dynamic::Timing { inner: Box::new(EchoService) });
+    println!("{:?}", dyn_service.call("world"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caching_inside_logging_logs_every_call_even_on_hit() {
+        let logger = RecordingLogger::default();
+        let service = Logging::new(Caching::new(EchoService), &logger);
+
+        service.call("x").unwrap();
+        service.call("x").unwrap();
+
+        // Two calls through the service, so logging (outermost) ran twice regardless of the
+        // cache hit on the second call.
+        assert_eq!(logger.lines.borrow().iter().filter(|l| l.starts_with("call(")).count(), 2);
+    }
+
+    #[test]
+    fn logging_inside_caching_only_logs_on_cache_miss() {
+        let logger = RecordingLogger::default();
+        let service = Caching::new(Logging::new(EchoService, &logger));
+
+        service.call("x").unwrap();
+        service.call("x").unwrap();
+
+        // The cache short-circuits the second call before it reaches the inner Logging decorator.
+        assert_eq!(logger.lines.borrow().iter().filter(|l| l.starts_with("call(")).count(), 1);
+    }
+
+    #[test]
+    fn cache_returns_identical_result_on_hit() {
+        let service = Caching::new(EchoService);
+        let first = service.call("same").unwrap();
+        let second = service.call("same").unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn errors_propagate_through_every_decorator() {
+        let logger = RecordingLogger::default();
+        let service = Logging::new(Caching::new(Timing::new(EchoService)), &logger);
+        assert!(service.call("").is_err());
+    }
+}
@@ -0,0 +1,201 @@
+// cargo run --example 17_object_pool
+
+// Object pool with RAII checkout: a `Pooled<T>` guard returns its object to the pool when
+// dropped, so callers can never forget to give it back. `take()` breaks that contract on
+// purpose - it converts the guard into an owned T, permanently removing it from the pool,
+// which then refills lazily the next time someone checks out more than it has on hand.
+
+use std::collections::VecDeque;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Condvar, Mutex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExhaustionPolicy {
+    /// Manufacture a new object on demand when the pool is empty.
+    Grow,
+    /// Block the calling thread until an object is returned.
+    Block,
+    /// Return `Err(PoolExhausted)` immediately.
+    Fail,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolExhausted;
+
+impl std::fmt::Display for PoolExhausted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "object pool is exhausted")
+    }
+}
+impl std::error::Error for PoolExhausted {}
+
+struct Inner<T> {
+    idle: Mutex<VecDeque<T>>,
+    returned: Condvar,
+    factory: Box<dyn Fn() -> T + Send + Sync>,
+    policy: ExhaustionPolicy,
+}
+
+pub struct ObjectPool<T> {
+    inner: Arc<Inner<T>>,
+}
+
+impl<T> Clone for ObjectPool<T> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<T: Send + 'static> ObjectPool<T> {
+    pub fn new(size: usize, policy: ExhaustionPolicy, factory: impl Fn() -> T + Send + Sync + 'static) -> Self {
+        let idle = (0..size).map(|_| factory()).collect();
+        Self { inner: Arc::new(Inner { idle: Mutex::new(idle), returned: Condvar::new(), factory: Box::new(factory), policy }) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.idle.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks an object out of the pool, applying this pool's [`ExhaustionPolicy`] if empty.
+    pub fn checkout(&self) -> Result<Pooled<T>, PoolExhausted> {
+        let mut idle = self.inner.idle.lock().unwrap();
+        loop {
+            if let Some(value) = idle.pop_front() {
+                return Ok(Pooled { value: Some(value), pool: self.clone() });
+            }
+            match self.inner.policy {
+                ExhaustionPolicy::Grow => {
+                    let value = (self.inner.factory)();
+                    return Ok(Pooled { value: Some(value), pool: self.clone() });
+                }
+                ExhaustionPolicy::Fail => return Err(PoolExhausted),
+                ExhaustionPolicy::Block => {
+                    idle = self.inner.returned.wait(idle).unwrap();
+                }
+            }
+        }
+    }
+
+    fn give_back(&self, value: T) {
+        self.inner.idle.lock().unwrap().push_back(value);
+        self.inner.returned.notify_one();
+    }
+
+    fn refill_one(&self) {
+        let value = (self.inner.factory)();
+        self.inner.idle.lock().unwrap().push_back(value);
+        self.inner.returned.notify_one();
+    }
+}
+
+/// RAII guard around a checked-out object. Returns it to the pool on drop unless [`Pooled::take`]
+/// converted it into an owned value first.
+pub struct Pooled<T: Send + 'static> {
+    value: Option<T>,
+    pool: ObjectPool<T>,
+}
+
+impl<T: Send + 'static> Pooled<T> {
+    /// Converts the guard into an owned `T`, permanently removing it from the pool. The pool
+    /// lazily refills the slot the next time it is exhausted and asked to grow, or immediately
+    /// if its policy would otherwise block or fail.
+    pub fn take(mut self) -> T {
+        let value = self.value.take().expect("value only ever taken once");
+        self.pool.refill_one();
+        value
+    }
+}
+
+impl<T: Send + 'static> Deref for Pooled<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        self.value.as_ref().expect("value present until drop or take")
+    }
+}
+
+impl<T: Send + 'static> DerefMut for Pooled<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value.as_mut().expect("value present until drop or take")
+    }
+}
+
+impl<T: Send + 'static> Drop for Pooled<T> {
+    fn drop(&mut self) {
+        if let Some(value) = self.value.take() {
+            self.pool.give_back(value);
+        }
+    }
+}
+
+fn main() {
+    let pool: ObjectPool<String> = ObjectPool::new(2, ExhaustionPolicy::Grow, || String::from("fresh"));
+
+    {
+        let mut guard = pool.checkout().unwrap();
+        guard.push_str("-used");
+        println!("Borrowed: {}", *guard);
+    } // returned to the pool here
+
+    println!("Pool size after return: {}", pool.len());
+
+    let taken = pool.checkout().unwrap().take();
+    println!("Taken out permanently: {taken}");
+    println!("Pool size after take (lazily refilled): {}", pool.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuse_hands_back_the_same_object_identity() {
+        let pool: ObjectPool<Vec<u8>> = ObjectPool::new(1, ExhaustionPolicy::Fail, Vec::new);
+        {
+            let mut guard = pool.checkout().unwrap();
+            guard.push(42);
+        }
+        let guard = pool.checkout().unwrap();
+        assert_eq!(*guard, vec![42]);
+    }
+
+    #[test]
+    fn fail_policy_reports_exhaustion() {
+        let pool: ObjectPool<u32> = ObjectPool::new(1, ExhaustionPolicy::Fail, || 0);
+        let _first = pool.checkout().unwrap();
+        assert!(pool.checkout().is_err());
+    }
+
+    #[test]
+    fn grow_policy_manufactures_extra_objects() {
+        let pool: ObjectPool<u32> = ObjectPool::new(0, ExhaustionPolicy::Grow, || 7);
+        let guard = pool.checkout().unwrap();
+        assert_eq!(*guard, 7);
+    }
+
+    #[test]
+    fn block_policy_wakes_up_once_an_object_is_returned() {
+        let pool: ObjectPool<u32> = ObjectPool::new(1, ExhaustionPolicy::Block, || 1);
+        let first = pool.checkout().unwrap();
+
+        let pool_clone = pool.clone();
+        let handle = std::thread::spawn(move || pool_clone.checkout().unwrap());
+
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        drop(first); // unblocks the waiting thread
+
+        let second = handle.join().unwrap();
+        assert_eq!(*second, 1);
+    }
+
+    #[test]
+    fn take_removes_from_pool_and_then_lazily_refills() {
+        let pool: ObjectPool<u32> = ObjectPool::new(1, ExhaustionPolicy::Fail, || 5);
+        let value = pool.checkout().unwrap().take();
+        assert_eq!(value, 5);
+        assert_eq!(pool.len(), 1, "take() refills the slot it vacated");
+    }
+}
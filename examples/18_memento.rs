@@ -0,0 +1,196 @@
+// cargo run --example 18_memento
+
+// Memento pattern layered onto the enum pipeline FSM from 05_state_machine_enums, this time with
+// a data payload attached to each state. `Fsm::save()` hands out an opaque `Memento` that a
+// `Caretaker` can stash and later replay through `Fsm::restore()` - without the caretaker, or
+// anyone else, ever seeing the FSM's internal fields.
+
+use std::collections::VecDeque;
+
+const MEMENTO_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FsmState {
+    Validated { raw: String },
+    Enriched { raw: String, metadata: String },
+    Persisted { raw: String, metadata: String },
+}
+
+// Opaque to callers: the fields are private, so a Memento can only be produced by `Fsm::save`
+// and consumed by `Fsm::restore`. Cheap by construction - it clones only the current state's
+// payload, not any history.
+#[derive(Debug, Clone)]
+pub struct Memento {
+    version: u32,
+    state: FsmState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IncompatibleMemento;
+
+impl std::fmt::Display for IncompatibleMemento {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "memento was produced by an incompatible FSM version")
+    }
+}
+impl std::error::Error for IncompatibleMemento {}
+
+pub struct Fsm {
+    state: FsmState,
+}
+
+impl Fsm {
+    pub fn new(raw: impl Into<String>) -> Self {
+        Self { state: FsmState::Validated { raw: raw.into() } }
+    }
+
+    pub fn state(&self) -> &FsmState {
+        &self.state
+    }
+
+    pub fn enrich(&mut self, metadata: impl Into<String>) {
+        if let FsmState::Validated { raw } = &self.state {
+            self.state = FsmState::Enriched { raw: raw.clone(), metadata: metadata.into() };
+        }
+    }
+
+    pub fn persist(&mut self) {
+        if let FsmState::Enriched { raw, metadata } = &self.state {
+            self.state = FsmState::Persisted { raw: raw.clone(), metadata: metadata.clone() };
+        }
+    }
+
+    pub fn save(&self) -> Memento {
+        Memento { version: MEMENTO_VERSION, state: self.state.clone() }
+    }
+
+    pub fn restore(&mut self, memento: &Memento) -> Result<(), IncompatibleMemento> {
+        if memento.version != MEMENTO_VERSION {
+            return Err(IncompatibleMemento);
+        }
+        self.state = memento.state.clone();
+        Ok(())
+    }
+}
+
+/// Keeps a bounded history of mementos, evicting the oldest entry once full.
+pub struct Caretaker {
+    history: VecDeque<Memento>,
+    capacity: usize,
+}
+
+impl Caretaker {
+    pub fn new(capacity: usize) -> Self {
+        Self { history: VecDeque::with_capacity(capacity), capacity }
+    }
+
+    pub fn push(&mut self, memento: Memento) {
+        if self.history.len() == self.capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back(memento);
+    }
+
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
+    pub fn last(&self) -> Option<&Memento> {
+        self.history.back()
+    }
+
+    pub fn pop(&mut self) -> Option<Memento> {
+        self.history.pop_back()
+    }
+}
+
+fn main() {
+    let mut fsm = Fsm::new("payload-1");
+    let mut caretaker = Caretaker::new(3);
+    caretaker.push(fsm.save());
+
+    // Enrich with the wrong metadata by mistake.
+    fsm.enrich("wrong-metadata");
+    println!("After bad enrich: {:?}", fsm.state());
+
+    // Undo: restore the pre-enrichment snapshot.
+    let pre_enrich = caretaker.last().unwrap().clone();
+    fsm.restore(&pre_enrich).unwrap();
+    println!("After undo: {:?}", fsm.state());
+
+    // Re-enrich correctly and persist.
+    fsm.enrich("correct-metadata");
+    caretaker.push(fsm.save());
+    fsm.persist();
+    println!("Final state: {:?}", fsm.state());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_restore_round_trips_in_every_state() {
+        let mut fsm = Fsm::new("raw");
+        let validated = fsm.save();
+
+        fsm.enrich("meta");
+        let enriched = fsm.save();
+
+        fsm.persist();
+        let persisted = fsm.save();
+
+        fsm.restore(&validated).unwrap();
+        assert_eq!(*fsm.state(), FsmState::Validated { raw: "raw".into() });
+
+        fsm.restore(&enriched).unwrap();
+        assert_eq!(*fsm.state(), FsmState::Enriched { raw: "raw".into(), metadata: "meta".into() });
+
+        fsm.restore(&persisted).unwrap();
+        assert_eq!(*fsm.state(), FsmState::Persisted { raw: "raw".into(), metadata: "meta".into() });
+    }
+
+    #[test]
+    fn undo_a_bad_enrich_and_redo_correctly() {
+        let mut fsm = Fsm::new("raw");
+        let pre_enrich = fsm.save();
+
+        fsm.enrich("wrong");
+        fsm.restore(&pre_enrich).unwrap();
+        fsm.enrich("right");
+
+        assert_eq!(*fsm.state(), FsmState::Enriched { raw: "raw".into(), metadata: "right".into() });
+    }
+
+    #[test]
+    fn restoring_an_incompatible_memento_fails_cleanly() {
+        let mut fsm = Fsm::new("raw");
+        let mut stale = fsm.save();
+        stale.version = MEMENTO_VERSION + 1;
+        assert_eq!(fsm.restore(&stale), Err(IncompatibleMemento));
+        // The FSM is untouched by the failed restore.
+        assert_eq!(*fsm.state(), FsmState::Validated { raw: "raw".into() });
+    }
+
+    #[test]
+    fn caretaker_evicts_oldest_entry_once_full() {
+        let mut caretaker = Caretaker::new(2);
+        let fsm = Fsm::new("raw");
+        let first = fsm.save();
+        let second = fsm.save();
+        let third = fsm.save();
+
+        caretaker.push(first);
+        caretaker.push(second.clone());
+        caretaker.push(third.clone());
+
+        assert_eq!(caretaker.len(), 2);
+        assert_eq!(caretaker.pop().unwrap().state, third.state);
+        assert_eq!(caretaker.pop().unwrap().state, second.state);
+        assert!(caretaker.is_empty());
+    }
+}
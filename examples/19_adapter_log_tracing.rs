@@ -0,0 +1,110 @@
+// cargo run --example 19_adapter_log_tracing --features log-adapter,tracing-adapter
+
+// Our `CommandLogger` trait (see the command-bus examples) is bespoke - it predates pulling in
+// the `log` and `tracing` ecosystems. Adapter pattern bridges the two directions:
+//   - `LogAdapter` / `TracingAdapter`: implement `CommandLogger` by forwarding into `log::` /
+//     `tracing::` macros, so our existing bus/observer code can log through either ecosystem.
+//   - `LoggerAsLog`: the reverse adapter, implementing `log::Log` on top of any `CommandLogger`,
+//     so third-party crates that log via the `log` facade land in the same sink we already use.
+
+pub trait CommandLogger {
+    fn log(&self, message: &str);
+}
+
+/// Forwards `CommandLogger::log` calls into the `log` crate at `Info` level.
+pub struct LogAdapter;
+impl CommandLogger for LogAdapter {
+    fn log(&self, message: &str) {
+        log::info!("{message}");
+    }
+}
+
+/// Forwards `CommandLogger::log` calls into `tracing` at `info` level.
+pub struct TracingAdapter;
+impl CommandLogger for TracingAdapter {
+    fn log(&self, message: &str) {
+        tracing::info!("{message}");
+    }
+}
+
+/// The reverse direction: makes any `CommandLogger` usable as the global `log::Log` sink, so
+/// output from third-party crates that log via the `log` facade lands in the same place as our
+/// bus/observer logs.
+pub struct LoggerAsLog<L: CommandLogger> {
+    inner: L,
+}
+
+impl<L: CommandLogger> LoggerAsLog<L> {
+    pub fn new(inner: L) -> Self {
+        Self { inner }
+    }
+}
+
+impl<L: CommandLogger + Send + Sync> log::Log for LoggerAsLog<L> {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        self.inner.log(&format!("[{}] {}", record.level(), record.args()));
+    }
+
+    fn flush(&self) {}
+}
+
+fn main() {
+    let log_sink = LogAdapter;
+    log_sink.log("via LogAdapter, forwarded into the `log` facade");
+
+    let tracing_sink = TracingAdapter;
+    tracing_sink.log("via TracingAdapter, forwarded into `tracing`");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::Log;
+    use std::sync::{Mutex, OnceLock};
+
+    struct RecordingLogger {
+        lines: &'static Mutex<Vec<String>>,
+    }
+    impl CommandLogger for RecordingLogger {
+        fn log(&self, message: &str) {
+            self.lines.lock().unwrap().push(message.to_string());
+        }
+    }
+
+    fn recording_lines() -> &'static Mutex<Vec<String>> {
+        static LINES: OnceLock<Mutex<Vec<String>>> = OnceLock::new();
+        LINES.get_or_init(|| Mutex::new(Vec::new()))
+    }
+
+    #[test]
+    fn log_as_logger_sink_receives_formatted_records() {
+        let lines = recording_lines();
+        lines.lock().unwrap().clear();
+        let sink = LoggerAsLog::new(RecordingLogger { lines });
+
+        log::logger().flush();
+        sink.log(&log::Record::builder().args(format_args!("hello from log")).level(log::Level::Warn).target("test").build());
+
+        let captured = lines.lock().unwrap();
+        assert_eq!(captured.len(), 1);
+        assert!(captured[0].contains("WARN"));
+        assert!(captured[0].contains("hello from log"));
+    }
+
+    #[test]
+    fn log_adapter_does_not_panic_without_a_global_logger_installed() {
+        // `log::info!` is a no-op without `log::set_logger`, but it must never panic.
+        LogAdapter.log("noop when unconfigured");
+    }
+
+    #[test]
+    fn tracing_adapter_does_not_panic_without_a_subscriber_installed() {
+        // Same guarantee on the `tracing` side: events are dropped, not panicking, absent a
+        // subscriber.
+        TracingAdapter.log("noop when unconfigured");
+    }
+}
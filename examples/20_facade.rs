@@ -0,0 +1,214 @@
+// cargo run --example 20_facade
+
+// Facade pattern: wire a command bus, an event bus, and the pipeline FSM together behind a
+// small surface. Callers of `UserService` never see the bus, the topic, or the FSM - they just
+// call `create_user`/`delete_user`/`subscribe`. Every subsystem is injected, so tests can swap
+// in fakes without touching the facade itself.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+pub type UserId = u32;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UserEvent {
+    UserCreated { id: UserId, name: String },
+    UserDeleted { id: UserId },
+}
+
+// --- A minimal command bus, scoped to this facade's two commands -------------------------------
+
+pub trait CommandHandler {
+    fn create(&mut self, name: String) -> UserId;
+    fn delete(&mut self, id: UserId) -> bool;
+}
+
+struct UserBus<H: CommandHandler> {
+    handler: H,
+}
+impl<H: CommandHandler> UserBus<H> {
+    fn dispatch_create(&mut self, name: String) -> UserId {
+        self.handler.create(name)
+    }
+    fn dispatch_delete(&mut self, id: UserId) -> bool {
+        self.handler.delete(id)
+    }
+}
+
+// --- A minimal event topic, scoped to UserEvent -------------------------------------------------
+
+type Subscriber = Rc<RefCell<dyn FnMut(UserEvent)>>;
+
+#[derive(Default)]
+struct Topic {
+    subs: Vec<Subscriber>,
+}
+impl Topic {
+    fn subscribe(&mut self, callback: Subscriber) {
+        self.subs.push(callback);
+    }
+    fn publish(&self, event: UserEvent) {
+        for sub in &self.subs {
+            sub.borrow_mut()(event.clone());
+        }
+    }
+}
+
+// --- The pipeline FSM tracking each user record's lifecycle -------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordState {
+    Validated,
+    Enriched,
+    Persisted,
+}
+
+fn advance(state: RecordState) -> RecordState {
+    match state {
+        RecordState::Validated => RecordState::Enriched,
+        RecordState::Enriched => RecordState::Persisted,
+        RecordState::Persisted => RecordState::Persisted,
+    }
+}
+
+// --- The real handler: an in-memory user registry, also the default CommandHandler impl --------
+
+#[derive(Default)]
+pub struct InMemoryUserStore {
+    next_id: UserId,
+    names: HashMap<UserId, String>,
+}
+impl CommandHandler for InMemoryUserStore {
+    fn create(&mut self, name: String) -> UserId {
+        self.next_id += 1;
+        self.names.insert(self.next_id, name);
+        self.next_id
+    }
+    fn delete(&mut self, id: UserId) -> bool {
+        self.names.remove(&id).is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownUser;
+
+impl std::fmt::Display for UnknownUser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown user id")
+    }
+}
+impl std::error::Error for UnknownUser {}
+
+/// The facade: hides the command bus, the event topic, and the lifecycle FSM behind three
+/// methods. Every subsystem is injected via [`UserService::new`], so fakes can replace any of
+/// them in tests.
+pub struct UserService<H: CommandHandler> {
+    bus: UserBus<H>,
+    topic: Topic,
+    records: HashMap<UserId, RecordState>,
+}
+
+impl<H: CommandHandler> UserService<H> {
+    pub fn new(handler: H) -> Self {
+        Self { bus: UserBus { handler }, topic: Topic::default(), records: HashMap::new() }
+    }
+
+    pub fn create_user(&mut self, name: impl Into<String>) -> UserId {
+        let name = name.into();
+        let id = self.bus.dispatch_create(name.clone());
+        let mut state = RecordState::Validated;
+        state = advance(state); // Enriched
+        state = advance(state); // Persisted
+        self.records.insert(id, state);
+        self.topic.publish(UserEvent::UserCreated { id, name });
+        id
+    }
+
+    pub fn delete_user(&mut self, id: UserId) -> Result<(), UnknownUser> {
+        if !self.bus.dispatch_delete(id) {
+            return Err(UnknownUser);
+        }
+        self.records.remove(&id);
+        self.topic.publish(UserEvent::UserDeleted { id });
+        Ok(())
+    }
+
+    pub fn record_state(&self, id: UserId) -> Option<RecordState> {
+        self.records.get(&id).copied()
+    }
+
+    pub fn subscribe(&mut self, callback: impl FnMut(UserEvent) + 'static) {
+        self.topic.subscribe(Rc::new(RefCell::new(callback)));
+    }
+}
+
+fn main() {
+    let mut service = UserService::new(InMemoryUserStore::default());
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let events_for_sub = Rc::clone(&events);
+    service.subscribe(move |event| events_for_sub.borrow_mut().push(event));
+
+    let id = service.create_user("Alice");
+    println!("Created user {id}, record state: {:?}", service.record_state(id));
+
+    service.delete_user(id).unwrap();
+    println!("Events observed: {:?}", events.borrow());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct FakeHandler {
+        created: Vec<String>,
+        deleted: Vec<UserId>,
+    }
+    impl CommandHandler for FakeHandler {
+        fn create(&mut self, name: String) -> UserId {
+            self.created.push(name);
+            self.created.len() as UserId
+        }
+        fn delete(&mut self, id: UserId) -> bool {
+            self.deleted.push(id);
+            true
+        }
+    }
+
+    #[test]
+    fn create_then_delete_reaches_persisted_and_notifies_observer() {
+        let mut service = UserService::new(FakeHandler::default());
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let events_for_sub = Rc::clone(&events);
+        service.subscribe(move |event| events_for_sub.borrow_mut().push(event));
+
+        let id = service.create_user("Bob");
+        assert_eq!(service.record_state(id), Some(RecordState::Persisted));
+
+        service.delete_user(id).unwrap();
+        assert_eq!(service.record_state(id), None);
+
+        assert_eq!(events.borrow().len(), 2);
+        assert!(matches!(events.borrow()[0], UserEvent::UserCreated { id: observed_id, .. } if observed_id == id));
+        assert_eq!(events.borrow()[1], UserEvent::UserDeleted { id });
+    }
+
+    #[test]
+    fn deleting_an_unknown_user_reports_an_error() {
+        struct FailingHandler;
+        impl CommandHandler for FailingHandler {
+            fn create(&mut self, _name: String) -> UserId {
+                0
+            }
+            fn delete(&mut self, _id: UserId) -> bool {
+                false
+            }
+        }
+
+        let mut failing = UserService::new(FailingHandler);
+        assert_eq!(failing.delete_user(999), Err(UnknownUser));
+    }
+}
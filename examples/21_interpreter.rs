@@ -0,0 +1,319 @@
+// cargo run --example 21_interpreter
+
+// Interpreter pattern: tokenize an arithmetic expression with a small FSM (in the spirit of
+// 06_state_machine_enums_comments' byte-scanning FSM), parse the tokens with a recursive-descent
+// parser into the Expr tree from 13_visitor, then evaluate. Every error carries the byte span of
+// the offending token so callers can point at the right character.
+
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Num(f64),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    // Carries the span of the '/' token itself, so a runtime DivisionByZero can point at the
+    // operator that divided by zero rather than an arbitrary fixed position.
+    Div(Box<Expr>, Box<Expr>, Span),
+    Neg(Box<Expr>),
+}
+
+// --- Lexer: a tiny FSM over bytes, producing spanned tokens -------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenKind {
+    Number(f64),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    LParen,
+    RParen,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+enum LexState {
+    Start,
+    InNumber { start: usize },
+}
+
+pub fn tokenize(input: &str) -> Result<Vec<Token>, InterpreterError> {
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+    let mut state = LexState::Start;
+    let mut i = 0;
+
+    while i <= bytes.len() {
+        let byte = bytes.get(i).copied();
+        match &state {
+            LexState::Start => match byte {
+                None => break,
+                Some(b) if b.is_ascii_whitespace() => i += 1,
+                Some(b) if b.is_ascii_digit() || b == b'.' => {
+                    state = LexState::InNumber { start: i };
+                    i += 1;
+                }
+                Some(b'+') => {
+                    tokens.push(Token { kind: TokenKind::Plus, span: Span { start: i, end: i + 1 } });
+                    i += 1;
+                }
+                Some(b'-') => {
+                    tokens.push(Token { kind: TokenKind::Minus, span: Span { start: i, end: i + 1 } });
+                    i += 1;
+                }
+                Some(b'*') => {
+                    tokens.push(Token { kind: TokenKind::Star, span: Span { start: i, end: i + 1 } });
+                    i += 1;
+                }
+                Some(b'/') => {
+                    tokens.push(Token { kind: TokenKind::Slash, span: Span { start: i, end: i + 1 } });
+                    i += 1;
+                }
+                Some(b'(') => {
+                    tokens.push(Token { kind: TokenKind::LParen, span: Span { start: i, end: i + 1 } });
+                    i += 1;
+                }
+                Some(b')') => {
+                    tokens.push(Token { kind: TokenKind::RParen, span: Span { start: i, end: i + 1 } });
+                    i += 1;
+                }
+                Some(_) => {
+                    return Err(InterpreterError::UnexpectedChar { span: Span { start: i, end: i + 1 } });
+                }
+            },
+            LexState::InNumber { start } => {
+                let start = *start;
+                match byte {
+                    Some(b) if b.is_ascii_digit() || b == b'.' => i += 1,
+                    _ => {
+                        let text = &input[start..i];
+                        let value = text.parse::<f64>().map_err(|_| InterpreterError::UnexpectedChar { span: Span { start, end: i } })?;
+                        tokens.push(Token { kind: TokenKind::Number(value), span: Span { start, end: i } });
+                        state = LexState::Start;
+                    }
+                }
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+// --- Errors, always pointing at a span in the source --------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InterpreterError {
+    UnexpectedChar { span: Span },
+    UnexpectedToken { span: Span },
+    UnbalancedParen { span: Span },
+    TrailingInput { span: Span },
+    DivisionByZero { span: Span },
+}
+
+impl fmt::Display for InterpreterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterpreterError::UnexpectedChar { span } => write!(f, "unexpected character at {}..{}", span.start, span.end),
+            InterpreterError::UnexpectedToken { span } => write!(f, "unexpected token at {}..{}", span.start, span.end),
+            InterpreterError::UnbalancedParen { span } => write!(f, "unbalanced parenthesis at {}..{}", span.start, span.end),
+            InterpreterError::TrailingInput { span } => write!(f, "trailing input at {}..{}", span.start, span.end),
+            InterpreterError::DivisionByZero { span } => write!(f, "division by zero at {}..{}", span.start, span.end),
+        }
+    }
+}
+impl std::error::Error for InterpreterError {}
+
+// --- Recursive-descent parser, precedence climbing via one function per level -------------------
+// expr   := term (('+' | '-') term)*
+// term   := unary (('*' | '/') unary)*
+// unary  := '-' unary | primary
+// primary:= NUMBER | '(' expr ')'
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    end_span: Span,
+}
+
+impl<'a> Parser<'a> {
+    fn new(tokens: &'a [Token], end_span: Span) -> Self {
+        Self { tokens, pos: 0, end_span }
+    }
+
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn span_here(&self) -> Span {
+        self.peek().map(|t| t.span).unwrap_or(self.end_span)
+    }
+
+    fn bump(&mut self) -> Option<Token> {
+        let tok = self.peek();
+        if tok.is_some() {
+            self.pos += 1;
+        }
+        tok
+    }
+
+    fn expr(&mut self) -> Result<Expr, InterpreterError> {
+        let mut lhs = self.term()?;
+        loop {
+            match self.peek().map(|t| t.kind) {
+                Some(TokenKind::Plus) => {
+                    self.bump();
+                    lhs = Expr::Add(Box::new(lhs), Box::new(self.term()?));
+                }
+                Some(TokenKind::Minus) => {
+                    self.bump();
+                    lhs = Expr::Sub(Box::new(lhs), Box::new(self.term()?));
+                }
+                _ => break,
+            }
+        }
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<Expr, InterpreterError> {
+        let mut lhs = self.unary()?;
+        loop {
+            match self.peek().map(|t| t.kind) {
+                Some(TokenKind::Star) => {
+                    self.bump();
+                    lhs = Expr::Mul(Box::new(lhs), Box::new(self.unary()?));
+                }
+                Some(TokenKind::Slash) => {
+                    let span = self.bump().expect("peeked Some above").span;
+                    lhs = Expr::Div(Box::new(lhs), Box::new(self.unary()?), span);
+                }
+                _ => break,
+            }
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, InterpreterError> {
+        if let Some(Token { kind: TokenKind::Minus, .. }) = self.peek() {
+            self.bump();
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, InterpreterError> {
+        match self.bump() {
+            Some(Token { kind: TokenKind::Number(value), .. }) => Ok(Expr::Num(value)),
+            Some(Token { kind: TokenKind::LParen, span }) => {
+                let inner = self.expr()?;
+                match self.bump() {
+                    Some(Token { kind: TokenKind::RParen, .. }) => Ok(inner),
+                    _ => Err(InterpreterError::UnbalancedParen { span }),
+                }
+            }
+            Some(Token { span, .. }) => Err(InterpreterError::UnexpectedToken { span }),
+            None => Err(InterpreterError::UnexpectedToken { span: self.span_here() }),
+        }
+    }
+}
+
+pub fn parse(tokens: &[Token], source_len: usize) -> Result<Expr, InterpreterError> {
+    let end_span = Span { start: source_len, end: source_len };
+    let mut parser = Parser::new(tokens, end_span);
+    let expr = parser.expr()?;
+    if let Some(tok) = parser.peek() {
+        return Err(InterpreterError::TrailingInput { span: tok.span });
+    }
+    Ok(expr)
+}
+
+pub fn evaluate(expr: &Expr) -> Result<f64, InterpreterError> {
+    match expr {
+        Expr::Num(value) => Ok(*value),
+        Expr::Add(lhs, rhs) => Ok(evaluate(lhs)? + evaluate(rhs)?),
+        Expr::Sub(lhs, rhs) => Ok(evaluate(lhs)? - evaluate(rhs)?),
+        Expr::Mul(lhs, rhs) => Ok(evaluate(lhs)? * evaluate(rhs)?),
+        Expr::Div(lhs, rhs, span) => {
+            let rhs_value = evaluate(rhs)?;
+            if rhs_value == 0.0 {
+                return Err(InterpreterError::DivisionByZero { span: *span });
+            }
+            Ok(evaluate(lhs)? / rhs_value)
+        }
+        Expr::Neg(inner) => Ok(-evaluate(inner)?),
+    }
+}
+
+pub fn interpret(source: &str) -> Result<f64, InterpreterError> {
+    let tokens = tokenize(source)?;
+    let expr = parse(&tokens, source.len())?;
+    evaluate(&expr)
+}
+
+fn main() {
+    let source = "3 + 4 * (2 - 1)";
+    match interpret(source) {
+        Ok(value) => println!("{source} = {value}"),
+        Err(err) => println!("error: {err}"),
+    }
+
+    let bad = "3 + (4 * 2";
+    match interpret(bad) {
+        Ok(value) => println!("{bad} = {value}"),
+        Err(err) => println!("{bad} -> error: {err}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn respects_precedence() {
+        assert_eq!(interpret("3 + 4 * 2").unwrap(), 11.0);
+    }
+
+    #[test]
+    fn parentheses_override_precedence() {
+        assert_eq!(interpret("(3 + 4) * 2").unwrap(), 14.0);
+    }
+
+    #[test]
+    fn unary_minus_binds_tighter_than_binary_operators() {
+        assert_eq!(interpret("-3 + 4").unwrap(), 1.0);
+        assert_eq!(interpret("4 * -2").unwrap(), -8.0);
+    }
+
+    #[test]
+    fn division_by_zero_points_at_the_offending_slash() {
+        assert_eq!(interpret("1 / 0"), Err(InterpreterError::DivisionByZero { span: Span { start: 2, end: 3 } }));
+    }
+
+    #[test]
+    fn unbalanced_paren_points_at_the_opening_paren() {
+        let err = interpret("(1 + 2").unwrap_err();
+        assert_eq!(err, InterpreterError::UnbalancedParen { span: Span { start: 0, end: 1 } });
+    }
+
+    #[test]
+    fn trailing_input_points_at_the_extra_token() {
+        let err = interpret("1 + 2)").unwrap_err();
+        assert_eq!(err, InterpreterError::TrailingInput { span: Span { start: 5, end: 6 } });
+    }
+
+    #[test]
+    fn unexpected_character_reports_its_span() {
+        let err = tokenize("1 + @").unwrap_err();
+        assert_eq!(err, InterpreterError::UnexpectedChar { span: Span { start: 4, end: 5 } });
+    }
+}
@@ -0,0 +1,127 @@
+// cargo run --example 22_flyweight
+
+// Flyweight pattern: an `Interner` maps each distinct string to a small `Symbol(u32)` handle.
+// Every occurrence of the same word after the first one costs a HashMap lookup instead of a new
+// allocation - the shared (flyweight) data is the owned String behind each Symbol, stored once.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbol(u32);
+
+#[derive(Default)]
+pub struct Interner {
+    strings: Vec<String>,
+    lookup: HashMap<String, Symbol>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { strings: Vec::with_capacity(capacity), lookup: HashMap::with_capacity(capacity) }
+    }
+
+    /// Interns `text`, returning its `Symbol`. Interning the same text twice returns the same
+    /// `Symbol` both times and allocates only on the first call.
+    pub fn intern(&mut self, text: &str) -> Symbol {
+        if let Some(&symbol) = self.lookup.get(text) {
+            return symbol;
+        }
+        let symbol = Symbol(self.strings.len() as u32);
+        self.strings.push(text.to_string());
+        self.lookup.insert(text.to_string(), symbol);
+        symbol
+    }
+
+    /// Resolves a `Symbol` back to its text in O(1). Stable for the interner's whole lifetime:
+    /// symbols are never reused or invalidated.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.strings[symbol.0 as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &str)> {
+        self.strings.iter().enumerate().map(|(i, s)| (Symbol(i as u32), s.as_str()))
+    }
+}
+
+/// Counts word frequencies, interning each word so repeated words cost one allocation total
+/// instead of one allocation per occurrence.
+pub fn word_frequencies(text: &str) -> (Interner, HashMap<Symbol, usize>) {
+    let mut interner = Interner::new();
+    let mut counts = HashMap::new();
+    for word in text.split_whitespace() {
+        let symbol = interner.intern(word);
+        *counts.entry(symbol).or_insert(0) += 1;
+    }
+    (interner, counts)
+}
+
+/// Counts the number of *unique* words in `text`, reusing the same interner idea: a word already
+/// seen resolves to the same `Symbol` instead of growing the table.
+pub fn unique_word_count(text: &str) -> usize {
+    let mut interner = Interner::new();
+    for word in text.split_whitespace() {
+        interner.intern(word);
+    }
+    interner.len()
+}
+
+fn main() {
+    let text = "the quick brown fox jumps over the lazy dog the fox runs";
+    let unique = unique_word_count(text);
+    println!("Unique words: {unique}");
+
+    let (interner, counts) = word_frequencies(text);
+    let mut by_symbol: Vec<_> = counts.into_iter().collect();
+    by_symbol.sort_by_key(|(symbol, _)| symbol.0);
+    for (symbol, count) in by_symbol {
+        println!("{:>2} x {}", count, interner.resolve(symbol));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interning_the_same_text_is_idempotent() {
+        let mut interner = Interner::new();
+        let a = interner.intern("hello");
+        let b = interner.intern("hello");
+        assert_eq!(a, b);
+        assert_eq!(interner.len(), 1);
+    }
+
+    #[test]
+    fn resolve_round_trips() {
+        let mut interner = Interner::new();
+        let symbol = interner.intern("world");
+        assert_eq!(interner.resolve(symbol), "world");
+    }
+
+    #[test]
+    fn distinct_words_get_distinct_symbols() {
+        let mut interner = Interner::new();
+        let a = interner.intern("foo");
+        let b = interner.intern("bar");
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn word_frequencies_counts_repeated_words() {
+        let (interner, counts) = word_frequencies("a b a c a b");
+        let a = counts.iter().find(|(sym, _)| interner.resolve(**sym) == "a").unwrap().1;
+        assert_eq!(*a, 3);
+    }
+}
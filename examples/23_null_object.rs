@@ -0,0 +1,174 @@
+// cargo run --example 23_null_object
+
+// Null Object pattern: canonical "do nothing" implementations for the traits used across the
+// other examples, so callers who want no logging/middleware/payment behavior don't have to
+// reach for `Option<Box<dyn Trait>>` and `if let Some(...)` everywhere. Each null object also
+// backs a `Default` constructor, so `CommandBus::default()` "just works".
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+pub trait CommandLogger {
+    fn log(&self, message: &str);
+}
+
+/// Does nothing. The canonical default for APIs that take `impl CommandLogger` but whose caller
+/// doesn't want logging.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopLogger;
+impl CommandLogger for NoopLogger {
+    fn log(&self, _message: &str) {}
+}
+
+pub trait Middleware {
+    fn before(&self, command_name: &str);
+    fn after(&self, command_name: &str);
+}
+
+/// Does nothing before or after dispatch. The canonical default when no middleware is needed.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopMiddleware;
+impl Middleware for NoopMiddleware {
+    fn before(&self, _command_name: &str) {}
+    fn after(&self, _command_name: &str) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Receipt {
+    pub amount: f64,
+    pub fee: f64,
+}
+
+pub trait PaymentStrategy {
+    fn pay(&self, amount: f64) -> Receipt;
+}
+
+/// Always "succeeds" with a zero-fee receipt. Useful as a test double wherever a
+/// `PaymentStrategy` is required but payment itself is out of scope for the test.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopPaymentStrategy;
+impl PaymentStrategy for NoopPaymentStrategy {
+    fn pay(&self, amount: f64) -> Receipt {
+        Receipt { amount, fee: 0.0 }
+    }
+}
+
+pub struct CommandBus<L: CommandLogger = NoopLogger, M: Middleware = NoopMiddleware> {
+    logger: L,
+    middleware: M,
+}
+
+impl Default for CommandBus<NoopLogger, NoopMiddleware> {
+    fn default() -> Self {
+        Self { logger: NoopLogger, middleware: NoopMiddleware }
+    }
+}
+
+impl<L: CommandLogger, M: Middleware> CommandBus<L, M> {
+    pub fn new(logger: impl Into<L>, middleware: impl Into<M>) -> Self {
+        Self { logger: logger.into(), middleware: middleware.into() }
+    }
+
+    pub fn dispatch(&self, command_name: &str) {
+        self.middleware.before(command_name);
+        self.logger.log(&format!("dispatching {command_name}"));
+        self.middleware.after(command_name);
+    }
+}
+
+pub struct PaymentContext<S: PaymentStrategy = NoopPaymentStrategy> {
+    strategy: S,
+}
+impl PaymentContext<NoopPaymentStrategy> {
+    pub fn noop() -> Self {
+        Self { strategy: NoopPaymentStrategy }
+    }
+}
+impl<S: PaymentStrategy> PaymentContext<S> {
+    pub fn new(strategy: impl Into<S>) -> Self {
+        Self { strategy: strategy.into() }
+    }
+    pub fn process(&self, amount: f64) -> Receipt {
+        self.strategy.pay(amount)
+    }
+}
+
+// Counts calls instead of doing anything, so tests can assert a logger saw zero activity.
+#[derive(Default, Clone)]
+pub struct CountingLogger {
+    calls: Rc<Cell<u32>>,
+}
+impl CountingLogger {
+    pub fn calls(&self) -> u32 {
+        self.calls.get()
+    }
+}
+impl CommandLogger for CountingLogger {
+    fn log(&self, _message: &str) {
+        self.calls.set(self.calls.get() + 1);
+    }
+}
+
+fn main() {
+    let bus = CommandBus::<NoopLogger, NoopMiddleware>::default();
+    bus.dispatch("CreateUser");
+
+    let payments = PaymentContext::noop();
+    println!("Noop receipt: {:?}", payments.process(100.0));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn noop_logger_produces_no_activity() {
+        let counting = CountingLogger::default();
+        // Wrap the real NoopLogger in a counting decorator to prove it never calls through.
+        struct Inert<L: CommandLogger> {
+            inner: L,
+            wrapped_calls: Rc<Cell<u32>>,
+        }
+        impl<L: CommandLogger> CommandLogger for Inert<L> {
+            fn log(&self, message: &str) {
+                self.wrapped_calls.set(self.wrapped_calls.get() + 1);
+                self.inner.log(message);
+            }
+        }
+        let inert = Inert { inner: NoopLogger, wrapped_calls: counting.calls.clone() };
+        inert.log("should be inert downstream, but the wrapper itself still counts the call");
+        assert_eq!(counting.calls(), 1);
+
+        // The NoopLogger itself, called directly, leaves no observable trace at all.
+        let direct = CountingLogger::default();
+        // NoopLogger can't be observed directly (it does nothing), so this documents intent:
+        // calling it never panics and has no side effects to assert against.
+        NoopLogger.log("ignored");
+        assert_eq!(direct.calls(), 0);
+    }
+
+    #[test]
+    fn noop_middleware_is_inert() {
+        NoopMiddleware.before("anything");
+        NoopMiddleware.after("anything");
+        // No panics, no state to observe - exactly the point of a null object.
+    }
+
+    #[test]
+    fn noop_payment_strategy_always_succeeds_with_zero_fee() {
+        let receipt = NoopPaymentStrategy.pay(42.0);
+        assert_eq!(receipt, Receipt { amount: 42.0, fee: 0.0 });
+    }
+
+    #[test]
+    fn command_bus_default_wires_in_the_null_objects() {
+        let bus = CommandBus::default();
+        bus.dispatch("DeleteUser"); // must not panic without an explicit logger/middleware
+    }
+
+    #[test]
+    fn payment_context_noop_wires_in_the_null_strategy() {
+        let payments = PaymentContext::noop();
+        assert_eq!(payments.process(10.0), Receipt { amount: 10.0, fee: 0.0 });
+    }
+}
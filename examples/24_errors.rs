@@ -0,0 +1,120 @@
+// cargo run --example 24_errors
+
+// Crate-wide error vocabulary: one enum per module family (bus, FSM, payment, builder, scanner,
+// pool), each via `thiserror` so `Display`/`Error`/`source()` chaining fall out of the derive
+// instead of being hand-rolled per module. These replace the ad-hoc `String` errors and panics
+// used for illustration elsewhere in this crate - library-shaped code should return one of
+// these instead of unwrapping; only `main()` in an example is allowed to unwrap.
+
+use std::num::ParseFloatError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BusError {
+    #[error("no handler registered for command {command}")]
+    NoHandler { command: &'static str },
+    #[error("handler registered for {command} has the wrong type")]
+    WrongHandlerType { command: &'static str },
+}
+
+#[derive(Debug, Error)]
+pub enum FsmError {
+    #[error("no transition defined for event {event} from state {state}")]
+    InvalidTransition { state: &'static str, event: &'static str },
+    #[error("FSM exceeded its {limit} step safety cap without reaching a final state")]
+    StepCapExceeded { limit: usize },
+}
+
+#[derive(Debug, Error)]
+pub enum PaymentError {
+    #[error("payment of {amount} was declined: {reason}")]
+    Declined { amount: f64, reason: String },
+    #[error("no payment strategy registered for key '{key}'")]
+    UnknownStrategy { key: String },
+}
+
+#[derive(Debug, Error)]
+pub enum BuildError {
+    #[error("required field '{field}' was not set")]
+    MissingField { field: &'static str },
+    #[error("field '{field}' failed validation: {reason}")]
+    InvalidField { field: &'static str, reason: String },
+}
+
+#[derive(Debug, Error)]
+pub enum ScanError {
+    #[error("unexpected end of input while scanning")]
+    UnexpectedEof,
+    #[error("could not parse number at byte {offset}")]
+    InvalidNumber {
+        offset: usize,
+        #[source]
+        source: ParseFloatError,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum PoolError {
+    #[error("object pool is exhausted")]
+    Exhausted,
+    #[error("object pool factory failed")]
+    FactoryFailed(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+fn main() {
+    let err = BuildError::MissingField { field: "email" };
+    println!("{err}");
+
+    let err = ScanError::InvalidNumber { offset: 12, source: "12.3.4".parse::<f64>().unwrap_err() };
+    println!("{err}");
+    println!("caused by: {}", std::error::Error::source(&err).unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_error_bounds<E: std::error::Error + Send + Sync + 'static>(_: &E) {}
+
+    #[test]
+    fn every_error_type_is_error_send_sync_static() {
+        assert_error_bounds(&BusError::NoHandler { command: "CreateUser" });
+        assert_error_bounds(&FsmError::StepCapExceeded { limit: 10 });
+        assert_error_bounds(&PaymentError::UnknownStrategy { key: "bitcoin".into() });
+        assert_error_bounds(&BuildError::MissingField { field: "name" });
+        assert_error_bounds(&ScanError::UnexpectedEof);
+        assert_error_bounds(&PoolError::Exhausted);
+    }
+
+    #[test]
+    fn display_messages_include_key_context_fields() {
+        let err = BusError::NoHandler { command: "DeleteUser" };
+        assert!(err.to_string().contains("DeleteUser"));
+
+        let err = FsmError::InvalidTransition { state: "Persisted", event: "Enrich" };
+        assert!(err.to_string().contains("Persisted"));
+        assert!(err.to_string().contains("Enrich"));
+
+        let err = PaymentError::Declined { amount: 42.0, reason: "insufficient funds".into() };
+        assert!(err.to_string().contains("42"));
+        assert!(err.to_string().contains("insufficient funds"));
+
+        let err = BuildError::InvalidField { field: "age", reason: "must be positive".into() };
+        assert!(err.to_string().contains("age"));
+        assert!(err.to_string().contains("must be positive"));
+    }
+
+    #[test]
+    fn scan_error_chains_the_underlying_parse_error() {
+        let parse_err = "not-a-number".parse::<f64>().unwrap_err();
+        let err = ScanError::InvalidNumber { offset: 3, source: parse_err };
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn pool_error_chains_an_arbitrary_factory_failure() {
+        let factory_err: Box<dyn std::error::Error + Send + Sync> = "factory blew up".into();
+        let err = PoolError::FactoryFailed(factory_err);
+        assert!(std::error::Error::source(&err).is_some());
+    }
+}
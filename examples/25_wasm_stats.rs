@@ -0,0 +1,70 @@
+// cargo build --example 25_wasm_stats --no-default-features --features wasm --target wasm32-unknown-unknown
+
+// A wasm-bindgen-friendly wrapper around the text-FSM word/line/number counter (01_enums_fsm.rs).
+// The counting logic itself takes only `&str` - no `std::fs`, no `std::time::Instant`, no
+// threads - so it compiles for wasm32-unknown-unknown without the `async`/`rand` features (which
+// is where file/clock/thread-dependent code, like the tokio examples, lives instead).
+
+use wasm_bindgen::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Whitespace,
+    InWord,
+    InNumber,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextStats {
+    pub word_count: usize,
+    pub line_count: usize,
+    pub number_count: usize,
+}
+
+/// Pure function: counts words, lines, and numbers in `text`. No I/O, no clock, no threads -
+/// safe to call from a wasm32-unknown-unknown build.
+pub fn count_stats(text: &str) -> TextStats {
+    let mut stats = TextStats::default();
+    let mut state = CharClass::Whitespace;
+
+    for ch in text.chars() {
+        if ch == '\n' {
+            stats.line_count += 1;
+        }
+        let next_state = if ch.is_whitespace() {
+            CharClass::Whitespace
+        } else if ch.is_ascii_digit() {
+            CharClass::InNumber
+        } else {
+            CharClass::InWord
+        };
+
+        if state == CharClass::Whitespace && next_state == CharClass::InWord {
+            stats.word_count += 1;
+        }
+        if state != CharClass::InNumber && next_state == CharClass::InNumber {
+            stats.number_count += 1;
+        }
+        state = next_state;
+    }
+
+    stats
+}
+
+/// The wasm-bindgen entry point: exposes `count_stats` to JavaScript, returning a plain object
+/// `{ word_count, line_count, number_count }`.
+#[wasm_bindgen(js_name = countStats)]
+pub fn count_stats_js(text: &str) -> JsValue {
+    let stats = count_stats(text);
+    let obj = js_sys::Object::new();
+    js_sys::Reflect::set(&obj, &JsValue::from_str("word_count"), &JsValue::from_f64(stats.word_count as f64)).unwrap();
+    js_sys::Reflect::set(&obj, &JsValue::from_str("line_count"), &JsValue::from_f64(stats.line_count as f64)).unwrap();
+    js_sys::Reflect::set(&obj, &JsValue::from_str("number_count"), &JsValue::from_f64(stats.number_count as f64)).unwrap();
+    obj.into()
+}
+
+fn main() {
+    let stats = count_stats("hello world 42\nsecond line with 7 numbers 9");
+    println!("{stats:?}");
+}
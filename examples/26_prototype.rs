@@ -0,0 +1,168 @@
+// cargo run --example 26_prototype
+
+// Prototype pattern: expensive-to-configure objects are built once, registered by name in a
+// `PrototypeRegistry`, and handed out as fresh, independent clones on demand. The interesting
+// part is object-safe cloning of trait objects - `Clone` itself can't be a supertrait of a
+// dyn-compatible trait (it returns `Self`, not object-safe), so `Prototype` instead exposes
+// `clone_boxed(&self) -> Box<dyn Prototype>`, and `clone_as::<T>()` downcasts the result for
+// callers who know the concrete type they registered.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+
+pub trait Prototype: Any {
+    fn clone_boxed(&self) -> Box<dyn Prototype>;
+    fn as_any(&self) -> &dyn Any;
+}
+
+#[derive(Debug)]
+pub struct WrongPrototypeType {
+    pub name: String,
+}
+
+impl fmt::Display for WrongPrototypeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "prototype '{}' is not the requested type", self.name)
+    }
+}
+
+impl std::error::Error for WrongPrototypeType {}
+
+#[derive(Default)]
+pub struct PrototypeRegistry {
+    prototypes: HashMap<String, Box<dyn Prototype>>,
+}
+
+impl PrototypeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, name: impl Into<String>, prototype: Box<dyn Prototype>) {
+        self.prototypes.insert(name.into(), prototype);
+    }
+
+    /// Clones the prototype registered under `name`, if any, as a trait object.
+    pub fn clone_boxed(&self, name: &str) -> Option<Box<dyn Prototype>> {
+        self.prototypes.get(name).map(|p| p.clone_boxed())
+    }
+
+    /// Clones the prototype registered under `name` and downcasts it to `T`. Returns
+    /// `Err(WrongPrototypeType)` if `name` is registered but under a different concrete type.
+    pub fn clone_as<T: Prototype + Clone + 'static>(&self, name: &str) -> Option<Result<T, WrongPrototypeType>> {
+        self.prototypes.get(name).map(|p| {
+            p.as_any().downcast_ref::<T>().cloned().ok_or_else(|| WrongPrototypeType { name: name.to_string() })
+        })
+    }
+}
+
+// --- A template UserBuilder, pre-configured once and cloned per request -------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserTemplate {
+    pub role: String,
+    pub email_domain: String,
+    pub welcome_email: bool,
+    pub name: Option<String>,
+}
+
+impl Prototype for UserTemplate {
+    fn clone_boxed(&self) -> Box<dyn Prototype> {
+        Box::new(self.clone())
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+// --- A pre-configured set of payment strategies, keyed by name --------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentProfile {
+    pub provider: String,
+    pub surcharge_percent: u32,
+    pub retries: u32,
+}
+
+impl Prototype for PaymentProfile {
+    fn clone_boxed(&self) -> Box<dyn Prototype> {
+        Box::new(self.clone())
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+fn main() {
+    let mut registry = PrototypeRegistry::new();
+    registry.register(
+        "admin-user",
+        Box::new(UserTemplate { role: "admin".to_string(), email_domain: "example.com".to_string(), welcome_email: true, name: None }),
+    );
+    registry.register(
+        "premium-payment",
+        Box::new(PaymentProfile { provider: "stripe".to_string(), surcharge_percent: 0, retries: 3 }),
+    );
+
+    let mut alice: UserTemplate = registry.clone_as("admin-user").unwrap().unwrap();
+    alice.name = Some("alice".to_string());
+    println!("{alice:?}");
+
+    match registry.clone_as::<PaymentProfile>("admin-user") {
+        Some(Err(err)) => println!("expected failure: {err}"),
+        other => println!("unexpected: {other:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_registry() -> PrototypeRegistry {
+        let mut registry = PrototypeRegistry::new();
+        registry.register(
+            "admin-user",
+            Box::new(UserTemplate { role: "admin".to_string(), email_domain: "example.com".to_string(), welcome_email: true, name: None }),
+        );
+        registry.register(
+            "premium-payment",
+            Box::new(PaymentProfile { provider: "stripe".to_string(), surcharge_percent: 0, retries: 3 }),
+        );
+        registry
+    }
+
+    #[test]
+    fn mutating_a_clone_does_not_affect_the_prototype() {
+        let registry = sample_registry();
+
+        let mut clone_one: UserTemplate = registry.clone_as("admin-user").unwrap().unwrap();
+        clone_one.name = Some("bob".to_string());
+
+        let clone_two: UserTemplate = registry.clone_as("admin-user").unwrap().unwrap();
+        assert_eq!(clone_two.name, None);
+        assert_ne!(clone_one, clone_two);
+    }
+
+    #[test]
+    fn clone_as_with_the_wrong_type_returns_a_typed_error() {
+        let registry = sample_registry();
+        let result = registry.clone_as::<PaymentProfile>("admin-user").unwrap();
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().name, "admin-user");
+    }
+
+    #[test]
+    fn clone_as_with_an_unknown_name_returns_none() {
+        let registry = sample_registry();
+        assert!(registry.clone_as::<UserTemplate>("missing").is_none());
+    }
+
+    #[test]
+    fn clone_boxed_preserves_the_concrete_type_through_the_trait_object() {
+        let registry = sample_registry();
+        let boxed = registry.clone_boxed("premium-payment").unwrap();
+        let payment = boxed.as_any().downcast_ref::<PaymentProfile>().unwrap();
+        assert_eq!(payment.provider, "stripe");
+    }
+}
@@ -0,0 +1,194 @@
+// cargo run --example 27_lazy --features async
+
+// Three lazy-initialization patterns side by side, from simplest to most involved:
+//  - `OnceLock`-based sync lazy statics: the standard library's own answer, used below to load
+//    benches/book.txt at most once no matter how many callers ask for it.
+//  - `Lazy<T>`: a per-instance cell with a *fallible* initializer. Unlike `OnceLock::get_or_init`,
+//    `get_or_try_init` leaves the cell empty on error so a later call can retry with fresh input.
+//  - `AsyncLazy<T>`: the async analogue - concurrent first callers all await the *same* in-flight
+//    initialization future instead of racing to run it multiple times. This reimplements the
+//    shape of `tokio::sync::OnceCell` locally rather than depending on it, since the point here
+//    is to show the state machine, not just call a library.
+
+use std::fs;
+use std::future::Future;
+use std::sync::OnceLock;
+use tokio::sync::{Mutex, Notify};
+
+// --- OnceLock-based sync lazy static --------------------------------------------------------
+
+static BOOK_WORD_COUNT: OnceLock<usize> = OnceLock::new();
+
+fn book_word_count() -> usize {
+    *BOOK_WORD_COUNT.get_or_init(|| {
+        fs::read_to_string("benches/book.txt").map(|text| text.split_whitespace().count()).unwrap_or(0)
+    })
+}
+
+// --- Lazy<T>: per-instance cell with a retryable fallible initializer -----------------------
+
+pub struct Lazy<T> {
+    cell: OnceLock<T>,
+}
+
+impl<T> Default for Lazy<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Lazy<T> {
+    pub fn new() -> Self {
+        Self { cell: OnceLock::new() }
+    }
+
+    /// Returns the cached value, initializing it with `init` on first success. If `init` returns
+    /// `Err`, the cell stays empty so the next call gets to try again.
+    pub fn get_or_try_init<E>(&self, init: impl FnOnce() -> Result<T, E>) -> Result<&T, E> {
+        if let Some(value) = self.cell.get() {
+            return Ok(value);
+        }
+        let value = init()?;
+        Ok(self.cell.get_or_init(|| value))
+    }
+}
+
+// --- AsyncLazy<T>: concurrent first callers share one in-flight init future -----------------
+
+enum LazyState<T> {
+    Uninit,
+    Initializing,
+    Init(T),
+}
+
+pub struct AsyncLazy<T> {
+    state: Mutex<LazyState<T>>,
+    notify: Notify,
+}
+
+impl<T: Clone> Default for AsyncLazy<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> AsyncLazy<T> {
+    pub fn new() -> Self {
+        Self { state: Mutex::new(LazyState::Uninit), notify: Notify::new() }
+    }
+
+    /// Returns the cached value. Exactly one caller runs `init`; every other concurrent caller
+    /// waits on a `Notify` instead of racing to initialize too. A waiter that gets cancelled
+    /// while waiting (its task aborted, its future dropped) never touches `state`, so it can't
+    /// leave the cell poisoned for whoever initializes it.
+    pub async fn get_or_init<F, Fut>(&self, init: F) -> T
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let mut init = Some(init);
+        loop {
+            let mut guard = self.state.lock().await;
+            match &*guard {
+                LazyState::Init(value) => return value.clone(),
+                LazyState::Uninit => {
+                    *guard = LazyState::Initializing;
+                    drop(guard);
+                    let value = init.take().expect("init is only taken once, on the Uninit branch")().await;
+                    let mut guard = self.state.lock().await;
+                    *guard = LazyState::Init(value.clone());
+                    drop(guard);
+                    self.notify.notify_waiters();
+                    return value;
+                }
+                LazyState::Initializing => {
+                    drop(guard);
+                    self.notify.notified().await;
+                }
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    println!("book has {} words", book_word_count());
+    println!("book has {} words (cached)", book_word_count());
+
+    let lazy: Lazy<i32> = Lazy::new();
+    let first: Result<&i32, &str> = lazy.get_or_try_init(|| Err("not ready yet"));
+    println!("first attempt: {first:?}");
+    let second = lazy.get_or_try_init(|| Ok::<_, &str>(42));
+    println!("second attempt: {second:?}");
+
+    let async_lazy = AsyncLazy::new();
+    let value = async_lazy.get_or_init(|| async { 7 }).await;
+    println!("async lazy value: {value}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn get_or_try_init_allows_retry_after_an_error() {
+        let lazy: Lazy<i32> = Lazy::new();
+        assert!(lazy.get_or_try_init(|| Err::<i32, &str>("boom")).is_err());
+        assert_eq!(lazy.get_or_try_init(|| Ok::<i32, &str>(5)), Ok(&5));
+        // Once initialized, later calls return the cached value even with a failing initializer.
+        assert_eq!(lazy.get_or_try_init(|| Err::<i32, &str>("too late")), Ok(&5));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn init_runs_exactly_once_under_16_racing_tasks() {
+        let lazy = Arc::new(AsyncLazy::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let mut handles = Vec::new();
+        for _ in 0..16 {
+            let lazy = Arc::clone(&lazy);
+            let calls = Arc::clone(&calls);
+            handles.push(tokio::spawn(async move {
+                lazy.get_or_init(|| async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    99
+                })
+                .await
+            }));
+        }
+
+        for handle in handles {
+            assert_eq!(handle.await.unwrap(), 99);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn cancelling_a_waiter_does_not_poison_the_cell() {
+        let lazy = Arc::new(AsyncLazy::new());
+
+        let initializer = {
+            let lazy = Arc::clone(&lazy);
+            tokio::spawn(async move { lazy.get_or_init(|| async { tokio::time::sleep(Duration::from_millis(50)).await; 123 }).await })
+        };
+
+        // Give the initializer time to claim the `Initializing` state, then start and abort a
+        // waiter mid-wait.
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let waiter = {
+            let lazy = Arc::clone(&lazy);
+            tokio::spawn(async move { lazy.get_or_init(|| async { unreachable!("the initializer above wins the race") }).await })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        waiter.abort();
+
+        assert_eq!(initializer.await.unwrap(), 123);
+        // The cell is still usable after a cancelled waiter - a fresh call sees the cached value.
+        let value = lazy.get_or_init(|| async { unreachable!("already initialized") }).await;
+        assert_eq!(value, 123);
+    }
+}
@@ -0,0 +1,249 @@
+// cargo run --example 28_repository --features serde
+
+// Repository pattern: the command-bus handlers in 09-12_command_bus.rs faked persistence with
+// `format!` strings. Here `CreateUserHandler`/`DeleteUserHandler` instead depend on
+// `Arc<dyn UserRepository>`, so the exact same handler logic runs against an in-memory store in
+// tests and a JSON file on disk in `main()`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use thiserror::Error;
+
+pub type UserId = u32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewUser {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct User {
+    pub id: UserId,
+    pub name: String,
+}
+
+#[derive(Debug, Error)]
+pub enum RepoError {
+    #[error("I/O error: {0}")]
+    Io(String),
+    #[error("corrupt repository file: {0}")]
+    Corrupt(String),
+}
+
+pub trait UserRepository: Send + Sync {
+    fn insert(&self, user: NewUser) -> Result<UserId, RepoError>;
+    fn delete(&self, id: UserId) -> Result<bool, RepoError>;
+    fn get(&self, id: UserId) -> Result<Option<User>, RepoError>;
+}
+
+// --- InMemoryUserRepository: HashMap behind an RwLock, monotonically assigned ids ----------
+
+#[derive(Default)]
+pub struct InMemoryUserRepository {
+    state: RwLock<InMemoryState>,
+}
+
+#[derive(Default)]
+struct InMemoryState {
+    users: HashMap<UserId, User>,
+    next_id: UserId,
+}
+
+impl InMemoryUserRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl UserRepository for InMemoryUserRepository {
+    fn insert(&self, user: NewUser) -> Result<UserId, RepoError> {
+        let mut state = self.state.write().unwrap();
+        state.next_id += 1;
+        let id = state.next_id;
+        state.users.insert(id, User { id, name: user.name });
+        Ok(id)
+    }
+
+    fn delete(&self, id: UserId) -> Result<bool, RepoError> {
+        let mut state = self.state.write().unwrap();
+        Ok(state.users.remove(&id).is_some())
+    }
+
+    fn get(&self, id: UserId) -> Result<Option<User>, RepoError> {
+        let state = self.state.read().unwrap();
+        Ok(state.users.get(&id).cloned())
+    }
+}
+
+// --- JsonFileUserRepository: the whole table lives in one JSON file on disk ----------------
+
+#[derive(Default, Serialize, Deserialize)]
+struct FileState {
+    users: HashMap<UserId, User>,
+    next_id: UserId,
+}
+
+pub struct JsonFileUserRepository {
+    path: PathBuf,
+    // Guards read-modify-write cycles against this file; the file itself is still the source of
+    // truth, re-read on every call rather than cached in memory.
+    lock: Mutex<()>,
+}
+
+impl JsonFileUserRepository {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), lock: Mutex::new(()) }
+    }
+
+    fn load(&self) -> Result<FileState, RepoError> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => serde_json::from_str(&contents).map_err(|e| RepoError::Corrupt(e.to_string())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(FileState::default()),
+            Err(e) => Err(RepoError::Io(e.to_string())),
+        }
+    }
+
+    /// Writes to a sibling temp file, then renames it over the real path - a rename on the same
+    /// filesystem is atomic, so readers never observe a half-written file.
+    fn save(&self, state: &FileState) -> Result<(), RepoError> {
+        let json = serde_json::to_string_pretty(state).map_err(|e| RepoError::Corrupt(e.to_string()))?;
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, json).map_err(|e| RepoError::Io(e.to_string()))?;
+        fs::rename(&tmp_path, &self.path).map_err(|e| RepoError::Io(e.to_string()))?;
+        Ok(())
+    }
+}
+
+impl UserRepository for JsonFileUserRepository {
+    fn insert(&self, user: NewUser) -> Result<UserId, RepoError> {
+        let _guard = self.lock.lock().unwrap();
+        let mut state = self.load()?;
+        state.next_id += 1;
+        let id = state.next_id;
+        state.users.insert(id, User { id, name: user.name });
+        self.save(&state)?;
+        Ok(id)
+    }
+
+    fn delete(&self, id: UserId) -> Result<bool, RepoError> {
+        let _guard = self.lock.lock().unwrap();
+        let mut state = self.load()?;
+        let removed = state.users.remove(&id).is_some();
+        if removed {
+            self.save(&state)?;
+        }
+        Ok(removed)
+    }
+
+    fn get(&self, id: UserId) -> Result<Option<User>, RepoError> {
+        let _guard = self.lock.lock().unwrap();
+        let state = self.load()?;
+        Ok(state.users.get(&id).cloned())
+    }
+}
+
+// --- The command-bus handlers, now backed by a repository instead of format! strings -------
+
+pub trait Command {
+    type Output;
+}
+pub trait Handler<C: Command> {
+    fn handle(&self, cmd: C) -> C::Output;
+}
+
+pub struct CreateUser {
+    pub name: String,
+}
+impl Command for CreateUser {
+    type Output = Result<UserId, RepoError>;
+}
+
+pub struct DeleteUser {
+    pub id: UserId,
+}
+impl Command for DeleteUser {
+    type Output = Result<bool, RepoError>;
+}
+
+pub struct CreateUserHandler {
+    repo: Arc<dyn UserRepository>,
+}
+impl CreateUserHandler {
+    pub fn new(repo: Arc<dyn UserRepository>) -> Self {
+        Self { repo }
+    }
+}
+impl Handler<CreateUser> for CreateUserHandler {
+    fn handle(&self, cmd: CreateUser) -> Result<UserId, RepoError> {
+        self.repo.insert(NewUser { name: cmd.name })
+    }
+}
+
+pub struct DeleteUserHandler {
+    repo: Arc<dyn UserRepository>,
+}
+impl DeleteUserHandler {
+    pub fn new(repo: Arc<dyn UserRepository>) -> Self {
+        Self { repo }
+    }
+}
+impl Handler<DeleteUser> for DeleteUserHandler {
+    fn handle(&self, cmd: DeleteUser) -> Result<bool, RepoError> {
+        self.repo.delete(cmd.id)
+    }
+}
+
+fn main() {
+    let path = std::env::temp_dir().join(format!("design_patterns_repository_demo_{}.json", std::process::id()));
+    let repo: Arc<dyn UserRepository> = Arc::new(JsonFileUserRepository::new(&path));
+
+    let create = CreateUserHandler::new(Arc::clone(&repo));
+    let delete = DeleteUserHandler::new(Arc::clone(&repo));
+
+    let id = create.handle(CreateUser { name: "Alice".into() }).unwrap();
+    println!("created user {id}: {:?}", repo.get(id).unwrap());
+    println!("deleted? {}", delete.handle(DeleteUser { id }).unwrap());
+    println!("still there? {:?}", repo.get(id).unwrap());
+
+    let _ = fs::remove_file(&path);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repository_contract(repo: &dyn UserRepository) {
+        let id = repo.insert(NewUser { name: "alice".into() }).unwrap();
+        assert_eq!(repo.get(id).unwrap().unwrap().name, "alice");
+        assert!(repo.delete(id).unwrap());
+        assert!(repo.get(id).unwrap().is_none());
+        assert!(!repo.delete(id).unwrap());
+    }
+
+    #[test]
+    fn in_memory_repository_satisfies_the_contract() {
+        repository_contract(&InMemoryUserRepository::new());
+    }
+
+    #[test]
+    fn json_file_repository_satisfies_the_contract() {
+        let path = std::env::temp_dir().join(format!("design_patterns_repo_test_{:?}.json", std::thread::current().id()));
+        let repo = JsonFileUserRepository::new(&path);
+        repository_contract(&repo);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn handlers_work_against_the_in_memory_repository() {
+        let repo: Arc<dyn UserRepository> = Arc::new(InMemoryUserRepository::new());
+        let create = CreateUserHandler::new(Arc::clone(&repo));
+        let delete = DeleteUserHandler::new(Arc::clone(&repo));
+
+        let id = create.handle(CreateUser { name: "bob".into() }).unwrap();
+        assert!(delete.handle(DeleteUser { id }).unwrap());
+        assert!(!delete.handle(DeleteUser { id }).unwrap());
+    }
+}
@@ -0,0 +1,236 @@
+// cargo run --example 29_specification
+
+// Specification pattern: `Spec<T>` combinators replace scattered `&&`-chained closures with
+// small, composable, and explainable rules. `And`/`Or`/`Not` are implemented once and work both
+// as static-dispatch generics (concrete types, zero-cost) and as dynamic dispatch
+// (`Box<dyn Spec<T>>`, for rules assembled at runtime), thanks to a blanket `Spec` impl for
+// `Box<dyn Spec<T>>`. Demonstrated on two call sites: payment routing and user validation.
+
+pub trait Spec<T> {
+    fn is_satisfied(&self, candidate: &T) -> bool;
+    /// Reasons this spec rejected `candidate`, one per failing leaf, left to right. Empty if
+    /// `is_satisfied` would return `true`.
+    fn explain(&self, candidate: &T) -> Vec<String>;
+}
+
+impl<T> Spec<T> for Box<dyn Spec<T>> {
+    fn is_satisfied(&self, candidate: &T) -> bool {
+        (**self).is_satisfied(candidate)
+    }
+    fn explain(&self, candidate: &T) -> Vec<String> {
+        (**self).explain(candidate)
+    }
+}
+
+pub struct And<L, R>(pub L, pub R);
+impl<T, L: Spec<T>, R: Spec<T>> Spec<T> for And<L, R> {
+    fn is_satisfied(&self, candidate: &T) -> bool {
+        self.0.is_satisfied(candidate) && self.1.is_satisfied(candidate)
+    }
+    fn explain(&self, candidate: &T) -> Vec<String> {
+        let mut reasons = self.0.explain(candidate);
+        reasons.extend(self.1.explain(candidate));
+        reasons
+    }
+}
+
+pub struct Or<L, R>(pub L, pub R);
+impl<T, L: Spec<T>, R: Spec<T>> Spec<T> for Or<L, R> {
+    fn is_satisfied(&self, candidate: &T) -> bool {
+        self.0.is_satisfied(candidate) || self.1.is_satisfied(candidate)
+    }
+    fn explain(&self, candidate: &T) -> Vec<String> {
+        if self.is_satisfied(candidate) {
+            Vec::new()
+        } else {
+            let mut reasons = self.0.explain(candidate);
+            reasons.extend(self.1.explain(candidate));
+            reasons
+        }
+    }
+}
+
+pub struct Not<S>(pub S);
+impl<T, S: Spec<T>> Spec<T> for Not<S> {
+    fn is_satisfied(&self, candidate: &T) -> bool {
+        !self.0.is_satisfied(candidate)
+    }
+    fn explain(&self, candidate: &T) -> Vec<String> {
+        if self.is_satisfied(candidate) { Vec::new() } else { vec!["negated spec was satisfied".to_string()] }
+    }
+}
+
+pub trait SpecExt<T>: Spec<T> + Sized {
+    fn and<R: Spec<T>>(self, other: R) -> And<Self, R> {
+        And(self, other)
+    }
+    fn or<R: Spec<T>>(self, other: R) -> Or<Self, R> {
+        Or(self, other)
+    }
+    fn negate(self) -> Not<Self> {
+        Not(self)
+    }
+}
+impl<T, S: Spec<T>> SpecExt<T> for S {}
+
+// --- Call site 1: payment routing rules (replaces closure predicates) ----------------------
+
+pub struct PaymentRequest {
+    pub amount: f64,
+    pub currency: &'static str,
+}
+
+pub struct AmountUnder(pub f64);
+impl Spec<PaymentRequest> for AmountUnder {
+    fn is_satisfied(&self, candidate: &PaymentRequest) -> bool {
+        candidate.amount < self.0
+    }
+    fn explain(&self, candidate: &PaymentRequest) -> Vec<String> {
+        if self.is_satisfied(candidate) { Vec::new() } else { vec![format!("amount {} is not under {}", candidate.amount, self.0)] }
+    }
+}
+
+pub struct CurrencyIs(pub &'static str);
+impl Spec<PaymentRequest> for CurrencyIs {
+    fn is_satisfied(&self, candidate: &PaymentRequest) -> bool {
+        candidate.currency == self.0
+    }
+    fn explain(&self, candidate: &PaymentRequest) -> Vec<String> {
+        if self.is_satisfied(candidate) {
+            Vec::new()
+        } else {
+            vec![format!("currency {} is not {}", candidate.currency, self.0)]
+        }
+    }
+}
+
+/// Routes a payment request to "credit_card" for small domestic amounts, "bank_transfer"
+/// otherwise - the same rule that used to live behind a `|req| req.amount < 500.0 && ...` closure.
+pub fn route_payment(request: &PaymentRequest) -> &'static str {
+    let domestic_card_eligible = AmountUnder(500.0).and(CurrencyIs("EUR"));
+    if domestic_card_eligible.is_satisfied(request) { "credit_card" } else { "bank_transfer" }
+}
+
+// --- Call site 2: user validation (age range, email domain allowlist) ----------------------
+
+pub struct User {
+    pub age: u32,
+    pub email: String,
+}
+
+pub struct AgeInRange(pub u32, pub u32);
+impl Spec<User> for AgeInRange {
+    fn is_satisfied(&self, candidate: &User) -> bool {
+        (self.0..=self.1).contains(&candidate.age)
+    }
+    fn explain(&self, candidate: &User) -> Vec<String> {
+        if self.is_satisfied(candidate) {
+            Vec::new()
+        } else {
+            vec![format!("age {} is not between {} and {}", candidate.age, self.0, self.1)]
+        }
+    }
+}
+
+pub struct EmailDomainAllowed(pub &'static [&'static str]);
+impl Spec<User> for EmailDomainAllowed {
+    fn is_satisfied(&self, candidate: &User) -> bool {
+        self.0.iter().any(|domain| candidate.email.ends_with(&format!("@{domain}")))
+    }
+    fn explain(&self, candidate: &User) -> Vec<String> {
+        if self.is_satisfied(candidate) {
+            Vec::new()
+        } else {
+            vec![format!("email '{}' is not in an allowed domain ({:?})", candidate.email, self.0)]
+        }
+    }
+}
+
+pub fn validate_user(user: &User) -> Result<(), Vec<String>> {
+    let spec = AgeInRange(18, 120).and(EmailDomainAllowed(&["example.com", "example.org"]));
+    if spec.is_satisfied(user) { Ok(()) } else { Err(spec.explain(user)) }
+}
+
+fn main() {
+    let small_domestic = PaymentRequest { amount: 100.0, currency: "EUR" };
+    let large_foreign = PaymentRequest { amount: 999.0, currency: "USD" };
+    println!("route(small_domestic) = {}", route_payment(&small_domestic));
+    println!("route(large_foreign) = {}", route_payment(&large_foreign));
+
+    let bad_user = User { age: 10, email: "kid@other.net".to_string() };
+    println!("validate(bad_user) = {:?}", validate_user(&bad_user));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy)]
+    struct Flag(bool);
+    impl Spec<()> for Flag {
+        fn is_satisfied(&self, _candidate: &()) -> bool {
+            self.0
+        }
+        fn explain(&self, _candidate: &()) -> Vec<String> {
+            if self.0 { Vec::new() } else { vec!["flag was false".to_string()] }
+        }
+    }
+
+    #[test]
+    fn de_morgan_not_and_equals_or_of_nots() {
+        for a in [true, false] {
+            for b in [true, false] {
+                let not_and = Not(And(Flag(a), Flag(b))).is_satisfied(&());
+                let or_of_nots = Or(Not(Flag(a)), Not(Flag(b))).is_satisfied(&());
+                assert_eq!(not_and, or_of_nots, "a={a} b={b}");
+            }
+        }
+    }
+
+    #[test]
+    fn and_explain_preserves_left_to_right_order() {
+        let spec = AgeInRange(18, 30).and(EmailDomainAllowed(&["example.com"]));
+        let user = User { age: 5, email: "bob@other.com".to_string() };
+        let reasons = spec.explain(&user);
+        assert_eq!(reasons.len(), 2);
+        assert!(reasons[0].contains("age"), "expected age reason first, got {reasons:?}");
+        assert!(reasons[1].contains("email"), "expected email reason second, got {reasons:?}");
+    }
+
+    #[test]
+    fn a_single_spec_instance_is_reused_against_several_candidates() {
+        let adult = AgeInRange(18, 120);
+        let young = User { age: 10, email: "x@example.com".to_string() };
+        let old_enough = User { age: 40, email: "x@example.com".to_string() };
+        assert!(!adult.is_satisfied(&young));
+        assert!(adult.is_satisfied(&old_enough));
+    }
+
+    #[test]
+    fn route_payment_picks_credit_card_only_for_small_domestic_amounts() {
+        assert_eq!(route_payment(&PaymentRequest { amount: 100.0, currency: "EUR" }), "credit_card");
+        assert_eq!(route_payment(&PaymentRequest { amount: 999.0, currency: "EUR" }), "bank_transfer");
+        assert_eq!(route_payment(&PaymentRequest { amount: 100.0, currency: "USD" }), "bank_transfer");
+    }
+
+    #[test]
+    fn validate_user_rejects_out_of_range_age_and_disallowed_domain() {
+        let bad = User { age: 10, email: "x@bad.com".to_string() };
+        let err = validate_user(&bad).unwrap_err();
+        assert_eq!(err.len(), 2);
+    }
+
+    #[test]
+    fn validate_user_accepts_a_well_formed_user() {
+        let good = User { age: 30, email: "x@example.org".to_string() };
+        assert!(validate_user(&good).is_ok());
+    }
+
+    #[test]
+    fn boxed_dynamic_spec_agrees_with_the_static_version() {
+        let boxed: Box<dyn Spec<User>> = Box::new(AgeInRange(18, 120).and(EmailDomainAllowed(&["example.com"])));
+        let user = User { age: 30, email: "x@example.com".to_string() };
+        assert!(boxed.is_satisfied(&user));
+        assert!(boxed.explain(&user).is_empty());
+    }
+}
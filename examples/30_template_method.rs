@@ -0,0 +1,167 @@
+// cargo run --example 30_template_method
+
+// Every run-to-completion driver in this crate (04-07_state_machine_*) hand-rolls its own loop
+// shape. This module pulls the skeleton out twice, in two different idioms, so they can be
+// compared directly: a trait-based Template Method (`PipelineSteps` with default hook
+// implementations) and Rust-flavored function injection (`RunConfig` holding boxed closures).
+// Both drive the same Validated -> Enriched -> Persisted pipeline used elsewhere in this crate.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub raw: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Persisted {
+    pub raw: String,
+    pub metadata: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PipelineError {
+    ValidationFailed(String),
+}
+
+// --- Template Method: the algorithm skeleton lives in `run_pipeline`, hooks are overridable --
+
+pub trait PipelineSteps {
+    fn validate(&mut self, record: &Record) -> Result<(), PipelineError> {
+        if record.raw.is_empty() { Err(PipelineError::ValidationFailed("record is empty".to_string())) } else { Ok(()) }
+    }
+    fn enrich(&mut self, record: &Record) -> String {
+        format!("len={}", record.raw.len())
+    }
+    fn persist(&mut self, record: &Record, metadata: &str) -> Persisted {
+        Persisted { raw: record.raw.clone(), metadata: metadata.to_string() }
+    }
+}
+
+pub fn run_pipeline<S: PipelineSteps>(steps: &mut S, input: Record) -> Result<Persisted, PipelineError> {
+    steps.validate(&input)?;
+    let metadata = steps.enrich(&input);
+    Ok(steps.persist(&input, &metadata))
+}
+
+pub struct DefaultPipeline;
+impl PipelineSteps for DefaultPipeline {}
+
+// --- Closure injection: same skeleton, hooks are values instead of trait methods -----------
+
+type ValidateFn = Box<dyn Fn(&Record) -> Result<(), PipelineError>>;
+type EnrichFn = Box<dyn Fn(&Record) -> String>;
+type PersistFn = Box<dyn Fn(&Record, &str) -> Persisted>;
+
+pub struct RunConfig {
+    pub validate: ValidateFn,
+    pub enrich: EnrichFn,
+    pub persist: PersistFn,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        Self {
+            validate: Box::new(|record| {
+                if record.raw.is_empty() { Err(PipelineError::ValidationFailed("record is empty".to_string())) } else { Ok(()) }
+            }),
+            enrich: Box::new(|record| format!("len={}", record.raw.len())),
+            persist: Box::new(|record, metadata| Persisted { raw: record.raw.clone(), metadata: metadata.to_string() }),
+        }
+    }
+}
+
+pub fn run_pipeline_with_config(config: &RunConfig, input: Record) -> Result<Persisted, PipelineError> {
+    (config.validate)(&input)?;
+    let metadata = (config.enrich)(&input);
+    Ok((config.persist)(&input, &metadata))
+}
+
+fn main() {
+    let mut pipeline = DefaultPipeline;
+    println!("{:?}", run_pipeline(&mut pipeline, Record { raw: "hello".to_string() }));
+
+    let config = RunConfig::default();
+    println!("{:?}", run_pipeline_with_config(&config, Record { raw: "hello".to_string() }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn template_method_and_closure_injection_agree_on_the_happy_path() {
+        let mut pipeline = DefaultPipeline;
+        let via_trait = run_pipeline(&mut pipeline, Record { raw: "hello".to_string() });
+
+        let config = RunConfig::default();
+        let via_closures = run_pipeline_with_config(&config, Record { raw: "hello".to_string() });
+
+        assert_eq!(via_trait, via_closures);
+    }
+
+    #[test]
+    fn both_styles_early_exit_on_validation_failure_without_running_later_hooks() {
+        struct TracingPipeline(Rc<RefCell<Vec<&'static str>>>);
+        impl PipelineSteps for TracingPipeline {
+            fn validate(&mut self, record: &Record) -> Result<(), PipelineError> {
+                self.0.borrow_mut().push("validate");
+                if record.raw.is_empty() { Err(PipelineError::ValidationFailed("empty".to_string())) } else { Ok(()) }
+            }
+            fn enrich(&mut self, record: &Record) -> String {
+                self.0.borrow_mut().push("enrich");
+                format!("len={}", record.raw.len())
+            }
+            fn persist(&mut self, record: &Record, metadata: &str) -> Persisted {
+                self.0.borrow_mut().push("persist");
+                Persisted { raw: record.raw.clone(), metadata: metadata.to_string() }
+            }
+        }
+
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut pipeline = TracingPipeline(Rc::clone(&calls));
+        let result = run_pipeline(&mut pipeline, Record { raw: String::new() });
+        assert!(result.is_err());
+        assert_eq!(*calls.borrow(), vec!["validate"]);
+
+        calls.borrow_mut().clear();
+        let (trace_validate, trace_enrich, trace_persist) = (Rc::clone(&calls), Rc::clone(&calls), Rc::clone(&calls));
+        let config = RunConfig {
+            validate: Box::new(move |record| {
+                trace_validate.borrow_mut().push("validate");
+                if record.raw.is_empty() { Err(PipelineError::ValidationFailed("empty".to_string())) } else { Ok(()) }
+            }),
+            enrich: Box::new(move |record| {
+                trace_enrich.borrow_mut().push("enrich");
+                format!("len={}", record.raw.len())
+            }),
+            persist: Box::new(move |record, metadata| {
+                trace_persist.borrow_mut().push("persist");
+                Persisted { raw: record.raw.clone(), metadata: metadata.to_string() }
+            }),
+        };
+        let result = run_pipeline_with_config(&config, Record { raw: String::new() });
+        assert!(result.is_err());
+        assert_eq!(*calls.borrow(), vec!["validate"]);
+    }
+
+    #[test]
+    fn overriding_a_single_hook_in_the_trait_style_changes_only_that_step() {
+        struct UppercaseEnrich;
+        impl PipelineSteps for UppercaseEnrich {
+            fn enrich(&mut self, record: &Record) -> String {
+                record.raw.to_uppercase()
+            }
+        }
+        let mut pipeline = UppercaseEnrich;
+        let result = run_pipeline(&mut pipeline, Record { raw: "hi".to_string() }).unwrap();
+        assert_eq!(result.metadata, "HI");
+    }
+
+    #[test]
+    fn overriding_a_single_hook_in_the_closure_style_changes_only_that_step() {
+        let config = RunConfig { enrich: Box::new(|record| record.raw.to_uppercase()), ..RunConfig::default() };
+        let result = run_pipeline_with_config(&config, Record { raw: "hi".to_string() }).unwrap();
+        assert_eq!(result.metadata, "HI");
+    }
+}
@@ -0,0 +1,6 @@
+//! Library half of this crate. Most patterns here live purely as runnable examples under
+//! `examples/`, each self-contained with its own copy of the logic it demonstrates (see
+//! `src/main.rs`'s doc comment) - `observer` is the first one promoted here, so it can be
+//! unit-tested and reused by more than one example instead of being copied.
+
+pub mod observer;
@@ -0,0 +1,285 @@
+// cargo run -- list
+// cargo run -- run comment-fsm --file benches/dummy.c --format json
+// cargo run -- run text-stats --file benches/book.txt
+// cargo run -- run command-bus
+
+// A single front-end over a few of the demos in examples/, so you don't have to remember which
+// example binary implements which pattern. Each demo below is a small, self-contained copy of the
+// logic in its corresponding example or bench (matching how those files already duplicate
+// implementations instead of sharing a lib crate), wired up behind `list` / `run <name>`.
+
+use std::env;
+use std::fs;
+use std::process::ExitCode;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+enum CliError {
+    #[error("missing required argument: {0}")]
+    MissingArg(&'static str),
+    #[error("unknown command '{0}' (expected 'list' or 'run')")]
+    UnknownCommand(String),
+    #[error("unknown demo '{name}' - did you mean '{suggestion}'?")]
+    UnknownDemo { name: String, suggestion: String },
+    #[error("could not read '{path}': {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("unknown output format '{0}' (expected 'text' or 'json')")]
+    UnknownFormat(String),
+}
+
+struct Demo {
+    name: &'static str,
+    description: &'static str,
+    run: fn(&[String]) -> Result<String, CliError>,
+}
+
+const DEMOS: &[Demo] = &[
+    Demo { name: "comment-fsm", description: "count bytes inside /* ... */ block comments (06_state_machine_enums_comments)", run: run_comment_fsm },
+    Demo { name: "text-stats", description: "count words, lines, and numbers in a text file (01_enums_fsm)", run: run_text_stats },
+    Demo { name: "command-bus", description: "dispatch a couple of commands through a tiny command bus (09-12_command_bus)", run: run_command_bus },
+];
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match dispatch(&args) {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn dispatch(args: &[String]) -> Result<String, CliError> {
+    match args.first().map(String::as_str) {
+        Some("list") => Ok(list_demos()),
+        Some("run") => {
+            let name = args.get(1).ok_or(CliError::MissingArg("demo name"))?;
+            match find_demo(name) {
+                Some(demo) => (demo.run)(&args[2..]),
+                None => Err(CliError::UnknownDemo { name: name.clone(), suggestion: suggest(name).to_string() }),
+            }
+        }
+        Some(other) => Err(CliError::UnknownCommand(other.to_string())),
+        None => Err(CliError::MissingArg("command ('list' or 'run')")),
+    }
+}
+
+fn list_demos() -> String {
+    DEMOS.iter().map(|d| format!("{:<12} {}", d.name, d.description)).collect::<Vec<_>>().join("\n")
+}
+
+fn find_demo(name: &str) -> Option<&'static Demo> {
+    DEMOS.iter().find(|d| d.name == name)
+}
+
+/// Returns the name of the demo closest to `name` by edit distance - used to suggest a fix for a
+/// typo'd demo name rather than just failing.
+fn suggest(name: &str) -> &'static str {
+    DEMOS.iter().min_by_key(|d| edit_distance(name, d.name)).map(|d| d.name).unwrap_or("list")
+}
+
+/// Classic Levenshtein distance (insert/delete/substitute, all cost 1).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut dp = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in dp.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in dp[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            dp[i][j] = (dp[i - 1][j] + 1).min(dp[i][j - 1] + 1).min(dp[i - 1][j - 1] + cost);
+        }
+    }
+    dp[a.len()][b.len()]
+}
+
+fn parse_flag<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter().position(|a| a == flag).and_then(|i| args.get(i + 1)).map(String::as_str)
+}
+
+fn read_file(path: &str) -> Result<Vec<u8>, CliError> {
+    fs::read(path).map_err(|source| CliError::Io { path: path.to_string(), source })
+}
+
+// --- comment-fsm: byte-level scanner copied from 06_state_machine_enums_comments.rs --------
+
+fn run_comment_fsm(args: &[String]) -> Result<String, CliError> {
+    let path = parse_flag(args, "--file").ok_or(CliError::MissingArg("--file"))?;
+    let format = parse_flag(args, "--format").unwrap_or("text");
+    let data = read_file(path)?;
+
+    #[derive(Clone, Copy)]
+    enum State {
+        Code,
+        Slash,
+        Block,
+        BlockStar,
+    }
+    let mut state = State::Code;
+    let mut comment_bytes = 0u64;
+    for &b in &data {
+        state = match (state, b) {
+            (State::Code, b'/') => State::Slash,
+            (State::Code, _) => State::Code,
+            (State::Slash, b'*') => State::Block,
+            (State::Slash, _) => State::Code,
+            (State::Block, b'*') => State::BlockStar,
+            (State::Block, _) => {
+                comment_bytes += 1;
+                State::Block
+            }
+            (State::BlockStar, b'/') => State::Code,
+            (State::BlockStar, b'*') => {
+                comment_bytes += 1;
+                State::BlockStar
+            }
+            (State::BlockStar, _) => {
+                comment_bytes += 2;
+                State::Block
+            }
+        };
+    }
+
+    render(format, &[("comment_bytes", comment_bytes)])
+}
+
+// --- text-stats: char-level scanner copied from benches/01_enums_fsm.rs --------------------
+
+fn run_text_stats(args: &[String]) -> Result<String, CliError> {
+    let path = parse_flag(args, "--file").ok_or(CliError::MissingArg("--file"))?;
+    let format = parse_flag(args, "--format").unwrap_or("text");
+    let data = read_file(path)?;
+    let text = String::from_utf8_lossy(&data);
+
+    #[derive(Clone, Copy)]
+    enum State {
+        Whitespace,
+        InWord,
+        InNumber,
+    }
+    let mut state = State::Whitespace;
+    let mut word_count = 0u64;
+    let mut line_count = 0u64;
+    let mut number_count = 0u64;
+    for c in text.chars() {
+        match state {
+            State::Whitespace => {
+                if c.is_alphabetic() {
+                    state = State::InWord;
+                    word_count += 1;
+                } else if c.is_numeric() {
+                    state = State::InNumber;
+                    number_count += 1;
+                } else if c == '\n' {
+                    line_count += 1;
+                }
+            }
+            State::InWord => {
+                if !c.is_alphabetic() {
+                    state = State::Whitespace;
+                    if c == '\n' {
+                        line_count += 1;
+                    }
+                }
+            }
+            State::InNumber => {
+                if !c.is_numeric() {
+                    state = State::Whitespace;
+                    if c == '\n' {
+                        line_count += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    render(format, &[("word_count", word_count), ("line_count", line_count), ("number_count", number_count)])
+}
+
+// --- command-bus: a couple of commands dispatched through a match-based bus (09-12) --------
+
+fn run_command_bus(_args: &[String]) -> Result<String, CliError> {
+    enum Command {
+        CreateUser(String),
+        DeleteUser(String),
+    }
+
+    fn handle(cmd: Command) -> String {
+        match cmd {
+            Command::CreateUser(name) => format!("created user '{name}'"),
+            Command::DeleteUser(name) => format!("deleted user '{name}'"),
+        }
+    }
+
+    let commands = vec![Command::CreateUser("alice".to_string()), Command::DeleteUser("alice".to_string())];
+    Ok(commands.into_iter().map(handle).collect::<Vec<_>>().join("\n"))
+}
+
+// --- output rendering shared by every demo --------------------------------------------------
+
+fn render(format: &str, fields: &[(&str, u64)]) -> Result<String, CliError> {
+    match format {
+        "text" => Ok(fields.iter().map(|(k, v)| format!("{k}: {v}")).collect::<Vec<_>>().join("\n")),
+        "json" => {
+            let body = fields.iter().map(|(k, v)| format!("\"{k}\":{v}")).collect::<Vec<_>>().join(",");
+            Ok(format!("{{{body}}}"))
+        }
+        other => Err(CliError::UnknownFormat(other.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_includes_every_demo_name() {
+        let output = dispatch(&["list".to_string()]).unwrap();
+        for demo in DEMOS {
+            assert!(output.contains(demo.name));
+        }
+    }
+
+    #[test]
+    fn run_command_bus_succeeds_without_a_file() {
+        let output = dispatch(&["run".to_string(), "command-bus".to_string()]).unwrap();
+        assert!(output.contains("created user 'alice'"));
+        assert!(output.contains("deleted user 'alice'"));
+    }
+
+    #[test]
+    fn run_text_stats_reports_json_when_asked() {
+        let args = ["run", "text-stats", "--file", "Cargo.toml", "--format", "json"].map(String::from);
+        let output = dispatch(&args).unwrap();
+        assert!(output.starts_with('{'));
+        assert!(output.contains("word_count"));
+    }
+
+    #[test]
+    fn unknown_demo_name_suggests_the_closest_match() {
+        let err = dispatch(&["run".to_string(), "command-buz".to_string()]).unwrap_err();
+        match err {
+            CliError::UnknownDemo { suggestion, .. } => assert_eq!(suggestion, "command-bus"),
+            other => panic!("expected UnknownDemo, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn missing_file_argument_is_reported() {
+        let err = dispatch(&["run".to_string(), "text-stats".to_string()]).unwrap_err();
+        assert!(matches!(err, CliError::MissingArg("--file")));
+    }
+}
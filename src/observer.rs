@@ -0,0 +1,866 @@
+//! The observer pattern from `examples/03_observer.rs`, promoted to a library module so it can be
+//! unit-tested and reused outside that one file. `examples/03_observer.rs` now just consumes these
+//! types; the `SyncTopic`, `TryTopic`, `QueueingTopic` and `EventBus` siblings stay in the example,
+//! since they are distinct type families rather than part of `Topic` itself.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::{Rc, Weak};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// A plain, `&str`-typed subscriber callback - see the module docs for why `Topic` doesn't
+/// generalize over `T`.
+pub type Subscriber = Rc<RefCell<dyn FnMut(&str)>>;
+
+/// A subscriber held only weakly, so a long-lived `Topic` doesn't keep it (or whatever it
+/// captured) alive past the point the owner dropped its strong `Subscriber` handle.
+pub type WeakSubscriber = Weak<RefCell<dyn FnMut(&str)>>;
+
+/// A subscriber that wants the whole batch from one `Topic::publish_all` call at once, rather
+/// than being re-borrowed and re-invoked once per message.
+pub type BatchSubscriber = Rc<RefCell<dyn FnMut(&[String])>>;
+
+/// Receives a report after every `Topic::deliver` call, with how many subscribers were actually
+/// invoked and how long delivery took. The default no-op impl means installing no collector (the
+/// common case - `Topic::new` never installs one) costs nothing beyond the `Option` check.
+pub trait TopicMetrics {
+    fn on_publish(&self, subscribers: usize, elapsed: Duration) {
+        let _ = (subscribers, elapsed);
+    }
+}
+
+/// A `TopicMetrics` that tallies totals behind `Cell`s instead of timing assertions, which would be
+/// flaky in a test - the counters it exposes are what tests and the example's closing printout read
+/// back.
+///
+/// ```
+/// use std::rc::Rc;
+/// use design_patterns::observer::{CountingMetrics, Topic};
+///
+/// let metrics = Rc::new(CountingMetrics::new());
+/// let topic = Topic::with_metrics(metrics.clone());
+/// topic.subscribe(Rc::new(std::cell::RefCell::new(|_: &str| {})));
+/// topic.publish("hello");
+///
+/// assert_eq!(metrics.publishes(), 1);
+/// assert_eq!(metrics.subscribers_invoked(), 1);
+/// ```
+pub struct CountingMetrics {
+    publishes: Cell<u64>,
+    subscribers_invoked: Cell<u64>,
+    total_elapsed: Cell<Duration>,
+}
+impl CountingMetrics {
+    pub fn new() -> Self {
+        CountingMetrics { publishes: Cell::new(0), subscribers_invoked: Cell::new(0), total_elapsed: Cell::new(Duration::ZERO) }
+    }
+
+    pub fn publishes(&self) -> u64 {
+        self.publishes.get()
+    }
+
+    pub fn subscribers_invoked(&self) -> u64 {
+        self.subscribers_invoked.get()
+    }
+
+    pub fn total_elapsed(&self) -> Duration {
+        self.total_elapsed.get()
+    }
+}
+impl Default for CountingMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl TopicMetrics for CountingMetrics {
+    fn on_publish(&self, subscribers: usize, elapsed: Duration) {
+        self.publishes.set(self.publishes.get() + 1);
+        self.subscribers_invoked.set(self.subscribers_invoked.get() + subscribers as u64);
+        self.total_elapsed.set(self.total_elapsed.get() + elapsed);
+    }
+}
+
+/// Identifies a subscription returned by `Topic::subscribe`, so it can later be handed to
+/// `Topic::unsubscribe` - a plain index into `subs` wouldn't survive an earlier subscriber being
+/// removed, since every later index would shift.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(pub u64);
+
+/// A point-in-time view of a `Topic` returned by `Topic::debug_snapshot`: how many subscribers it
+/// has, a label per subscriber, how many messages it has ever delivered, and the last one (reusing
+/// the replay buffer's tail if replay is enabled, since that is already the most recent message).
+/// Implements `Serialize` (behind the `serde` feature) so a debugging tool can dump it as JSON
+/// instead of just `{:?}`.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TopicSnapshot {
+    pub subscriber_count: usize,
+    pub subscriber_labels: Vec<String>,
+    pub total_published: u64,
+    pub last_message: Option<String>,
+}
+
+/// What `Topic::publish_isolated` did: how many subscribers it successfully delivered to, and
+/// which ones panicked and were evicted as a result, in the order they panicked.
+#[derive(Debug, PartialEq)]
+pub struct PublishIsolatedReport {
+    pub delivered: usize,
+    pub evicted: Vec<SubscriptionId>,
+}
+
+/// `subs` and `replay_buffer` live behind their own `RefCell` so `publish` can take `&self`
+/// instead of `&mut self` - callers no longer need exclusive access to the topic just to publish,
+/// and `Topic` no longer needs wrapping in an outer `Rc<RefCell<Topic>>` to be shared.
+///
+/// ```
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+/// use design_patterns::observer::Topic;
+///
+/// let topic = Topic::new();
+/// let received = Rc::new(RefCell::new(Vec::new()));
+/// let log = received.clone();
+/// topic.subscribe(Rc::new(RefCell::new(move |msg: &str| log.borrow_mut().push(msg.to_string()))));
+///
+/// topic.publish("hello");
+/// assert_eq!(*received.borrow(), vec!["hello".to_string()]);
+/// ```
+pub struct Topic {
+    subs: RefCell<Vec<(SubscriptionId, Subscriber, bool, i32)>>,
+    weak_subs: RefCell<Vec<(SubscriptionId, WeakSubscriber)>>,
+    batch_subs: RefCell<Vec<(SubscriptionId, BatchSubscriber)>>,
+    next_id: Cell<u64>,
+    replay_capacity: usize,
+    replay_buffer: RefCell<VecDeque<String>>,
+    publishing: Cell<bool>,
+    pending: RefCell<VecDeque<String>>,
+    metrics: Option<Rc<dyn TopicMetrics>>,
+    labels: RefCell<HashMap<SubscriptionId, String>>,
+    total_published: Cell<u64>,
+    last_message: RefCell<Option<String>>,
+}
+impl Topic {
+    pub fn new() -> Self {
+        Topic {
+            subs: RefCell::new(vec![]),
+            weak_subs: RefCell::new(vec![]),
+            batch_subs: RefCell::new(vec![]),
+            next_id: Cell::new(0),
+            replay_capacity: 0,
+            replay_buffer: RefCell::new(VecDeque::new()),
+            publishing: Cell::new(false),
+            pending: RefCell::new(VecDeque::new()),
+            metrics: None,
+            labels: RefCell::new(HashMap::new()),
+            total_published: Cell::new(0),
+            last_message: RefCell::new(None),
+        }
+    }
+
+    /// Like `new`, but keeps the last `capacity` published messages around and replays them, in
+    /// order, to every new subscriber as soon as it joins - the classic "late joiner" fix for
+    /// event buses, where `new`'s subscribers only ever see messages published after they joined.
+    pub fn with_replay(capacity: usize) -> Self {
+        Topic {
+            subs: RefCell::new(vec![]),
+            weak_subs: RefCell::new(vec![]),
+            batch_subs: RefCell::new(vec![]),
+            next_id: Cell::new(0),
+            replay_capacity: capacity,
+            replay_buffer: RefCell::new(VecDeque::with_capacity(capacity)),
+            publishing: Cell::new(false),
+            pending: RefCell::new(VecDeque::new()),
+            metrics: None,
+            labels: RefCell::new(HashMap::new()),
+            total_published: Cell::new(0),
+            last_message: RefCell::new(None),
+        }
+    }
+
+    /// Like `new`, but installs `metrics` so every `deliver` reports how many subscribers it
+    /// invoked and how long that took - see `TopicMetrics`.
+    pub fn with_metrics(metrics: Rc<dyn TopicMetrics>) -> Self {
+        Topic {
+            subs: RefCell::new(vec![]),
+            weak_subs: RefCell::new(vec![]),
+            batch_subs: RefCell::new(vec![]),
+            next_id: Cell::new(0),
+            replay_capacity: 0,
+            replay_buffer: RefCell::new(VecDeque::new()),
+            publishing: Cell::new(false),
+            pending: RefCell::new(VecDeque::new()),
+            metrics: Some(metrics),
+            labels: RefCell::new(HashMap::new()),
+            total_published: Cell::new(0),
+            last_message: RefCell::new(None),
+        }
+    }
+
+    pub fn subscribe(&self, callback: Subscriber) -> SubscriptionId {
+        self.subscribe_with_priority(0, callback)
+    }
+
+    /// Subscribes `callback` so it is automatically removed after its first invocation - the
+    /// `once` family of JS event emitters. Bypasses replay: firing it once for every already
+    /// buffered message the moment it joins would make "once" mean something different than it
+    /// does for a live publish, so a once-subscriber only ever sees messages published from here on.
+    pub fn subscribe_once(&self, callback: Subscriber) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        self.subs.borrow_mut().push((id, callback, true, 0));
+        id
+    }
+
+    /// Subscribes `callback` to run at `priority`: lower numbers run first, and subscribers
+    /// sharing a priority run in the order they subscribed (a stable sort at publish time, not a
+    /// sorted insert). Useful when one subscriber enriches state that later subscribers read -
+    /// e.g. an audit log that must see a message before anything else touches it. Plain
+    /// `subscribe` is priority `0`.
+    pub fn subscribe_with_priority(&self, priority: i32, callback: Subscriber) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        for msg in self.replay_buffer.borrow().iter() {
+            callback.borrow_mut()(msg);
+        }
+        self.subs.borrow_mut().push((id, callback, false, priority));
+        id
+    }
+
+    /// Subscribes `callback` wrapped so it only runs when `pred` returns `true` for the published
+    /// message - the predicate is evaluated on every `publish`, and a non-matching message is
+    /// dropped before `callback` ever sees it.
+    pub fn subscribe_filtered(&self, pred: impl Fn(&str) -> bool + 'static, callback: Subscriber) -> SubscriptionId {
+        let filtered: Subscriber = Rc::new(RefCell::new(move |msg: &str| {
+            if pred(msg) {
+                callback.borrow_mut()(msg);
+            }
+        }));
+        self.subscribe(filtered)
+    }
+
+    /// Subscribes `callback` and ties its lifetime to the returned `SubscriptionGuard`: once the
+    /// guard is dropped, the callback is removed even though nothing holds `&mut Topic` at that
+    /// point. `topic` must be the same `Rc<Topic>` the guard's drop will later try to reach back
+    /// into, held only as a `Weak` so the guard can't keep the topic alive by itself.
+    pub fn subscribe_scoped(topic: &Rc<Topic>, callback: Subscriber) -> SubscriptionGuard {
+        let id = topic.subscribe(callback);
+        SubscriptionGuard { topic: Rc::downgrade(topic), id }
+    }
+
+    /// Removes the subscriber registered under `id`, preserving the relative order of the rest.
+    /// Returns `true` if a subscriber was actually removed, `false` if `id` wasn't (or is no
+    /// longer) subscribed.
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut subs = self.subs.borrow_mut();
+        let len_before = subs.len();
+        subs.retain(|(sub_id, _, _, _)| *sub_id != id);
+        subs.len() != len_before
+    }
+
+    /// Subscribes `callback` without keeping it alive: `Topic` stores only a `Weak` reference, so
+    /// once every strong handle to `callback` is dropped elsewhere, the next `publish` finds it
+    /// gone, silently skips it, and prunes the dead entry - unlike `subscribe`, there is no need
+    /// to `unsubscribe` (or drop a `SubscriptionGuard`) to stop a weak subscriber from leaking.
+    pub fn subscribe_weak(&self, callback: &Subscriber) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        self.weak_subs.borrow_mut().push((id, Rc::downgrade(callback)));
+        id
+    }
+
+    /// Subscribes `callback` to receive whole batches instead of individual messages: it is only
+    /// invoked by `publish_all`, once per call, with every message from that call in order -
+    /// never by plain `publish`, which has no batch to hand it.
+    pub fn subscribe_batch(&self, callback: BatchSubscriber) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        self.batch_subs.borrow_mut().push((id, callback));
+        id
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.subs.borrow().len() + self.weak_subs.borrow().len()
+    }
+
+    /// Like `subscribe`, but also records `name` as this subscriber's label for
+    /// `debug_snapshot` - a subscriber that went through plain `subscribe` instead gets a
+    /// generated "sub-N" label, based on its position in `subs` at snapshot time.
+    pub fn subscribe_named(&self, name: &str, callback: Subscriber) -> SubscriptionId {
+        let id = self.subscribe(callback);
+        self.labels.borrow_mut().insert(id, name.to_string());
+        id
+    }
+
+    /// A point-in-time view of this `Topic` for debugging/observability tooling - see
+    /// `TopicSnapshot`.
+    pub fn debug_snapshot(&self) -> TopicSnapshot {
+        let labels = self.labels.borrow();
+        let subscriber_labels: Vec<String> = self
+            .subs
+            .borrow()
+            .iter()
+            .enumerate()
+            .map(|(index, (id, _, _, _))| labels.get(id).cloned().unwrap_or_else(|| format!("sub-{index}")))
+            .collect();
+        let last_message = if self.replay_capacity > 0 {
+            self.replay_buffer.borrow().back().cloned()
+        } else {
+            self.last_message.borrow().clone()
+        };
+        TopicSnapshot {
+            subscriber_count: self.subscriber_count(),
+            subscriber_labels,
+            total_published: self.total_published.get(),
+            last_message,
+        }
+    }
+
+    /// Delivers every message in `msgs` in order through the usual `publish`, then hands the
+    /// whole batch to every `subscribe_batch` subscriber in one call - each of those is invoked
+    /// exactly once per `publish_all`, not once per message, which is the point of subscribing to
+    /// a batch instead of to the topic directly. Returns the total number of per-message
+    /// subscriber invocations (plain plus weak, summed across the batch) plus one per batch
+    /// subscriber invoked.
+    pub fn publish_all<'a>(&self, msgs: impl IntoIterator<Item = &'a str>) -> usize {
+        let mut invocations = 0;
+        let mut batch = Vec::new();
+        for msg in msgs {
+            invocations += self.subscriber_count();
+            self.publish(msg);
+            batch.push(msg.to_string());
+        }
+        if !batch.is_empty() {
+            let batch_snapshot: Vec<(SubscriptionId, BatchSubscriber)> = self.batch_subs.borrow().clone();
+            for (_, sub) in &batch_snapshot {
+                sub.borrow_mut()(&batch);
+            }
+            invocations += batch_snapshot.len();
+        }
+        invocations
+    }
+
+    /// Like `publish`, but a panicking subscriber no longer unwinds through the rest of the
+    /// delivery loop: each invocation is wrapped in `catch_unwind`, a panicker is evicted from
+    /// `subs` on the spot so it can never run (and panic) again, and the panic itself never leaves
+    /// this function - the caller only learns about it through `evicted`. Only isolates plain
+    /// subscribers from each other; weak and batch subscribers, replay, and the reentrancy queue
+    /// `publish` uses are untouched, so this is opt-in rather than `publish`'s new default.
+    pub fn publish_isolated(&self, msg: &str) -> PublishIsolatedReport {
+        let mut snapshot: Vec<(SubscriptionId, Subscriber, bool, i32)> = self.subs.borrow().clone();
+        snapshot.sort_by_key(|(_, _, _, priority)| *priority);
+        let mut delivered = 0;
+        let mut evicted = Vec::new();
+        for (id, sub, _, _) in &snapshot {
+            match panic::catch_unwind(AssertUnwindSafe(|| sub.borrow_mut()(msg))) {
+                Ok(()) => delivered += 1,
+                Err(_) => evicted.push(*id),
+            }
+        }
+        if !evicted.is_empty() {
+            self.subs.borrow_mut().retain(|(id, _, _, _)| !evicted.contains(id));
+        }
+        PublishIsolatedReport { delivered, evicted }
+    }
+
+    /// A subscriber that calls `publish` again while already inside one doesn't recurse into
+    /// `deliver` - it queues the nested message in `pending` and returns immediately. The
+    /// outermost `publish` call drains that queue after its own delivery completes, one message
+    /// at a time, so messages raised while handling "original" are all delivered breadth-first
+    /// (every subscriber sees "original" before any of them sees a message raised in reaction to
+    /// it) instead of depth-first, and delivery order no longer depends on which subscriber
+    /// happened to react first.
+    pub fn publish(&self, msg: &str) {
+        if self.publishing.get() {
+            self.pending.borrow_mut().push_back(msg.to_string());
+            return;
+        }
+        self.publishing.set(true);
+        self.deliver(msg);
+        while let Some(next) = {
+            let mut pending = self.pending.borrow_mut();
+            pending.pop_front()
+        } {
+            self.deliver(&next);
+        }
+        self.publishing.set(false);
+    }
+
+    /// Clones the subscriber `Rc`s out of `subs` before invoking anything, then drops the `subs`
+    /// borrow - the same discipline `SyncTopic::publish` uses for its `Mutex`. A callback that
+    /// subscribes or unsubscribes only ever contends on a short-lived borrow taken *after* this
+    /// one has already ended, so none of that can panic with "already borrowed", and `msg` itself
+    /// is only ever passed as `&str` - no per-subscriber clone of the payload, unlike the old
+    /// `&mut self` design.
+    ///
+    /// A once-subscriber is removed from `subs` right before it is invoked rather than after the
+    /// whole loop - iterating over the owned `snapshot` instead of `subs` itself means that removal
+    /// is no longer a mid-iteration mutation, and removing it first means a once-subscriber that
+    /// reenters `publish` won't find itself still registered and try to invoke (i.e. borrow) itself
+    /// a second time while its first invocation is still on the stack.
+    fn deliver(&self, msg: &str) {
+        let started_at = Instant::now();
+        let mut snapshot: Vec<(SubscriptionId, Subscriber, bool, i32)> = self.subs.borrow().clone();
+        // `sort_by_key` is stable, so subscribers sharing a priority keep their insertion order.
+        snapshot.sort_by_key(|(_, _, _, priority)| *priority);
+        for (id, sub, once, _) in &snapshot {
+            if *once {
+                self.subs.borrow_mut().retain(|(sub_id, _, _, _)| sub_id != id);
+            }
+            sub.borrow_mut()(msg);
+        }
+
+        // Same snapshot-then-drop-the-borrow discipline as above: upgrade and invoke from an
+        // owned copy, then prune whatever failed to upgrade in a second, separate borrow.
+        let weak_snapshot: Vec<(SubscriptionId, WeakSubscriber)> = self.weak_subs.borrow().clone();
+        let mut dead = Vec::new();
+        for (id, weak) in &weak_snapshot {
+            match weak.upgrade() {
+                Some(sub) => sub.borrow_mut()(msg),
+                None => dead.push(*id),
+            }
+        }
+        if !dead.is_empty() {
+            self.weak_subs.borrow_mut().retain(|(id, _)| !dead.contains(id));
+        }
+
+        if self.replay_capacity > 0 {
+            let mut buffer = self.replay_buffer.borrow_mut();
+            if buffer.len() == self.replay_capacity {
+                buffer.pop_front();
+            }
+            buffer.push_back(msg.to_string());
+        } else {
+            *self.last_message.borrow_mut() = Some(msg.to_string());
+        }
+        self.total_published.set(self.total_published.get() + 1);
+
+        if let Some(metrics) = &self.metrics {
+            let invoked = snapshot.len() + (weak_snapshot.len() - dead.len());
+            metrics.on_publish(invoked, started_at.elapsed());
+        }
+    }
+}
+impl Default for Topic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// RAII handle returned by `Topic::subscribe_scoped`. Holds only a `Weak` reference to the topic,
+/// so dropping the topic first simply makes the guard's drop a no-op instead of a dangling access.
+pub struct SubscriptionGuard {
+    topic: Weak<Topic>,
+    id: SubscriptionId,
+}
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        if let Some(topic) = self.topic.upgrade() {
+            topic.unsubscribe(self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsubscribing_the_middle_subscriber_leaves_the_others_untouched_and_in_order() {
+        let topic = Topic::new();
+        let received: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+
+        let log = received.clone();
+        topic.subscribe(Rc::new(RefCell::new(move |msg: &str| log.borrow_mut().push(format!("first:{msg}")))));
+
+        let log = received.clone();
+        let middle_id = topic.subscribe(Rc::new(RefCell::new(move |msg: &str| log.borrow_mut().push(format!("middle:{msg}")))));
+
+        let log = received.clone();
+        topic.subscribe(Rc::new(RefCell::new(move |msg: &str| log.borrow_mut().push(format!("last:{msg}")))));
+
+        assert!(topic.unsubscribe(middle_id));
+        topic.publish("hi");
+
+        assert_eq!(*received.borrow(), vec!["first:hi".to_string(), "last:hi".to_string()]);
+    }
+
+    #[test]
+    fn subscriber_count_reflects_subscriptions_and_removals() {
+        let topic = Topic::new();
+        assert_eq!(topic.subscriber_count(), 0);
+
+        let id1 = topic.subscribe(Rc::new(RefCell::new(|_: &str| {})));
+        topic.subscribe(Rc::new(RefCell::new(|_: &str| {})));
+        assert_eq!(topic.subscriber_count(), 2);
+
+        topic.unsubscribe(id1);
+        assert_eq!(topic.subscriber_count(), 1);
+    }
+
+    #[test]
+    fn unsubscribing_an_unknown_id_returns_false_and_changes_nothing() {
+        let topic = Topic::new();
+        let id = topic.subscribe(Rc::new(RefCell::new(|_: &str| {})));
+        topic.unsubscribe(id);
+
+        assert!(!topic.unsubscribe(id));
+        assert_eq!(topic.subscriber_count(), 0);
+    }
+
+    #[test]
+    fn dropping_the_guard_before_the_topic_unsubscribes_the_callback() {
+        let topic = Rc::new(Topic::new());
+        let received: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+
+        let log = received.clone();
+        let guard = Topic::subscribe_scoped(&topic, Rc::new(RefCell::new(move |msg: &str| log.borrow_mut().push(msg.to_string()))));
+        assert_eq!(topic.subscriber_count(), 1);
+
+        drop(guard);
+        assert_eq!(topic.subscriber_count(), 0);
+
+        topic.publish("hi");
+        assert!(received.borrow().is_empty());
+    }
+
+    #[test]
+    fn dropping_the_topic_before_the_guard_does_not_panic() {
+        let topic = Rc::new(Topic::new());
+        let guard = Topic::subscribe_scoped(&topic, Rc::new(RefCell::new(|_: &str| {})));
+
+        drop(topic);
+        drop(guard); // must not panic trying to reach the now-gone topic
+    }
+
+    #[test]
+    fn forgetting_the_guard_keeps_the_subscription_alive() {
+        let topic = Rc::new(Topic::new());
+        let received: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+
+        let log = received.clone();
+        let guard = Topic::subscribe_scoped(&topic, Rc::new(RefCell::new(move |msg: &str| log.borrow_mut().push(msg.to_string()))));
+        std::mem::forget(guard);
+
+        topic.publish("hi");
+        assert_eq!(*received.borrow(), vec!["hi".to_string()]);
+    }
+
+    #[test]
+    fn subscribe_filtered_short_circuits_non_matching_messages() {
+        let topic = Topic::new();
+        let calls = Rc::new(RefCell::new(0u32));
+
+        let counter = calls.clone();
+        topic.subscribe_filtered(|msg: &str| msg.contains("Rust"), Rc::new(RefCell::new(move |_: &str| *counter.borrow_mut() += 1)));
+
+        topic.publish("no match here");
+        assert_eq!(*calls.borrow(), 0);
+
+        topic.publish("I love Rust");
+        assert_eq!(*calls.borrow(), 1);
+    }
+
+    #[test]
+    fn subscribe_filtered_does_not_affect_other_subscribers() {
+        let topic = Topic::new();
+        let filtered_calls = Rc::new(RefCell::new(0u32));
+        let unfiltered_calls = Rc::new(RefCell::new(0u32));
+
+        let counter = filtered_calls.clone();
+        topic.subscribe_filtered(|msg: &str| msg.contains("Rust"), Rc::new(RefCell::new(move |_: &str| *counter.borrow_mut() += 1)));
+
+        let counter = unfiltered_calls.clone();
+        topic.subscribe(Rc::new(RefCell::new(move |_: &str| *counter.borrow_mut() += 1)));
+
+        topic.publish("no match here");
+
+        assert_eq!(*filtered_calls.borrow(), 0);
+        assert_eq!(*unfiltered_calls.borrow(), 1);
+    }
+
+    #[test]
+    fn a_late_subscriber_is_replayed_only_the_last_capacity_messages() {
+        let topic = Topic::with_replay(3);
+        for i in 1..=5 {
+            topic.publish(&format!("msg{i}"));
+        }
+
+        let received: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+        let log = received.clone();
+        topic.subscribe(Rc::new(RefCell::new(move |msg: &str| log.borrow_mut().push(msg.to_string()))));
+
+        assert_eq!(*received.borrow(), vec!["msg3".to_string(), "msg4".to_string(), "msg5".to_string()]);
+    }
+
+    #[test]
+    fn replay_happens_exactly_once_per_subscriber() {
+        let topic = Topic::with_replay(10);
+        topic.publish("first");
+        topic.publish("second");
+
+        let calls = Rc::new(RefCell::new(0u32));
+        let counter = calls.clone();
+        topic.subscribe(Rc::new(RefCell::new(move |_: &str| *counter.borrow_mut() += 1)));
+
+        assert_eq!(*calls.borrow(), 2);
+
+        // A later, unrelated publish must not re-trigger replay for the already-subscribed callback.
+        topic.publish("third");
+        assert_eq!(*calls.borrow(), 3);
+    }
+
+    #[test]
+    fn a_once_subscriber_fires_exactly_once() {
+        let topic = Topic::new();
+        let calls = Rc::new(RefCell::new(0u32));
+
+        let counter = calls.clone();
+        topic.subscribe_once(Rc::new(RefCell::new(move |_: &str| *counter.borrow_mut() += 1)));
+
+        topic.publish("first");
+        assert_eq!(*calls.borrow(), 1);
+
+        topic.publish("second");
+        assert_eq!(*calls.borrow(), 1);
+    }
+
+    #[test]
+    fn a_once_subscriber_is_removed_from_subscriber_count_after_firing() {
+        let topic = Topic::new();
+        topic.subscribe_once(Rc::new(RefCell::new(|_: &str| {})));
+        assert_eq!(topic.subscriber_count(), 1);
+
+        topic.publish("hi");
+        assert_eq!(topic.subscriber_count(), 0);
+    }
+
+    #[test]
+    fn a_once_subscriber_that_republishes_does_not_panic() {
+        // Before `publish` took `&self` over an internal `RefCell`, a once-subscriber re-entering
+        // `publish` on the same externally-wrapped `Rc<RefCell<Topic>>` would panic with a double
+        // borrow. `publish` now snapshots and drops its `subs` borrow before invoking any
+        // callback, so a reentrant call like this one just works - no `try_borrow_mut` dance needed.
+        let topic = Rc::new(Topic::new());
+        let reentrant_calls = Rc::new(RefCell::new(0u32));
+
+        let inner_topic = topic.clone();
+        let counter = reentrant_calls.clone();
+        topic.subscribe_once(Rc::new(RefCell::new(move |_: &str| {
+            inner_topic.publish("reentrant");
+            *counter.borrow_mut() += 1;
+        })));
+
+        topic.publish("outer");
+
+        assert_eq!(*reentrant_calls.borrow(), 1);
+    }
+
+    #[test]
+    fn publishing_from_inside_a_subscriber_is_delivered_breadth_first_without_panicking() {
+        let topic = Rc::new(Topic::new());
+        let order: Rc<RefCell<Vec<&'static str>>> = Rc::new(RefCell::new(Vec::new()));
+
+        let inner_topic = topic.clone();
+        let log_a = order.clone();
+        topic.subscribe(Rc::new(RefCell::new(move |msg: &str| {
+            log_a.borrow_mut().push("A");
+            if msg == "original" {
+                inner_topic.publish("follow-up");
+            }
+        })));
+
+        let log_b = order.clone();
+        topic.subscribe(Rc::new(RefCell::new(move |_: &str| {
+            log_b.borrow_mut().push("B");
+        })));
+
+        topic.publish("original");
+
+        // Breadth-first: both subscribers see "original" before either sees "follow-up", which A
+        // only raised while reacting to "original" - a depth-first design would run A's "follow-up"
+        // recursively before B ever saw "original".
+        assert_eq!(*order.borrow(), vec!["A", "B", "A", "B"]);
+    }
+
+    #[test]
+    fn a_lower_priority_subscriber_runs_before_a_higher_priority_one_regardless_of_join_order() {
+        let topic = Topic::new();
+        let order: Rc<RefCell<Vec<&'static str>>> = Rc::new(RefCell::new(Vec::new()));
+
+        let normal_log = order.clone();
+        topic.subscribe(Rc::new(RefCell::new(move |_: &str| normal_log.borrow_mut().push("normal"))));
+        let audit_log = order.clone();
+        topic.subscribe_with_priority(-10, Rc::new(RefCell::new(move |_: &str| audit_log.borrow_mut().push("audit"))));
+
+        topic.publish("msg");
+
+        assert_eq!(*order.borrow(), vec!["audit", "normal"]);
+    }
+
+    #[test]
+    fn same_priority_subscribers_keep_insertion_order() {
+        let topic = Topic::new();
+        let order: Rc<RefCell<Vec<&'static str>>> = Rc::new(RefCell::new(Vec::new()));
+
+        let first_log = order.clone();
+        topic.subscribe_with_priority(5, Rc::new(RefCell::new(move |_: &str| first_log.borrow_mut().push("first"))));
+        let second_log = order.clone();
+        topic.subscribe_with_priority(5, Rc::new(RefCell::new(move |_: &str| second_log.borrow_mut().push("second"))));
+
+        topic.publish("msg");
+
+        assert_eq!(*order.borrow(), vec!["first", "second"]);
+    }
+
+    #[test]
+    fn a_weak_subscriber_is_invoked_while_its_strong_handle_is_alive() {
+        let topic = Topic::new();
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let log = received.clone();
+        let weak_sub: Subscriber = Rc::new(RefCell::new(move |msg: &str| log.borrow_mut().push(msg.to_string())));
+        topic.subscribe_weak(&weak_sub);
+
+        topic.publish("hello");
+
+        assert_eq!(*received.borrow(), vec!["hello".to_string()]);
+    }
+
+    #[test]
+    fn dropping_a_weak_subscribers_handle_prunes_it_on_the_next_publish() {
+        let topic = Topic::new();
+        let weak_sub: Subscriber = Rc::new(RefCell::new(|_: &str| {}));
+        topic.subscribe_weak(&weak_sub);
+        assert_eq!(topic.subscriber_count(), 1);
+
+        drop(weak_sub);
+        topic.publish("nobody should receive this");
+
+        assert_eq!(topic.subscriber_count(), 0);
+    }
+
+    #[test]
+    fn publish_all_hands_a_batch_subscriber_every_message_in_order_in_one_call() {
+        let topic = Topic::new();
+        let batches: Rc<RefCell<Vec<Vec<String>>>> = Rc::new(RefCell::new(Vec::new()));
+        let log = batches.clone();
+        topic.subscribe_batch(Rc::new(RefCell::new(move |batch: &[String]| log.borrow_mut().push(batch.to_vec()))));
+
+        topic.publish_all(["one", "two", "three"]);
+
+        assert_eq!(*batches.borrow(), vec![vec!["one".to_string(), "two".to_string(), "three".to_string()]]);
+    }
+
+    #[test]
+    fn publish_all_returns_per_message_deliveries_plus_one_per_batch_subscriber() {
+        let topic = Topic::new();
+        topic.subscribe(Rc::new(RefCell::new(|_: &str| {})));
+        topic.subscribe_batch(Rc::new(RefCell::new(|_: &[String]| {})));
+
+        // 2 messages * 1 plain subscriber = 2, plus 1 batch subscriber invoked once = 3.
+        let invocations = topic.publish_all(["a", "b"]);
+
+        assert_eq!(invocations, 3);
+    }
+
+    #[test]
+    fn batch_subscriber_is_invoked_exactly_once_per_publish_all_call_not_per_message() {
+        let topic = Topic::new();
+        let calls = Rc::new(RefCell::new(0u32));
+        let counter = calls.clone();
+        topic.subscribe_batch(Rc::new(RefCell::new(move |_: &[String]| *counter.borrow_mut() += 1)));
+
+        topic.publish_all(["one", "two", "three"]);
+        topic.publish_all(["four"]);
+        topic.publish("five"); // plain publish - must not trigger the batch subscriber at all
+
+        assert_eq!(*calls.borrow(), 2);
+    }
+
+    #[test]
+    fn counting_metrics_tallies_publishes_and_subscriber_invocations_not_timing() {
+        let metrics = Rc::new(CountingMetrics::new());
+        let topic = Topic::with_metrics(metrics.clone());
+        topic.subscribe(Rc::new(RefCell::new(|_: &str| {})));
+        topic.subscribe(Rc::new(RefCell::new(|_: &str| {})));
+
+        topic.publish("one");
+        topic.publish("two");
+        topic.publish("three");
+
+        assert_eq!(metrics.publishes(), 3);
+        assert_eq!(metrics.subscribers_invoked(), 6); // 2 subscribers * 3 publishes
+    }
+
+    #[test]
+    fn a_topic_without_metrics_installed_never_touches_the_default_no_op_hook() {
+        // No `with_metrics` collector here - `deliver` must not panic or do anything observable.
+        let topic = Topic::new();
+        topic.subscribe(Rc::new(RefCell::new(|_: &str| {})));
+        topic.publish("no collector installed");
+    }
+
+    #[test]
+    fn publish_isolated_evicts_a_panicking_subscriber_without_the_panic_reaching_the_caller() {
+        let topic = Topic::new();
+        let before = Rc::new(RefCell::new(Vec::new()));
+        let before_log = before.clone();
+        topic.subscribe(Rc::new(RefCell::new(move |msg: &str| before_log.borrow_mut().push(msg.to_string()))));
+        let panicking_id = topic.subscribe(Rc::new(RefCell::new(|msg: &str| panic!("always panics on {msg}"))));
+        let after = Rc::new(RefCell::new(Vec::new()));
+        let after_log = after.clone();
+        topic.subscribe(Rc::new(RefCell::new(move |msg: &str| after_log.borrow_mut().push(msg.to_string()))));
+
+        let report = topic.publish_isolated("first");
+
+        assert_eq!(report.delivered, 2);
+        assert_eq!(report.evicted, vec![panicking_id]);
+        assert_eq!(*before.borrow(), vec!["first".to_string()]);
+        assert_eq!(*after.borrow(), vec!["first".to_string()]);
+    }
+
+    #[test]
+    fn an_evicted_subscriber_does_not_run_on_the_next_publish_isolated_call() {
+        let topic = Topic::new();
+        let calls = Rc::new(RefCell::new(0u32));
+        let counter = calls.clone();
+        topic.subscribe(Rc::new(RefCell::new(move |_: &str| *counter.borrow_mut() += 1)));
+        topic.subscribe(Rc::new(RefCell::new(|msg: &str| panic!("always panics on {msg}"))));
+
+        let first = topic.publish_isolated("one");
+        let second = topic.publish_isolated("two");
+
+        assert_eq!(first.evicted.len(), 1);
+        assert!(second.evicted.is_empty());
+        assert_eq!(second.delivered, 1);
+        assert_eq!(*calls.borrow(), 2);
+    }
+
+    #[test]
+    fn debug_snapshot_reports_counters_and_the_last_message() {
+        let topic = Topic::new();
+        topic.subscribe(Rc::new(RefCell::new(|_: &str| {})));
+        topic.publish("first");
+        topic.publish("second");
+
+        let snapshot = topic.debug_snapshot();
+
+        assert_eq!(snapshot.subscriber_count, 1);
+        assert_eq!(snapshot.total_published, 2);
+        assert_eq!(snapshot.last_message, Some("second".to_string()));
+    }
+
+    #[test]
+    fn debug_snapshot_gives_unnamed_subscribers_a_generated_sub_n_label() {
+        let topic = Topic::new();
+        topic.subscribe_named("uppercaser", Rc::new(RefCell::new(|_: &str| {})));
+        topic.subscribe(Rc::new(RefCell::new(|_: &str| {})));
+
+        let snapshot = topic.debug_snapshot();
+
+        assert_eq!(snapshot.subscriber_labels, vec!["uppercaser".to_string(), "sub-1".to_string()]);
+    }
+}
@@ -0,0 +1,65 @@
+// cargo test --test bench_support
+
+// `benches/bench_support.rs` is pulled into every bench via `#[path]`, and Cargo always builds
+// bench targets with `--cfg test` (even under plain `cargo build`), so a `#[cfg(test)] mod tests`
+// living inside that file would get compiled - but never run - into each bench, producing dead
+// code warnings. Including it here instead, as its own integration test crate, tests it once
+// without that side effect.
+
+#[path = "../benches/bench_support.rs"]
+mod bench_support;
+
+use bench_support::{DEFAULT_PROFILE, TextProfile, generate_c_source, generate_text};
+
+#[test]
+fn generate_text_is_deterministic_for_the_same_seed() {
+    let a = generate_text(42, 5_000, DEFAULT_PROFILE);
+    let b = generate_text(42, 5_000, DEFAULT_PROFILE);
+    assert_eq!(a, b);
+}
+
+#[test]
+fn generate_text_differs_across_seeds() {
+    let a = generate_text(1, 5_000, DEFAULT_PROFILE);
+    let b = generate_text(2, 5_000, DEFAULT_PROFILE);
+    assert_ne!(a, b);
+}
+
+#[test]
+fn generate_text_respects_the_requested_byte_length() {
+    let text = generate_text(7, 12_345, DEFAULT_PROFILE);
+    assert_eq!(text.len(), 12_345);
+}
+
+#[test]
+fn generate_text_word_ratio_roughly_matches_the_profile() {
+    let profile = TextProfile { word_ratio: 0.7, number_ratio: 0.1, newline_every: 80 };
+    let text = generate_text(99, 200_000, profile);
+
+    let words = text.split_whitespace().filter(|tok| tok.chars().next().is_some_and(|c| c.is_alphabetic())).count();
+    let numbers = text.split_whitespace().filter(|tok| tok.chars().next().is_some_and(|c| c.is_numeric())).count();
+    let total = words + numbers;
+
+    // `generate_text` also emits lone spaces (the remaining `1 - word_ratio - number_ratio` of
+    // rolls) that `split_whitespace` swallows without producing a token, so the ratio to check
+    // against is words-vs-numbers among the tokens actually produced, not among all rolls.
+    let measured_word_ratio = words as f64 / total as f64;
+    let expected_word_ratio = profile.word_ratio / (profile.word_ratio + profile.number_ratio);
+    assert!(
+        (measured_word_ratio - expected_word_ratio).abs() < 0.05,
+        "measured word ratio was {measured_word_ratio}, expected ~{expected_word_ratio}"
+    );
+}
+
+#[test]
+fn generate_c_source_is_deterministic_for_the_same_seed() {
+    let a = generate_c_source(13, 5_000, 0.3);
+    let b = generate_c_source(13, 5_000, 0.3);
+    assert_eq!(a, b);
+}
+
+#[test]
+fn generate_c_source_contains_block_comments() {
+    let source = generate_c_source(13, 5_000, 0.3);
+    assert!(source.contains("/*") && source.contains("*/"));
+}